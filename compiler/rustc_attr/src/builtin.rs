@@ -6,7 +6,7 @@
 use rustc_errors::ErrorGuaranteed;
 use rustc_feature::{find_gated_cfg, is_builtin_attr_name, Features, GatedCfg};
 use rustc_macros::HashStable_Generic;
-use rustc_session::config::ExpectedValues;
+use rustc_session::config::{ExpectedValues, OptLevel};
 use rustc_session::lint::builtin::UNEXPECTED_CFGS;
 use rustc_session::lint::BuiltinLintDiag;
 use rustc_session::parse::feature_err;
@@ -53,6 +53,8 @@ pub enum OptimizeAttr {
     None,
     Speed,
     Size,
+    /// `#[optimize(level = "...")]`, which takes the same values as `-C opt-level`.
+    Level(OptLevel),
 }
 
 /// Represents the following attributes:
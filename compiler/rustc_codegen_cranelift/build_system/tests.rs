@@ -99,6 +99,7 @@ const fn jit_bin(config: &'static str, source: &'static str, args: &'static str)
         runner.run_out_command("polymorphize_coroutine", &[]);
     }),
     TestCase::build_bin_and_run("aot.neon", "example/neon.rs", &[]),
+    TestCase::build_bin_and_run("aot.x86_intrinsics", "example/x86_intrinsics.rs", &[]),
     TestCase::custom("aot.gen_block_iterate", &|runner| {
         runner.run_rustc([
             "example/gen_block_iterate.rs",
@@ -108,6 +109,45 @@ const fn jit_bin(config: &'static str, source: &'static str, args: &'static str)
         ]);
         runner.run_out_command("gen_block_iterate", &[]);
     }),
+    TestCase::custom("aot.debuginfo", &|runner| {
+        if !runner.is_native || Command::new("gdb").arg("--version").output().is_err() {
+            eprintln!("[SKIP] debuginfo: needs a native target and gdb");
+            return;
+        }
+
+        // Without MIR optimizations all variables are stored in locals of their own. With them,
+        // `limit` is replaced by a constant and `pair` is split into one local per field.
+        for (name, mir_opt_level) in [("debuginfo", "0"), ("debuginfo_mir_opt", "2")] {
+            runner.run_rustc([
+                "example/debuginfo.rs",
+                "--edition",
+                "2021",
+                "--crate-name",
+                name,
+                &format!("-Zmir-opt-level={mir_opt_level}"),
+            ]);
+            runner.check_gdb_output(
+                name,
+                "example/debuginfo.gdb",
+                &[
+                    "point = 0x",
+                    "shape = 0x",
+                    "tuple = (7, -8)",
+                    "{10, 20, 30}",
+                    "option = core::option::Option<&",
+                    "::Some(0x",
+                    "limit = 42",
+                    "{x: 3, y: 4}",
+                    "Unresumed",
+                    "inner = 3",
+                    "{x: 1, y: 2}",
+                    "radius: 5",
+                ],
+                // `inner` is only in scope at the first call to `zzz`.
+                &["---second---", "inner ="],
+            );
+        }
+    }),
 ];
 
 pub(crate) static RAND_REPO: GitRepo = GitRepo::github(
@@ -487,6 +527,38 @@ fn run_rustc<I, S>(&self, args: I)
         spawn_and_wait(self.rustc_command(args));
     }
 
+    /// Runs the given example under gdb with the commands in `script` and checks that the output
+    /// contains all of `expected`. The second string of `unexpected_after` must not occur after the
+    /// first one.
+    fn check_gdb_output(
+        &self,
+        name: &str,
+        script: &str,
+        expected: &[&str],
+        unexpected_after: &[&str; 2],
+    ) {
+        let mut cmd = Command::new("gdb");
+        cmd.args(["-batch", "-nx", "-x", script]);
+        cmd.arg(BUILD_EXAMPLE_OUT_DIR.to_path(&self.dirs).join(name));
+        let output = cmd.output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let [marker, unexpected] = unexpected_after;
+        let after_marker = stdout.split_once(marker).map(|(_, after)| after);
+        let missing: Vec<_> = expected.iter().filter(|line| !stdout.contains(*line)).collect();
+        if !missing.is_empty() || after_marker.map_or(true, |after| after.contains(unexpected)) {
+            eprintln!("{stdout}");
+            eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+            eprintln!("Missing from the gdb output: {missing:?}");
+            if after_marker.is_none() {
+                eprintln!("Missing from the gdb output: {marker:?}");
+            } else {
+                eprintln!("Unexpected after {marker:?}: {unexpected:?}");
+            }
+            std::process::exit(1);
+        }
+    }
+
     fn run_out_command(&self, name: &str, args: &[&str]) {
         let mut full_cmd = vec![];
 
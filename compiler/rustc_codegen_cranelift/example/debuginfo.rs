@@ -0,0 +1,48 @@
+// Run under gdb with example/debuginfo.gdb by the `aot.debuginfo` test, which checks the values
+// printed for the variables at the two calls to `zzz`.
+
+use std::hint::black_box;
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+enum Shape {
+    Circle {
+        center: Point,
+        radius: u32,
+    },
+    #[allow(dead_code)]
+    Empty,
+}
+
+const LIMIT: u32 = 42;
+
+#[inline(never)]
+fn zzz() {
+    black_box(());
+}
+
+#[inline(never)]
+fn check(point: &Point, shape: &Shape, tuple: (u8, i64), slice: &[u16], option: Option<&Point>) {
+    let limit = LIMIT;
+    // Only used through its fields, so that it gets split into several locals with MIR
+    // optimizations enabled.
+    let pair = Point { x: 3, y: 4 };
+    let future = async { black_box(LIMIT) };
+    {
+        let inner = point.x + point.y;
+        zzz();
+        black_box(inner);
+    }
+    zzz();
+    black_box((limit, pair.x, pair.y, &future));
+    black_box((point, shape, tuple, slice, option));
+}
+
+fn main() {
+    let point = Point { x: 1, y: 2 };
+    let shape = Shape::Circle { center: Point { x: 1, y: 2 }, radius: 5 };
+    check(&point, &shape, (7, -8), &[10, 20, 30], Some(&point));
+}
@@ -0,0 +1,626 @@
+// Tests for the `llvm.x86.*` intrinsics implemented in `src/intrinsics/llvm_x86.rs` and the
+// `simd_*` intrinsics used by `core::arch` and `std::simd`. There is one function per target
+// feature, which is skipped if the CPU doesn't support it. The expected values are those of the
+// hardware instructions, so this test is also run with the LLVM backend on CI.
+
+#![feature(portable_simd)]
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+#[cfg(target_arch = "x86_64")]
+use std::mem::transmute;
+use std::simd::prelude::*;
+
+fn main() {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        test_sse();
+        test_sse2();
+        if is_x86_feature_detected!("sse3") {
+            test_sse3();
+        }
+        if is_x86_feature_detected!("ssse3") {
+            test_ssse3();
+        }
+        if is_x86_feature_detected!("sse4.1") {
+            test_sse41();
+        }
+        if is_x86_feature_detected!("sse4.2") {
+            test_sse42();
+        }
+        if is_x86_feature_detected!("avx") {
+            test_avx();
+        }
+        if is_x86_feature_detected!("avx2") {
+            test_avx2();
+        }
+        if is_x86_feature_detected!("fma") {
+            test_fma();
+        }
+    }
+
+    test_simd_intrinsics();
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m128(a: __m128) -> [f32; 4] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m128_bits(a: __m128) -> [u32; 4] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m128d(a: __m128d) -> [f64; 2] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m128d_bits(a: __m128d) -> [u64; 2] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m128i_i16(a: __m128i) -> [i16; 8] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m128i_i32(a: __m128i) -> [i32; 4] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m128i_i64(a: __m128i) -> [i64; 2] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m256(a: __m256) -> [f32; 8] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m256_bits(a: __m256) -> [u32; 8] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m256d(a: __m256d) -> [f64; 4] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m256d_bits(a: __m256d) -> [u64; 4] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m256i_i8(a: __m256i) -> [i8; 32] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m256i_i16(a: __m256i) -> [i16; 16] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m256i_i32(a: __m256i) -> [i32; 8] {
+    unsafe { transmute(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn m256i_i64(a: __m256i) -> [i64; 4] {
+    unsafe { transmute(a) }
+}
+
+/// `rcpps` and `rsqrtps` only guarantee a relative error of at most 1.5 * 2^-12.
+#[cfg(target_arch = "x86_64")]
+fn assert_approx_eq(actual: &[f32], expected: &[f32]) {
+    for (actual, expected) in actual.iter().zip(expected) {
+        assert!(((actual - expected) / expected).abs() <= 1.5 / 4096.0, "{actual} != {expected}");
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse")]
+unsafe fn test_sse() {
+    let a = _mm_setr_ps(1.0, 2.0, 3.0, f32::NAN);
+    let b = _mm_set1_ps(2.0);
+    assert_eq!(m128_bits(_mm_cmplt_ps(a, b)), [!0, 0, 0, 0]);
+    assert_eq!(m128_bits(_mm_cmpge_ps(a, b)), [0, !0, !0, 0]);
+    assert_eq!(m128_bits(_mm_cmpunord_ps(a, b)), [0, 0, 0, !0]);
+    assert_eq!(m128_bits(_mm_cmpneq_ps(a, b)), [!0, 0, !0, !0]);
+
+    let a = _mm_setr_ps(1.0, 5.0, 6.0, 7.0);
+    let b = _mm_setr_ps(3.0, 0.0, 0.0, 0.0);
+    assert_eq!(m128(_mm_max_ss(a, b)), [3.0, 5.0, 6.0, 7.0]);
+    assert_eq!(m128(_mm_min_ss(a, b)), [1.0, 5.0, 6.0, 7.0]);
+
+    let one = _mm_set1_ps(1.0);
+    let two = _mm_set1_ps(2.0);
+    assert_eq!(_mm_comieq_ss(one, one), 1);
+    assert_eq!(_mm_comilt_ss(one, two), 1);
+    assert_eq!(_mm_comile_ss(one, one), 1);
+    assert_eq!(_mm_comigt_ss(one, two), 0);
+    assert_eq!(_mm_comige_ss(two, one), 1);
+    assert_eq!(_mm_comineq_ss(one, two), 1);
+    assert_eq!(_mm_ucomieq_ss(one, two), 0);
+    assert_eq!(_mm_ucomilt_ss(two, one), 0);
+    assert_eq!(_mm_ucomile_ss(one, two), 1);
+    assert_eq!(_mm_ucomigt_ss(two, one), 1);
+    assert_eq!(_mm_ucomige_ss(one, one), 1);
+    assert_eq!(_mm_ucomineq_ss(one, one), 0);
+
+    assert_approx_eq(&m128(_mm_rcp_ps(_mm_setr_ps(1.0, 2.0, 4.0, 0.5))), &[1.0, 0.5, 0.25, 2.0]);
+    assert_approx_eq(
+        &m128(_mm_rsqrt_ps(_mm_setr_ps(1.0, 4.0, 16.0, 0.25))),
+        &[1.0, 0.5, 0.25, 2.0],
+    );
+
+    _mm_sfence();
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn test_sse2() {
+    let a = _mm_setr_epi16(1, 2, 3, 4, -1, -2, -3, -4);
+    assert_eq!(
+        m128i_i16(_mm_sll_epi16(a, _mm_set_epi64x(0, 4))),
+        [16, 32, 48, 64, -16, -32, -48, -64]
+    );
+    // Counts larger than the lane width shift out all bits.
+    assert_eq!(m128i_i16(_mm_sll_epi16(a, _mm_set_epi64x(0, 16))), [0; 8]);
+    // Only the low 64 bits of the count are used.
+    assert_eq!(m128i_i16(_mm_sll_epi16(a, _mm_set_epi64x(1, 0))), m128i_i16(a));
+
+    let a = _mm_setr_epi32(-1, 16, 0, 1);
+    assert_eq!(m128i_i32(_mm_srl_epi32(a, _mm_set_epi64x(0, 4))), [0x0fffffff, 1, 0, 0]);
+    assert_eq!(m128i_i32(_mm_sll_epi32(a, _mm_set_epi64x(0, 1))), [-2, 32, 0, 2]);
+    assert_eq!(m128i_i64(_mm_sll_epi64(_mm_set1_epi64x(3), _mm_set_epi64x(0, 2))), [12, 12]);
+    assert_eq!(m128i_i64(_mm_srl_epi64(_mm_set1_epi64x(-1), _mm_set_epi64x(0, 64))), [0, 0]);
+    assert_eq!(m128i_i16(_mm_srl_epi16(_mm_set1_epi16(-2), _mm_set_epi64x(0, 1))), [32767; 8]);
+
+    let a = _mm_setr_epi16(-32768, 32767, -1, 1, 0, 0, 0, 0);
+    // Arithmetic shifts by more than the lane width fill the lane with the sign bit.
+    assert_eq!(m128i_i16(_mm_sra_epi16(a, _mm_set_epi64x(0, 20))), [-1, 0, -1, 0, 0, 0, 0, 0]);
+    assert_eq!(m128i_i32(_mm_sra_epi32(_mm_set1_epi32(-64), _mm_set_epi64x(0, 3))), [-8; 4]);
+
+    let a = _mm_setr_epi32(-16, 16, i32::MIN, i32::MAX);
+    assert_eq!(m128i_i32(_mm_srai_epi32::<2>(a)), [-4, 4, i32::MIN >> 2, i32::MAX >> 2]);
+    assert_eq!(m128i_i32(_mm_srai_epi32::<40>(a)), [-1, 0, -1, 0]);
+    assert_eq!(m128i_i16(_mm_srai_epi16::<15>(_mm_set1_epi16(-5))), [-1; 8]);
+    assert_eq!(m128i_i16(_mm_slli_epi16::<3>(_mm_set1_epi16(1))), [8; 8]);
+    assert_eq!(m128i_i32(_mm_slli_epi32::<32>(_mm_set1_epi32(1))), [0; 4]);
+    assert_eq!(m128i_i64(_mm_slli_epi64::<4>(_mm_set_epi64x(0, 1))), [16, 0]);
+    assert_eq!(m128i_i16(_mm_srli_epi16::<1>(_mm_set1_epi16(-2))), [32767; 8]);
+    assert_eq!(m128i_i32(_mm_srli_epi32::<28>(_mm_set1_epi32(-1))), [15; 4]);
+    assert_eq!(m128i_i64(_mm_srli_epi64::<63>(_mm_set1_epi64x(-1))), [1, 1]);
+
+    let a = _mm_setr_pd(1.0, 2.0);
+    let b = _mm_setr_pd(1.0, 3.0);
+    assert_eq!(m128d_bits(_mm_cmpneq_pd(a, b)), [0, !0]);
+    assert_eq!(m128d_bits(_mm_cmple_pd(a, b)), [!0, !0]);
+    assert_eq!(m128d_bits(_mm_cmpnlt_pd(a, b)), [!0, 0]);
+
+    let a = _mm_setr_pd(1.0, 5.0);
+    let b = _mm_setr_pd(3.0, 0.0);
+    assert_eq!(m128d(_mm_max_sd(a, b)), [3.0, 5.0]);
+    assert_eq!(m128d(_mm_min_sd(a, b)), [1.0, 5.0]);
+
+    let one = _mm_set1_pd(1.0);
+    let two = _mm_set1_pd(2.0);
+    assert_eq!(_mm_comieq_sd(one, one), 1);
+    assert_eq!(_mm_comilt_sd(two, one), 0);
+    assert_eq!(_mm_comile_sd(one, two), 1);
+    assert_eq!(_mm_comigt_sd(two, one), 1);
+    assert_eq!(_mm_comige_sd(one, two), 0);
+    assert_eq!(_mm_comineq_sd(one, one), 0);
+    assert_eq!(_mm_ucomieq_sd(two, two), 1);
+    assert_eq!(_mm_ucomilt_sd(one, two), 1);
+    assert_eq!(_mm_ucomile_sd(two, one), 0);
+    assert_eq!(_mm_ucomigt_sd(one, one), 0);
+    assert_eq!(_mm_ucomige_sd(two, two), 1);
+    assert_eq!(_mm_ucomineq_sd(one, two), 1);
+
+    let a = _mm_setr_epi16(1000, -1000, 30000, -1, 0, 0, 0, 0);
+    let b = _mm_setr_epi16(1000, 1000, 30000, 2, 0, 0, 0, 0);
+    assert_eq!(m128i_i16(_mm_mulhi_epi16(a, b)), [15, -16, 13732, -1, 0, 0, 0, 0]);
+    assert_eq!(m128i_i16(_mm_mulhi_epu16(_mm_set1_epi16(-1), _mm_set1_epi16(2))), [1; 8]);
+
+    _mm_lfence();
+    _mm_mfence();
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse3")]
+unsafe fn test_sse3() {
+    let a = _mm_setr_ps(1.0, 2.0, 3.0, 4.0);
+    let b = _mm_setr_ps(5.0, 6.0, 7.0, 8.0);
+    assert_eq!(m128(_mm_hadd_ps(a, b)), [3.0, 7.0, 11.0, 15.0]);
+    assert_eq!(m128(_mm_hsub_ps(a, b)), [-1.0; 4]);
+    assert_eq!(m128(_mm_addsub_ps(a, _mm_set1_ps(10.0))), [-9.0, 12.0, -7.0, 14.0]);
+
+    let a = _mm_setr_pd(1.0, 2.0);
+    let b = _mm_setr_pd(3.0, 5.0);
+    assert_eq!(m128d(_mm_hadd_pd(a, b)), [3.0, 8.0]);
+    assert_eq!(m128d(_mm_hsub_pd(a, b)), [-1.0, -2.0]);
+    assert_eq!(m128d(_mm_addsub_pd(a, _mm_set1_pd(10.0))), [-9.0, 12.0]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn test_ssse3() {
+    let a: [i8; 16] = transmute(_mm_sign_epi8(
+        _mm_set1_epi8(5),
+        _mm_setr_epi8(-1, 0, 1, -128, 127, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    ));
+    assert_eq!(a, [-5, 0, 5, -5, 5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    let a = _mm_setr_epi16(1, 2, 3, 4, 5, 6, 7, 8);
+    let b = _mm_setr_epi16(-1, 0, 1, -5, 0, 0, 1, 1);
+    assert_eq!(m128i_i16(_mm_sign_epi16(a, b)), [-1, 0, 3, -4, 0, 0, 7, 8]);
+    let a = _mm_setr_epi32(1, 2, 3, 4);
+    let b = _mm_setr_epi32(0, -1, 1, i32::MIN);
+    assert_eq!(m128i_i32(_mm_sign_epi32(a, b)), [0, -2, 3, -4]);
+
+    let a = _mm_setr_epi16(1, 2, 3, 4, 5, 6, 7, 8);
+    let b = _mm_setr_epi16(9, 10, 11, 12, 13, 14, 15, 16);
+    assert_eq!(m128i_i16(_mm_hadd_epi16(a, b)), [3, 7, 11, 15, 19, 23, 27, 31]);
+    assert_eq!(m128i_i16(_mm_hsub_epi16(a, b)), [-1; 8]);
+    let a = _mm_setr_epi32(1, 2, 3, 4);
+    let b = _mm_setr_epi32(10, 5, 8, 9);
+    assert_eq!(m128i_i32(_mm_hadd_epi32(a, b)), [3, 7, 15, 17]);
+    assert_eq!(m128i_i32(_mm_hsub_epi32(a, b)), [-1, -1, 5, -1]);
+
+    // The saturating variants clamp instead of wrapping.
+    let a = _mm_setr_epi16(32767, 1, -32768, -1, 0, 0, 0, 0);
+    let zero = _mm_setzero_si128();
+    assert_eq!(m128i_i16(_mm_hadds_epi16(a, zero)), [32767, -32768, 0, 0, 0, 0, 0, 0]);
+    let a = _mm_setr_epi16(-32768, 1, 32767, -1, 0, 0, 0, 0);
+    assert_eq!(m128i_i16(_mm_hsubs_epi16(a, zero)), [-32768, 32767, 0, 0, 0, 0, 0, 0]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn test_sse41() {
+    assert_eq!(_mm_testz_si128(_mm_set1_epi8(1), _mm_set1_epi8(2)), 1);
+    assert_eq!(_mm_testz_si128(_mm_set1_epi8(1), _mm_set1_epi8(3)), 0);
+    assert_eq!(_mm_testc_si128(_mm_set1_epi8(3), _mm_set1_epi8(1)), 1);
+    assert_eq!(_mm_testc_si128(_mm_set1_epi8(1), _mm_set1_epi8(3)), 0);
+    assert_eq!(_mm_testnzc_si128(_mm_set1_epi8(1), _mm_set1_epi8(3)), 1);
+    assert_eq!(_mm_testnzc_si128(_mm_set1_epi8(3), _mm_set1_epi8(1)), 0);
+
+    let a = _mm_set1_epi8(1);
+    let b = _mm_set1_epi8(2);
+    #[rustfmt::skip]
+    let mask = _mm_setr_epi8(
+        0, -1, 0x7f, -128, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, -1,
+    );
+    let r: [i8; 16] = transmute(_mm_blendv_epi8(a, b, mask));
+    assert_eq!(r, [1, 2, 1, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2]);
+    let mask = _mm_setr_ps(0.0, -0.0, 1.0, -1.0);
+    assert_eq!(m128(_mm_blendv_ps(_mm_set1_ps(1.0), _mm_set1_ps(2.0), mask)), [1.0, 2.0, 1.0, 2.0]);
+    let mask = _mm_setr_pd(-1.0, 1.0);
+    assert_eq!(m128d(_mm_blendv_pd(_mm_set1_pd(1.0), _mm_set1_pd(2.0), mask)), [2.0, 1.0]);
+
+    let a = _mm_setr_ps(1.5, 2.5, -1.5, 0.4);
+    assert_eq!(
+        m128(_mm_round_ps::<{ _MM_FROUND_TO_NEAREST_INT | _MM_FROUND_NO_EXC }>(a)),
+        [2.0, 2.0, -2.0, 0.0]
+    );
+    assert_eq!(m128(_mm_ceil_ps(a)), [2.0, 3.0, -1.0, 1.0]);
+    assert_eq!(
+        m128(_mm_round_ps::<{ _MM_FROUND_TO_ZERO | _MM_FROUND_NO_EXC }>(a)),
+        [1.0, 2.0, -1.0, 0.0]
+    );
+    assert_eq!(m128d(_mm_floor_pd(_mm_setr_pd(1.7, -1.2))), [1.0, -2.0]);
+
+    let a = _mm_setr_epi16(23, 18, 44, 97, 50, 13, 67, 66);
+    assert_eq!(m128i_i16(_mm_minpos_epu16(a)), [13, 5, 0, 0, 0, 0, 0, 0]);
+    // The first of several equal minima is returned.
+    let a = _mm_setr_epi16(-1, 7, 3, 7, 3, -2, -3, 9);
+    assert_eq!(m128i_i16(_mm_minpos_epu16(a)), [3, 2, 0, 0, 0, 0, 0, 0]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.2")]
+unsafe fn test_sse42() {
+    assert_eq!(_mm_crc32_u8(0x2aa1e72b, 0x2a), 0xf24122e4);
+    assert_eq!(_mm_crc32_u8(1, 100), 1412925310);
+    assert_eq!(_mm_crc32_u16(0xffffffff, 0x1234), 4047457514);
+    assert_eq!(_mm_crc32_u32(0, 0xdeadbeef), 161029396);
+    assert_eq!(_mm_crc32_u64(0xffffffff, 0x0123456789abcdef), 2588878812);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn test_avx() {
+    let a = _mm256_setr_ps(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0);
+    let b = _mm256_set1_ps(4.0);
+    assert_eq!(m256_bits(_mm256_cmp_ps::<_CMP_GE_OQ>(a, b)), [0, 0, 0, !0, !0, !0, !0, !0]);
+    assert_eq!(m256_bits(_mm256_cmp_ps::<_CMP_EQ_OQ>(a, b)), [0, 0, 0, !0, 0, 0, 0, 0]);
+    let a = _mm256_setr_pd(1.0, f64::NAN, 3.0, 4.0);
+    let b = _mm256_set1_pd(3.0);
+    assert_eq!(m256d_bits(_mm256_cmp_pd::<_CMP_LT_OS>(a, b)), [!0, 0, 0, 0]);
+    assert_eq!(m256d_bits(_mm256_cmp_pd::<_CMP_NEQ_UQ>(a, b)), [!0, !0, 0, !0]);
+    // FALSE and TRUE ignore the operands, even NaN.
+    let nan = _mm256_setr_ps(1.0, f32::NAN, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0);
+    assert_eq!(m256_bits(_mm256_cmp_ps::<_CMP_FALSE_OQ>(nan, nan)), [0; 8]);
+    assert_eq!(m256_bits(_mm256_cmp_ps::<_CMP_TRUE_UQ>(nan, nan)), [!0; 8]);
+    assert_eq!(m256d_bits(_mm256_cmp_pd::<_CMP_FALSE_OS>(a, b)), [0; 4]);
+    assert_eq!(m256d_bits(_mm256_cmp_pd::<_CMP_TRUE_US>(a, b)), [!0; 4]);
+    assert_eq!(m128_bits(_mm_cmp_ps::<_CMP_LE_OS>(_mm_set1_ps(1.0), _mm_set1_ps(1.0))), [!0; 4]);
+
+    assert_eq!(_mm256_testz_si256(_mm256_set1_epi64x(1), _mm256_set1_epi64x(2)), 1);
+    assert_eq!(_mm256_testc_si256(_mm256_set1_epi64x(3), _mm256_set1_epi64x(1)), 1);
+    assert_eq!(_mm256_testnzc_si256(_mm256_set1_epi64x(1), _mm256_set1_epi64x(3)), 1);
+    assert_eq!(_mm256_testnzc_si256(_mm256_set1_epi64x(3), _mm256_set1_epi64x(1)), 0);
+
+    let a = _mm256_setr_ps(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0);
+    let b = _mm256_setr_ps(9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0);
+    // Horizontal operations work on each 128-bit half separately.
+    assert_eq!(m256(_mm256_hadd_ps(a, b)), [3.0, 7.0, 19.0, 23.0, 11.0, 15.0, 27.0, 31.0]);
+    assert_eq!(m256(_mm256_hsub_ps(a, b)), [-1.0; 8]);
+    let a = _mm256_setr_pd(1.0, 2.0, 3.0, 4.0);
+    let b = _mm256_setr_pd(10.0, 20.0, 30.0, 40.0);
+    assert_eq!(m256d(_mm256_hadd_pd(a, b)), [3.0, 30.0, 7.0, 70.0]);
+    assert_eq!(m256d(_mm256_hsub_pd(a, b)), [-1.0, -10.0, -1.0, -10.0]);
+
+    let a = _mm256_setr_ps(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0);
+    assert_eq!(
+        m256(_mm256_addsub_ps(a, _mm256_set1_ps(10.0))),
+        [-9.0, 12.0, -7.0, 14.0, -5.0, 16.0, -3.0, 18.0]
+    );
+    let a = _mm256_setr_pd(1.0, 2.0, 3.0, 4.0);
+    assert_eq!(m256d(_mm256_addsub_pd(a, _mm256_set1_pd(10.0))), [-9.0, 12.0, -7.0, 14.0]);
+
+    let mask = _mm256_setr_ps(-1.0, 1.0, -0.0, 0.0, 1.0, 1.0, 1.0, -2.0);
+    assert_eq!(
+        m256(_mm256_blendv_ps(_mm256_set1_ps(1.0), _mm256_set1_ps(2.0), mask)),
+        [2.0, 1.0, 2.0, 1.0, 1.0, 1.0, 1.0, 2.0]
+    );
+    let mask = _mm256_setr_pd(1.0, -1.0, -1.0, 1.0);
+    assert_eq!(
+        m256d(_mm256_blendv_pd(_mm256_set1_pd(1.0), _mm256_set1_pd(2.0), mask)),
+        [1.0, 2.0, 2.0, 1.0]
+    );
+
+    let a = _mm256_setr_ps(1.5, 2.5, -1.5, 0.4, -0.6, 3.0, -3.5, 100.5);
+    assert_eq!(m256(_mm256_floor_ps(a)), [1.0, 2.0, -2.0, 0.0, -1.0, 3.0, -4.0, 100.0]);
+    assert_eq!(
+        m256(_mm256_round_ps::<{ _MM_FROUND_TO_NEAREST_INT | _MM_FROUND_NO_EXC }>(a)),
+        [2.0, 2.0, -2.0, 0.0, -1.0, 3.0, -4.0, 100.0]
+    );
+    assert_eq!(m256d(_mm256_ceil_pd(_mm256_setr_pd(1.2, -1.2, 2.0, -0.5))), [2.0, -1.0, 2.0, -0.0]);
+
+    let a = _mm_setr_ps(1.0, 2.0, 3.0, 4.0);
+    assert_eq!(m128(_mm_permutevar_ps(a, _mm_setr_epi32(3, 2, 1, 0))), [4.0, 3.0, 2.0, 1.0]);
+    // Only the low two bits of each control element are used.
+    assert_eq!(m128(_mm_permutevar_ps(a, _mm_setr_epi32(4, 5, 6, 7))), [1.0, 2.0, 3.0, 4.0]);
+    let a = _mm256_setr_ps(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0);
+    let b = _mm256_setr_epi32(3, 2, 1, 0, 0, 0, 0, 0);
+    assert_eq!(m256(_mm256_permutevar_ps(a, b)), [4.0, 3.0, 2.0, 1.0, 5.0, 5.0, 5.0, 5.0]);
+    // Bit 1 of each control element selects the element.
+    let a = _mm_setr_pd(1.0, 2.0);
+    assert_eq!(m128d(_mm_permutevar_pd(a, _mm_set_epi64x(0, 2))), [2.0, 1.0]);
+    let a = _mm256_setr_pd(1.0, 2.0, 3.0, 4.0);
+    let b = _mm256_setr_epi64x(2, 0, 0, 2);
+    assert_eq!(m256d(_mm256_permutevar_pd(a, b)), [2.0, 1.0, 3.0, 4.0]);
+
+    let a = _mm256_setr_ps(1.0, 2.0, 4.0, 0.5, 8.0, 0.25, 1.0, 2.0);
+    assert_approx_eq(&m256(_mm256_rcp_ps(a)), &[1.0, 0.5, 0.25, 2.0, 0.125, 4.0, 1.0, 0.5]);
+    let a = _mm256_setr_ps(1.0, 4.0, 16.0, 0.25, 64.0, 1.0, 4.0, 16.0);
+    assert_approx_eq(&m256(_mm256_rsqrt_ps(a)), &[1.0, 0.5, 0.25, 2.0, 0.125, 1.0, 0.5, 0.25]);
+
+    let a = _mm256_setr_ps(-1.0, 1.0, -1.0, 1.0, 1.0, 1.0, 1.0, -1.0);
+    assert_eq!(_mm256_movemask_ps(a), 0b1000_0101);
+    assert_eq!(_mm256_movemask_pd(_mm256_setr_pd(-1.0, 1.0, 1.0, -1.0)), 0b1001);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn test_avx2() {
+    let a = _mm256_setr_epi16(1, 2, 3, 4, -1, -2, -3, -4, 1, 2, 3, 4, -1, -2, -3, -4);
+    assert_eq!(
+        m256i_i16(_mm256_sll_epi16(a, _mm_set_epi64x(0, 4))),
+        [16, 32, 48, 64, -16, -32, -48, -64, 16, 32, 48, 64, -16, -32, -48, -64]
+    );
+    assert_eq!(m256i_i16(_mm256_srl_epi16(a, _mm_set_epi64x(0, 16))), [0; 16]);
+    assert_eq!(m256i_i16(_mm256_sra_epi16(a, _mm_set_epi64x(0, 30)))[4..8], [-1; 4]);
+    let a = _mm256_set1_epi32(-16);
+    assert_eq!(m256i_i32(_mm256_sll_epi32(a, _mm_set_epi64x(0, 1))), [-32; 8]);
+    assert_eq!(m256i_i32(_mm256_srl_epi32(a, _mm_set_epi64x(0, 28))), [15; 8]);
+    assert_eq!(m256i_i32(_mm256_sra_epi32(a, _mm_set_epi64x(0, 2))), [-4; 8]);
+    let a = _mm256_set1_epi64x(1);
+    assert_eq!(m256i_i64(_mm256_sll_epi64(a, _mm_set_epi64x(0, 63))), [i64::MIN; 4]);
+    assert_eq!(m256i_i64(_mm256_srl_epi64(a, _mm_set_epi64x(0, 1))), [0; 4]);
+
+    assert_eq!(m256i_i16(_mm256_slli_epi16::<2>(_mm256_set1_epi16(3))), [12; 16]);
+    assert_eq!(m256i_i16(_mm256_srli_epi16::<8>(_mm256_set1_epi16(-1))), [255; 16]);
+    assert_eq!(m256i_i16(_mm256_srai_epi16::<20>(_mm256_set1_epi16(-1))), [-1; 16]);
+    assert_eq!(m256i_i32(_mm256_slli_epi32::<4>(_mm256_set1_epi32(1))), [16; 8]);
+    assert_eq!(m256i_i32(_mm256_srli_epi32::<31>(_mm256_set1_epi32(-1))), [1; 8]);
+    assert_eq!(m256i_i32(_mm256_srai_epi32::<1>(_mm256_set1_epi32(-3))), [-2; 8]);
+    assert_eq!(m256i_i64(_mm256_slli_epi64::<64>(_mm256_set1_epi64x(1))), [0; 4]);
+    assert_eq!(m256i_i64(_mm256_srli_epi64::<32>(_mm256_set1_epi64x(-1))), [0xffffffff; 4]);
+
+    // Variable shifts shift every element by its own count.
+    let a = _mm_set1_epi32(1);
+    assert_eq!(m128i_i32(_mm_sllv_epi32(a, _mm_setr_epi32(0, 1, 31, 32))), [1, 2, i32::MIN, 0]);
+    let a = _mm256_set1_epi32(1);
+    let count = _mm256_setr_epi32(0, 1, 2, 3, 4, 5, 31, 33);
+    assert_eq!(m256i_i32(_mm256_sllv_epi32(a, count)), [1, 2, 4, 8, 16, 32, i32::MIN, 0]);
+    let a = _mm_set1_epi32(-1);
+    assert_eq!(m128i_i32(_mm_srlv_epi32(a, _mm_setr_epi32(0, 4, 31, 40))), [-1, 0x0fffffff, 1, 0]);
+    let a = _mm256_set1_epi32(i32::MIN);
+    let count = _mm256_setr_epi32(0, 1, 2, 3, 28, 30, 31, 32);
+    assert_eq!(
+        m256i_i32(_mm256_srlv_epi32(a, count)),
+        [i32::MIN, 1 << 30, 1 << 29, 1 << 28, 8, 2, 1, 0]
+    );
+    let a = _mm_set1_epi32(-16);
+    assert_eq!(m128i_i32(_mm_srav_epi32(a, _mm_setr_epi32(0, 2, 4, 40))), [-16, -4, -1, -1]);
+    let a = _mm256_setr_epi32(-16, 16, -16, 16, -16, 16, -16, 16);
+    let count = _mm256_setr_epi32(0, 0, 2, 2, 4, 4, 100, 100);
+    assert_eq!(m256i_i32(_mm256_srav_epi32(a, count)), [-16, 16, -4, 4, -1, 1, -1, 0]);
+    let a = _mm_set1_epi64x(1);
+    assert_eq!(m128i_i64(_mm_sllv_epi64(a, _mm_set_epi64x(64, 63))), [i64::MIN, 0]);
+    let a = _mm256_set1_epi64x(1);
+    let count = _mm256_setr_epi64x(0, 1, 62, 64);
+    assert_eq!(m256i_i64(_mm256_sllv_epi64(a, count)), [1, 2, 1 << 62, 0]);
+    let a = _mm_set1_epi64x(-1);
+    assert_eq!(m128i_i64(_mm_srlv_epi64(a, _mm_set_epi64x(64, 63))), [1, 0]);
+    let a = _mm256_set1_epi64x(-1);
+    let count = _mm256_setr_epi64x(0, 1, 63, 64);
+    assert_eq!(m256i_i64(_mm256_srlv_epi64(a, count)), [-1, i64::MAX, 1, 0]);
+
+    let a = _mm256_set1_epi16(-30000);
+    let b = _mm256_set1_epi16(30000);
+    assert_eq!(m256i_i16(_mm256_mulhi_epi16(a, b)), [-13733; 16]);
+    assert_eq!(m256i_i16(_mm256_mulhi_epu16(a, b)), [16267; 16]);
+
+    let r = m256i_i8(_mm256_sign_epi8(_mm256_set1_epi8(7), _mm256_set1_epi8(-3)));
+    assert_eq!(r, [-7; 32]);
+    let r = m256i_i16(_mm256_sign_epi16(_mm256_set1_epi16(7), _mm256_setzero_si256()));
+    assert_eq!(r, [0; 16]);
+    let r = m256i_i32(_mm256_sign_epi32(_mm256_set1_epi32(-7), _mm256_set1_epi32(1)));
+    assert_eq!(r, [-7; 8]);
+
+    let a = _mm256_setr_epi16(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16);
+    let b = _mm256_set1_epi16(100);
+    assert_eq!(
+        m256i_i16(_mm256_hadd_epi16(a, b)),
+        [3, 7, 11, 15, 200, 200, 200, 200, 19, 23, 27, 31, 200, 200, 200, 200]
+    );
+    assert_eq!(
+        m256i_i16(_mm256_hsub_epi16(a, b)),
+        [-1, -1, -1, -1, 0, 0, 0, 0, -1, -1, -1, -1, 0, 0, 0, 0]
+    );
+    let a = _mm256_setr_epi32(1, 2, 3, 4, 5, 6, 7, 8);
+    let b = _mm256_setr_epi32(10, 20, 30, 40, 50, 60, 70, 80);
+    assert_eq!(m256i_i32(_mm256_hadd_epi32(a, b)), [3, 7, 30, 70, 11, 15, 110, 150]);
+    assert_eq!(m256i_i32(_mm256_hsub_epi32(a, b)), [-1, -1, -10, -10, -1, -1, -10, -10]);
+    let a = _mm256_set1_epi16(32767);
+    assert_eq!(m256i_i16(_mm256_hadds_epi16(a, a)), [32767; 16]);
+    // Pairs of -32768 and 1.
+    let a = _mm256_set1_epi32(0x0001_8000);
+    assert_eq!(m256i_i16(_mm256_hsubs_epi16(a, a)), [-32768; 16]);
+
+    let mask = _mm256_setr_epi8(
+        -1, 0, -1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, //
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -128,
+    );
+    let r = m256i_i8(_mm256_blendv_epi8(_mm256_set1_epi8(1), _mm256_set1_epi8(2), mask));
+    assert_eq!(&r[..4], [2, 1, 2, 1]);
+    assert_eq!(r[31], 2);
+    assert_eq!(r[4..31], [1; 27]);
+
+    let a = _mm256_setr_epi32(10, 11, 12, 13, 14, 15, 16, 17);
+    let idx = _mm256_setr_epi32(7, 6, 5, 4, 3, 2, 1, 8);
+    // Indices wrap around, so 8 selects the first element.
+    assert_eq!(m256i_i32(_mm256_permutevar8x32_epi32(a, idx)), [17, 16, 15, 14, 13, 12, 11, 10]);
+    let a = _mm256_setr_ps(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0);
+    let idx = _mm256_setr_epi32(0, 0, 7, 7, 4, 3, 2, 1);
+    assert_eq!(m256(_mm256_permutevar8x32_ps(a, idx)), [1.0, 1.0, 8.0, 8.0, 5.0, 4.0, 3.0, 2.0]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "fma")]
+unsafe fn test_fma() {
+    let a = _mm_setr_ps(1.0, 2.0, 3.0, 4.0);
+    let b = _mm_set1_ps(2.0);
+    let c = _mm_set1_ps(1.0);
+    // The even elements subtract `c`, the odd elements add it.
+    assert_eq!(m128(_mm_fmaddsub_ps(a, b, c)), [1.0, 5.0, 5.0, 9.0]);
+    assert_eq!(m128(_mm_fmsubadd_ps(a, b, c)), [3.0, 3.0, 7.0, 7.0]);
+    assert_eq!(m128(_mm_fnmadd_ps(a, b, c)), [-1.0, -3.0, -5.0, -7.0]);
+    let a = _mm256_setr_pd(1.0, 2.0, 3.0, 4.0);
+    let b = _mm256_set1_pd(2.0);
+    let c = _mm256_set1_pd(1.0);
+    assert_eq!(m256d(_mm256_fmaddsub_pd(a, b, c)), [1.0, 5.0, 5.0, 9.0]);
+    assert_eq!(m256d(_mm256_fmsubadd_pd(a, b, c)), [3.0, 3.0, 7.0, 7.0]);
+    assert_eq!(m256d(_mm256_fnmadd_pd(a, b, c)), [-1.0, -3.0, -5.0, -7.0]);
+}
+
+/// Tests the generic `simd_*` intrinsics through `std::simd`, which lowers most operations
+/// directly to them.
+fn test_simd_intrinsics() {
+    let a = i32x4::from_array([1, -8, 3, i32::MIN]);
+    // simd_shl and simd_shr
+    assert_eq!((a << i32x4::splat(2)).to_array(), [4, -32, 12, 0]);
+    assert_eq!((a >> i32x4::splat(2)).to_array(), [0, -2, 0, i32::MIN >> 2]);
+    let b = u32x4::from_array([1, 8, 3, u32::MAX]);
+    assert_eq!((b >> u32x4::from_array([0, 1, 2, 31])).to_array(), [1, 4, 0, 1]);
+
+    // simd_saturating_add and simd_saturating_sub
+    let a = i8x4::from_array([100, -100, 1, -1]);
+    assert_eq!(a.saturating_add(i8x4::splat(100)).to_array(), [127, 0, 101, 99]);
+    assert_eq!(a.saturating_sub(i8x4::splat(100)).to_array(), [0, -128, -99, -101]);
+    let a = u8x4::from_array([200, 0, 1, 255]);
+    assert_eq!(a.saturating_add(u8x4::splat(100)).to_array(), [255, 100, 101, 255]);
+    assert_eq!(a.saturating_sub(u8x4::splat(100)).to_array(), [100, 0, 0, 155]);
+
+    // simd_lt and simd_select
+    let a = i32x4::from_array([1, 5, 3, 7]);
+    let b = i32x4::from_array([4, 4, 4, 4]);
+    let mask = a.simd_lt(b);
+    assert_eq!(mask.select(a, b).to_array(), [1, 4, 3, 4]);
+    // simd_bitmask and simd_select_bitmask
+    assert_eq!(mask.to_bitmask(), 0b0101);
+    assert_eq!(mask32x4::from_bitmask(0b1010).select(a, b).to_array(), [4, 5, 4, 7]);
+    // simd_reduce_any and simd_reduce_all
+    assert!(mask.any());
+    assert!(!mask.all());
+
+    // simd_reduce_add_ordered, simd_reduce_mul_ordered, simd_reduce_min and simd_reduce_max
+    assert_eq!(a.reduce_sum(), 16);
+    assert_eq!(a.reduce_product(), 105);
+    assert_eq!(a.reduce_min(), 1);
+    assert_eq!(a.reduce_max(), 7);
+    assert_eq!(f32x4::from_array([1.0, 2.0, 3.0, 4.5]).reduce_sum(), 10.5);
+    // simd_reduce_and, simd_reduce_or and simd_reduce_xor
+    let a = u8x4::from_array([0b1100, 0b1010, 0b1111, 0b1000]);
+    assert_eq!(a.reduce_and(), 0b1000);
+    assert_eq!(a.reduce_or(), 0b1111);
+    assert_eq!(a.reduce_xor(), 0b0001);
+
+    // simd_fmin, simd_fmax and simd_fabs
+    let a = f32x4::from_array([1.0, -2.0, f32::NAN, 4.0]);
+    let b = f32x4::from_array([0.5, 3.0, 1.0, f32::NAN]);
+    assert_eq!(a.simd_min(b).to_array(), [0.5, -2.0, 1.0, 4.0]);
+    assert_eq!(a.simd_max(b).to_array(), [1.0, 3.0, 1.0, 4.0]);
+    assert_eq!(f32x2::from_array([-1.5, 2.0]).abs().to_array(), [1.5, 2.0]);
+
+    // simd_cast and simd_as
+    let a = f32x4::from_array([1.7, -1.7, 300.0, -300.0]);
+    assert_eq!(a.cast::<i32>().to_array(), [1, -1, 300, -300]);
+    // Float to int casts saturate.
+    assert_eq!(a.cast::<u8>().to_array(), [1, 0, 255, 0]);
+    assert_eq!(i16x4::from_array([-1, 256, 2, 3]).cast::<u8>().to_array(), [255, 0, 2, 3]);
+
+    // simd_shuffle
+    let a = i32x4::from_array([1, 2, 3, 4]);
+    assert_eq!(a.reverse().to_array(), [4, 3, 2, 1]);
+    let b = i32x4::from_array([5, 6, 7, 8]);
+    let (lo, hi) = a.interleave(b);
+    assert_eq!((lo.to_array(), hi.to_array()), ([1, 5, 2, 6], [3, 7, 4, 8]));
+    assert_eq!(simd_swizzle!(a, b, [0, 7, 3, 4]).to_array(), [1, 8, 4, 5]);
+
+    // simd_gather and simd_scatter
+    let mut data = [10, 20, 30, 40, 50];
+    let idx = usizex4::from_array([4, 0, 9, 2]);
+    let gathered = i32x4::gather_or(&data, idx, i32x4::splat(-1));
+    assert_eq!(gathered.to_array(), [50, 10, -1, 30]);
+    i32x4::from_array([1, 2, 3, 4]).scatter(&mut data, idx);
+    assert_eq!(data, [2, 20, 4, 40, 1]);
+
+    // simd_ctlz, simd_cttz and simd_bswap
+    let a = u32x4::from_array([1, 0x8000_0000, 0, 0x0001_0000]);
+    assert_eq!(a.leading_zeros().to_array(), [31, 0, 32, 15]);
+    assert_eq!(a.trailing_zeros().to_array(), [0, 31, 32, 16]);
+    assert_eq!(a.swap_bytes().to_array(), [0x0100_0000, 0x80, 0, 0x0100]);
+}
@@ -0,0 +1,113 @@
+#!/usr/bin/env bash
+#![forbid(unsafe_code)]
+/* This line is ignored by bash
+# This block is ignored by rustc
+pushd $(dirname "$0")/../
+RUSTC="$(pwd)/dist/rustc-clif"
+CG_CLIF_DIR="$(pwd)"
+popd
+CG_CLIF_DIR=$CG_CLIF_DIR STDARCH=$1 CHECK=$2 exec $RUSTC -Zunstable-options -Cllvm-args=mode=jit -Cprefer-dynamic $0
+#*/
+
+//! This program compares the `llvm.x86.*` intrinsics used by `core::arch` with the ones
+//! implemented in `src/intrinsics/llvm_x86.rs` and prints a markdown coverage table.
+//!
+//! Usage: ./x86_intrinsic_coverage.rs <path to stdarch checkout> [--check]
+//!
+//! With `--check` the program exits with an error if any intrinsic from SSE up to and including
+//! AVX2 is not implemented.
+//!
+//! This file is specially crafted to be both a valid bash script and valid rust source file. If
+//! executed as bash script this will run the rust source using cg_clif in JIT mode.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// The extensions that are expected to be fully supported. The name is the second component of
+/// the intrinsic name, like `sse41` in `llvm.x86.sse41.ptestz`.
+const CHECKED_EXTENSIONS: &[&str] =
+    &["sse", "sse2", "sse3", "ssse3", "sse41", "sse42", "avx", "avx2", "fma"];
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let stdarch = std::env::var("STDARCH").unwrap();
+    let check = match &*std::env::var("CHECK").unwrap() {
+        "" => false,
+        "--check" => true,
+        arg => return Err(format!("Unknown argument {arg}").into()),
+    };
+    if stdarch.is_empty() {
+        println!("Usage: ./x86_intrinsic_coverage.rs <path to stdarch checkout> [--check]");
+        std::process::exit(1);
+    }
+
+    let mut used = BTreeSet::new();
+    for dir in ["x86", "x86_64"] {
+        let dir = Path::new(&stdarch).join("crates/core_arch/src").join(dir);
+        for entry in std::fs::read_dir(&dir)
+            .map_err(|err| format!("Failed to read {}: {}", dir.display(), err))?
+        {
+            let path = entry?.path();
+            if path.extension().map_or(true, |ext| ext != "rs") {
+                continue;
+            }
+            for line in std::fs::read_to_string(&path)?.lines() {
+                let line = line.trim();
+                if !line.starts_with("#[link_name") {
+                    continue;
+                }
+                used.extend(intrinsic_names(line));
+            }
+        }
+    }
+
+    let llvm_x86 =
+        Path::new(&std::env::var("CG_CLIF_DIR").unwrap()).join("src/intrinsics/llvm_x86.rs");
+    let implemented_src = std::fs::read_to_string(&llvm_x86)
+        .map_err(|err| format!("Failed to read {}: {}", llvm_x86.display(), err))?;
+    let implemented: BTreeSet<String> = implemented_src.lines().flat_map(intrinsic_names).collect();
+
+    let mut by_extension: BTreeMap<&str, (Vec<&str>, Vec<&str>)> = BTreeMap::new();
+    for name in &used {
+        let extension = name.strip_prefix("llvm.x86.").unwrap().split('.').next().unwrap();
+        let entry = by_extension.entry(extension).or_default();
+        if implemented.contains(name) {
+            entry.0.push(name);
+        } else {
+            entry.1.push(name);
+        }
+    }
+
+    println!("| Extension | Implemented | Missing |");
+    println!("|-----------|-------------|---------|");
+    for (extension, (implemented, missing)) in &by_extension {
+        println!("| {} | {} | {} |", extension, implemented.len(), missing.len());
+    }
+
+    let mut failed = false;
+    for (extension, (_implemented, missing)) in &by_extension {
+        if missing.is_empty() {
+            continue;
+        }
+        println!();
+        println!("Missing `{}` intrinsics:", extension);
+        for name in missing {
+            println!("* `{}`", name);
+        }
+        failed |= CHECKED_EXTENSIONS.contains(extension);
+    }
+
+    if check && failed {
+        return Err("Some SSE-AVX2 intrinsics are not implemented".into());
+    }
+
+    Ok(())
+}
+
+/// Return all string literals on `line` that name an x86 LLVM intrinsic.
+fn intrinsic_names(line: &str) -> impl Iterator<Item = String> + '_ {
+    line.split('"')
+        .skip(1)
+        .step_by(2)
+        .filter(|lit| lit.starts_with("llvm.x86.") && !lit.contains(' '))
+        .map(|lit| lit.to_owned())
+}
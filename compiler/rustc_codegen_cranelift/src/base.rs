@@ -56,7 +56,9 @@ pub(crate) fn codegen_fn<'tcx>(
     func.clear();
     func.name = UserFuncName::user(0, func_id.as_u32());
     func.signature = sig;
-    func.collect_debug_info();
+    if cx.debug_context.is_some() {
+        func.collect_debug_info();
+    }
 
     let mut bcx = FunctionBuilder::new(&mut func, &mut func_ctx);
 
@@ -102,6 +104,9 @@ pub(crate) fn codegen_fn<'tcx>(
     };
 
     tcx.prof.generic_activity("codegen clif ir").run(|| codegen_fn_body(&mut fx, start_block));
+    if fx.func_debug_cx.is_some() {
+        crate::debuginfo::define_variables(&mut fx, type_dbg);
+    }
     fx.bcx.seal_all_blocks();
     fx.bcx.finalize();
 
@@ -221,6 +226,7 @@ pub(crate) fn compile_fn(
         if let Some(debug_context) = debug_context {
             codegened_func.func_debug_cx.unwrap().finalize(
                 debug_context,
+                isa,
                 codegened_func.func_id,
                 context,
             );
@@ -414,8 +414,12 @@ pub(crate) fn set_debug_loc(&mut self, source_info: mir::SourceInfo) {
             let (file_id, line, column) =
                 debug_context.get_span_loc(self.tcx, self.mir.span, source_info.span);
 
-            let source_loc =
-                self.func_debug_cx.as_mut().unwrap().add_dbg_loc(file_id, line, column);
+            let source_loc = self.func_debug_cx.as_mut().unwrap().add_dbg_loc(
+                file_id,
+                line,
+                column,
+                source_info.scope,
+            );
             self.bcx.set_srcloc(source_loc);
         }
     }
@@ -135,14 +135,8 @@ pub(crate) fn codegen_const_value<'tcx>(
             Scalar::Ptr(ptr, _size) => {
                 let (prov, offset) = ptr.into_parts(); // we know the `offset` is relative
                 let alloc_id = prov.alloc_id();
-                let base_addr = match fx.tcx.global_alloc(alloc_id) {
-                    GlobalAlloc::Memory(alloc) => {
-                        let data_id = data_id_for_alloc_id(
-                            &mut fx.constants_cx,
-                            fx.module,
-                            alloc_id,
-                            alloc.inner().mutability,
-                        );
+                let base_addr = match global_alloc_base(fx, alloc_id) {
+                    GlobalAllocBase::Data(data_id) => {
                         let local_data_id =
                             fx.module.declare_data_in_func(data_id, &mut fx.bcx.func);
                         if fx.clif_comments.enabled() {
@@ -150,38 +144,11 @@ pub(crate) fn codegen_const_value<'tcx>(
                         }
                         fx.bcx.ins().global_value(fx.pointer_type, local_data_id)
                     }
-                    GlobalAlloc::Function(instance) => {
-                        let func_id = crate::abi::import_function(fx.tcx, fx.module, instance);
+                    GlobalAllocBase::Func(func_id) => {
                         let local_func_id =
                             fx.module.declare_func_in_func(func_id, &mut fx.bcx.func);
                         fx.bcx.ins().func_addr(fx.pointer_type, local_func_id)
                     }
-                    GlobalAlloc::VTable(ty, trait_ref) => {
-                        let data_id = data_id_for_vtable(
-                            fx.tcx,
-                            &mut fx.constants_cx,
-                            fx.module,
-                            ty,
-                            trait_ref,
-                        );
-                        let local_data_id =
-                            fx.module.declare_data_in_func(data_id, &mut fx.bcx.func);
-                        fx.bcx.ins().global_value(fx.pointer_type, local_data_id)
-                    }
-                    GlobalAlloc::Static(def_id) => {
-                        assert!(fx.tcx.is_static(def_id));
-                        let data_id = data_id_for_static(
-                            fx.tcx, fx.module, def_id, false,
-                            // For a declaration the stated mutability doesn't matter.
-                            false,
-                        );
-                        let local_data_id =
-                            fx.module.declare_data_in_func(data_id, &mut fx.bcx.func);
-                        if fx.clif_comments.enabled() {
-                            fx.add_comment(local_data_id, format!("{:?}", def_id));
-                        }
-                        fx.bcx.ins().global_value(fx.pointer_type, local_data_id)
-                    }
                 };
                 let val = if offset.bytes() != 0 {
                     fx.bcx.ins().iadd_imm(base_addr, i64::try_from(offset.bytes()).unwrap())
@@ -205,6 +172,44 @@ pub(crate) fn codegen_const_value<'tcx>(
     }
 }
 
+/// The data object or function that a pointer into a global allocation is relative to.
+pub(crate) enum GlobalAllocBase {
+    Data(DataId),
+    Func(FuncId),
+}
+
+pub(crate) fn global_alloc_base<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    alloc_id: AllocId,
+) -> GlobalAllocBase {
+    match fx.tcx.global_alloc(alloc_id) {
+        GlobalAlloc::Memory(alloc) => GlobalAllocBase::Data(data_id_for_alloc_id(
+            &mut fx.constants_cx,
+            fx.module,
+            alloc_id,
+            alloc.inner().mutability,
+        )),
+        GlobalAlloc::Function(instance) => {
+            GlobalAllocBase::Func(crate::abi::import_function(fx.tcx, fx.module, instance))
+        }
+        GlobalAlloc::VTable(ty, trait_ref) => GlobalAllocBase::Data(data_id_for_vtable(
+            fx.tcx,
+            &mut fx.constants_cx,
+            fx.module,
+            ty,
+            trait_ref,
+        )),
+        GlobalAlloc::Static(def_id) => {
+            assert!(fx.tcx.is_static(def_id));
+            GlobalAllocBase::Data(data_id_for_static(
+                fx.tcx, fx.module, def_id, false,
+                // For a declaration the stated mutability doesn't matter.
+                false,
+            ))
+        }
+    }
+}
+
 fn pointer_for_allocation<'tcx>(
     fx: &mut FunctionCx<'_, '_, 'tcx>,
     alloc_id: AllocId,
@@ -123,8 +123,14 @@ pub(crate) fn add_source_file(&mut self, source_file: &SourceFile) -> FileId {
 }
 
 impl FunctionDebugContext {
-    pub(crate) fn add_dbg_loc(&mut self, file_id: FileId, line: u64, column: u64) -> SourceLoc {
-        let (index, _) = self.source_loc_set.insert_full((file_id, line, column));
+    pub(crate) fn add_dbg_loc(
+        &mut self,
+        file_id: FileId,
+        line: u64,
+        column: u64,
+        scope: SourceScope,
+    ) -> SourceLoc {
+        let (index, _) = self.source_loc_set.insert_full((file_id, line, column, scope));
         SourceLoc::new(u32::try_from(index).unwrap())
     }
 
@@ -152,8 +158,8 @@ pub(super) fn create_debug_lines(
         for &MachSrcLoc { start, end, loc } in mcr.buffer.get_srclocs_sorted() {
             debug_context.dwarf.unit.line_program.row().address_offset = u64::from(start);
             if !loc.is_default() {
-                let source_loc = self.source_loc_set[loc.bits() as usize];
-                create_row_for_span(debug_context, source_loc);
+                let (file_id, line, column, _scope) = self.source_loc_set[loc.bits() as usize];
+                create_row_for_span(debug_context, (file_id, line, column));
             } else {
                 create_row_for_span(debug_context, self.function_source_loc);
             }
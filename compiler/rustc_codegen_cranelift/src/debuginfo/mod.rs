@@ -5,6 +5,7 @@
 mod object;
 mod types;
 mod unwind;
+mod variables;
 
 use cranelift_codegen::ir::Endianness;
 use cranelift_codegen::isa::TargetIsa;
@@ -18,6 +19,7 @@
 use rustc_codegen_ssa::debuginfo::type_names;
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::DefIdMap;
+use rustc_index::IndexVec;
 use rustc_session::Session;
 use rustc_span::{SourceFileHash, StableSourceFileId};
 use rustc_target::abi::call::FnAbi;
@@ -25,7 +27,9 @@
 pub(crate) use self::emit::{DebugReloc, DebugRelocName};
 pub(crate) use self::types::TypeDebugContext;
 pub(crate) use self::unwind::UnwindContext;
+pub(crate) use self::variables::define_variables;
 use crate::debuginfo::emit::{address_for_data, address_for_func};
+use crate::debuginfo::variables::{LexicalBlock, VariableDebugInfo};
 use crate::prelude::*;
 
 pub(crate) fn producer(sess: &Session) -> String {
@@ -48,7 +52,12 @@ pub(crate) struct DebugContext {
 pub(crate) struct FunctionDebugContext {
     entry_id: UnitEntryId,
     function_source_loc: (FileId, u64, u64),
-    source_loc_set: IndexSet<(FileId, u64, u64)>,
+    /// The source locations used in the function, together with the MIR scope of the code.
+    source_loc_set: IndexSet<(FileId, u64, u64, SourceScope)>,
+    variables: Vec<VariableDebugInfo>,
+    lexical_blocks: Vec<LexicalBlock>,
+    /// The innermost lexical block containing the code of each MIR scope, if any.
+    scope_blocks: IndexVec<SourceScope, Option<usize>>,
 }
 
 impl DebugContext {
@@ -254,6 +263,9 @@ pub(crate) fn define_function<'tcx>(
             entry_id,
             function_source_loc: (file_id, line, column),
             source_loc_set: IndexSet::new(),
+            variables: Vec::new(),
+            lexical_blocks: Vec::new(),
+            scope_blocks: IndexVec::new(),
         }
     }
 
@@ -318,10 +330,13 @@ impl FunctionDebugContext {
     pub(crate) fn finalize(
         mut self,
         debug_context: &mut DebugContext,
+        isa: &dyn TargetIsa,
         func_id: FuncId,
         context: &Context,
     ) {
         let end = self.create_debug_lines(debug_context, func_id, context);
+        self.define_lexical_block_ranges(debug_context, func_id, context);
+        self.define_variable_locations(debug_context, isa, func_id, context, end);
 
         debug_context
             .unit_range_list
@@ -1,10 +1,13 @@
 // Adapted from https://github.com/rust-lang/rust/blob/10a7aa14fed9b528b74b0f098c4899c37c09a9c7/compiler/rustc_codegen_llvm/src/debuginfo/metadata.rs
 
 use gimli::write::{AttributeValue, UnitEntryId};
-use rustc_codegen_ssa::debuginfo::type_names;
+use rustc_codegen_ssa::debuginfo::{type_names, wants_c_like_enum_debuginfo};
 use rustc_data_structures::fx::FxHashMap;
-use rustc_middle::ty::layout::LayoutOf;
-use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_hir::def::CtorKind;
+use rustc_middle::ty::layout::{LayoutOf, PrimitiveExt, TyAndLayout};
+use rustc_middle::ty::{self, AdtDef, AdtKind, GenericArgsRef, Ty, TyCtxt};
+use rustc_span::def_id::DefId;
+use rustc_target::abi::{Align, Size, TagEncoding, VariantIdx, Variants};
 
 use crate::{has_ptr_meta, DebugContext, RevealAllLayoutCx};
 
@@ -44,21 +47,40 @@ pub(crate) fn debug_type<'tcx>(
                 type_dbg,
                 ty,
                 *elem_ty,
-                len.eval_target_usize(tcx, ty::ParamEnv::reveal_all()),
+                Some(len.eval_target_usize(tcx, ty::ParamEnv::reveal_all())),
             ),
-            // ty::Slice(_) | ty::Str
-            // ty::Dynamic
-            // ty::Foreign
+            ty::Slice(elem_ty) => self.array_type(tcx, type_dbg, ty, *elem_ty, None),
+            ty::Str => self.array_type(tcx, type_dbg, ty, tcx.types.u8, None),
+            ty::Dynamic(..) | ty::Foreign(_) => self.opaque_type(tcx, ty),
             ty::RawPtr(pointee_type, _) | ty::Ref(_, pointee_type, _) => {
                 self.pointer_type(tcx, type_dbg, ty, *pointee_type)
             }
-            // ty::Adt(def, args) if def.is_box() && args.get(1).map_or(true, |arg| cx.layout_of(arg.expect_ty()).is_1zst())
-            // ty::FnDef(..) | ty::FnPtr(..)
-            // ty::Closure(..)
-            // ty::Adt(def, ..)
+            ty::Adt(def, args)
+                if def.is_box()
+                    && args.get(1).map_or(true, |arg| {
+                        RevealAllLayoutCx(tcx).layout_of(arg.expect_ty()).is_1zst()
+                    }) =>
+            {
+                self.pointer_type(tcx, type_dbg, ty, ty.boxed_ty())
+            }
+            ty::FnDef(..) | ty::FnPtr(_) => self.subroutine_type(tcx, type_dbg, ty),
+            ty::Closure(def_id, args) => {
+                self.closure_type(tcx, type_dbg, ty, *def_id, args.as_closure().upvar_tys())
+            }
+            ty::CoroutineClosure(def_id, args) => self.closure_type(
+                tcx,
+                type_dbg,
+                ty,
+                *def_id,
+                args.as_coroutine_closure().upvar_tys(),
+            ),
+            ty::Coroutine(def_id, args) => self.coroutine_type(tcx, type_dbg, ty, *def_id, args),
+            ty::Adt(def, args) => match def.adt_kind() {
+                AdtKind::Struct => self.struct_type(tcx, type_dbg, ty, *def, args),
+                AdtKind::Union => self.union_type(tcx, type_dbg, ty, *def, args),
+                AdtKind::Enum => self.enum_type(tcx, type_dbg, ty, *def, args),
+            },
             ty::Tuple(components) => self.tuple_type(tcx, type_dbg, ty, *components),
-            // ty::Param(_)
-            // FIXME implement remaining types and add unreachable!() to the fallback branch
             _ => self.placeholder_for_type(tcx, type_dbg, ty),
         };
 
@@ -97,7 +119,7 @@ fn array_type<'tcx>(
         type_dbg: &mut TypeDebugContext<'tcx>,
         array_ty: Ty<'tcx>,
         elem_ty: Ty<'tcx>,
-        len: u64,
+        len: Option<u64>,
     ) -> UnitEntryId {
         let elem_dw_ty = self.debug_type(tcx, type_dbg, elem_ty);
 
@@ -106,12 +128,19 @@ fn array_type<'tcx>(
         let array_type_id = self.dwarf.unit.add(self.dwarf.unit.root(), gimli::DW_TAG_array_type);
         let array_type_entry = self.dwarf.unit.get_mut(array_type_id);
         array_type_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(elem_dw_ty));
+        if array_ty.is_str() {
+            array_type_entry
+                .set(gimli::DW_AT_name, AttributeValue::StringRef(self.dwarf.strings.add("str")));
+        }
 
         let subrange_id = self.dwarf.unit.add(array_type_id, gimli::DW_TAG_subrange_type);
         let subrange_entry = self.dwarf.unit.get_mut(subrange_id);
         subrange_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(self.array_size_type));
         subrange_entry.set(gimli::DW_AT_lower_bound, AttributeValue::Udata(0));
-        subrange_entry.set(gimli::DW_AT_count, AttributeValue::Udata(len));
+        // Slices and `str` don't have a statically known length.
+        if let Some(len) = len {
+            subrange_entry.set(gimli::DW_AT_count, AttributeValue::Udata(len));
+        }
 
         array_type_id
     }
@@ -130,18 +159,126 @@ fn pointer_type<'tcx>(
         let name = type_names::compute_debuginfo_type_name(tcx, ptr_type, true);
 
         if !has_ptr_meta(tcx, ptr_type) {
-            let pointer_type_id =
-                self.dwarf.unit.add(self.dwarf.unit.root(), gimli::DW_TAG_pointer_type);
-            let pointer_entry = self.dwarf.unit.get_mut(pointer_type_id);
-            pointer_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(pointee_dw_ty));
-            pointer_entry
-                .set(gimli::DW_AT_name, AttributeValue::StringRef(self.dwarf.strings.add(name)));
-
-            pointer_type_id
+            return self.thin_pointer_type(pointee_dw_ty, &name);
+        }
+
+        // Fat pointers are described as a struct of the data pointer and the metadata. For
+        // slices and `str` the data pointer points to the first element.
+        let (data_ptr_dw_ty, meta_name, meta_ty) = match pointee_type.kind() {
+            ty::Slice(elem_ty) => {
+                let elem_dw_ty = self.debug_type(tcx, type_dbg, *elem_ty);
+                let elem_name = type_names::compute_debuginfo_type_name(tcx, *elem_ty, true);
+                (
+                    self.thin_pointer_type(elem_dw_ty, &format!("*const {elem_name}")),
+                    "length",
+                    tcx.types.usize,
+                )
+            }
+            ty::Str => {
+                let elem_dw_ty = self.debug_type(tcx, type_dbg, tcx.types.u8);
+                (self.thin_pointer_type(elem_dw_ty, "*const u8"), "length", tcx.types.usize)
+            }
+            _ => {
+                let pointee_name = type_names::compute_debuginfo_type_name(tcx, pointee_type, true);
+                let data_ptr_dw_ty =
+                    self.thin_pointer_type(pointee_dw_ty, &format!("*const {pointee_name}"));
+                let tail =
+                    tcx.struct_tail_erasing_lifetimes(pointee_type, ty::ParamEnv::reveal_all());
+                if let ty::Dynamic(..) = tail.kind() {
+                    let vtable_ty = Ty::new_imm_ref(
+                        tcx,
+                        tcx.lifetimes.re_static,
+                        Ty::new_array(tcx, tcx.types.usize, 3),
+                    );
+                    (data_ptr_dw_ty, "vtable", vtable_ty)
+                } else {
+                    (data_ptr_dw_ty, "length", tcx.types.usize)
+                }
+            }
+        };
+        let meta_dw_ty = self.debug_type(tcx, type_dbg, meta_ty);
+
+        return_if_type_created_in_meantime!(type_dbg, ptr_type);
+
+        let layout = RevealAllLayoutCx(tcx).layout_of(ptr_type);
+        let pointer_type_id = self.composite_type(
+            self.dwarf.unit.root(),
+            gimli::DW_TAG_structure_type,
+            &name,
+            layout,
+        );
+        let pointer_size = tcx.data_layout.pointer_size;
+        let pointer_align = tcx.data_layout.pointer_align.abi;
+        self.add_member(pointer_type_id, "data_ptr", data_ptr_dw_ty, Size::ZERO, pointer_align);
+        self.add_member(pointer_type_id, meta_name, meta_dw_ty, pointer_size, pointer_align);
+
+        pointer_type_id
+    }
+
+    fn thin_pointer_type(&mut self, pointee_dw_ty: UnitEntryId, name: &str) -> UnitEntryId {
+        let pointer_type_id =
+            self.dwarf.unit.add(self.dwarf.unit.root(), gimli::DW_TAG_pointer_type);
+        let pointer_entry = self.dwarf.unit.get_mut(pointer_type_id);
+        pointer_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(pointee_dw_ty));
+        pointer_entry
+            .set(gimli::DW_AT_name, AttributeValue::StringRef(self.dwarf.strings.add(name)));
+
+        pointer_type_id
+    }
+
+    /// Describes trait objects and extern types, which have an unknown size.
+    fn opaque_type<'tcx>(&mut self, tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> UnitEntryId {
+        let name = type_names::compute_debuginfo_type_name(tcx, ty, false);
+
+        let type_id = self.dwarf.unit.add(self.dwarf.unit.root(), gimli::DW_TAG_structure_type);
+        let type_entry = self.dwarf.unit.get_mut(type_id);
+        type_entry.set(gimli::DW_AT_name, AttributeValue::StringRef(self.dwarf.strings.add(name)));
+        type_entry.set(gimli::DW_AT_declaration, AttributeValue::FlagPresent);
+
+        type_id
+    }
+
+    fn subroutine_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        fn_ty: Ty<'tcx>,
+    ) -> UnitEntryId {
+        let sig =
+            tcx.normalize_erasing_late_bound_regions(ty::ParamEnv::reveal_all(), fn_ty.fn_sig(tcx));
+        let output_dw_ty = if sig.output().is_unit() {
+            None
         } else {
-            // FIXME implement debuginfo for fat pointers
-            self.placeholder_for_type(tcx, type_dbg, ptr_type)
+            Some(self.debug_type(tcx, type_dbg, sig.output()))
+        };
+        let input_dw_tys =
+            sig.inputs().iter().map(|&ty| self.debug_type(tcx, type_dbg, ty)).collect::<Vec<_>>();
+
+        return_if_type_created_in_meantime!(type_dbg, fn_ty);
+
+        let subroutine_type_id =
+            self.dwarf.unit.add(self.dwarf.unit.root(), gimli::DW_TAG_subroutine_type);
+        if let Some(output_dw_ty) = output_dw_ty {
+            let subroutine_entry = self.dwarf.unit.get_mut(subroutine_type_id);
+            subroutine_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(output_dw_ty));
+        }
+        for input_dw_ty in input_dw_tys {
+            let param_id = self.dwarf.unit.add(subroutine_type_id, gimli::DW_TAG_formal_parameter);
+            let param_entry = self.dwarf.unit.get_mut(param_id);
+            param_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(input_dw_ty));
         }
+
+        // Both fn items and fn pointers are described as a pointer to the function. Fn items
+        // are zero-sized though.
+        let name = type_names::compute_debuginfo_type_name(tcx, fn_ty, false);
+        let pointer_type_id = self.thin_pointer_type(subroutine_type_id, &name);
+        let pointer_entry = self.dwarf.unit.get_mut(pointer_type_id);
+        pointer_entry.set(
+            gimli::DW_AT_byte_size,
+            AttributeValue::Udata(RevealAllLayoutCx(tcx).layout_of(fn_ty).size.bytes()),
+        );
+
+        pointer_type_id
     }
 
     fn tuple_type<'tcx>(
@@ -189,6 +326,391 @@ fn tuple_type<'tcx>(
         tuple_type_id
     }
 
+    fn struct_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        struct_type: Ty<'tcx>,
+        adt_def: AdtDef<'tcx>,
+        args: GenericArgsRef<'tcx>,
+    ) -> UnitEntryId {
+        let scope = self.item_namespace(tcx, tcx.parent(adt_def.did()));
+        let name = type_names::compute_debuginfo_type_name(tcx, struct_type, false);
+        let layout = RevealAllLayoutCx(tcx).layout_of(struct_type);
+
+        // The type is registered before describing the fields, as they may point back to it.
+        let struct_type_id =
+            self.composite_type(scope, gimli::DW_TAG_structure_type, &name, layout);
+        type_dbg.type_map.insert(struct_type, struct_type_id);
+
+        self.add_variant_fields(
+            tcx,
+            type_dbg,
+            struct_type_id,
+            layout,
+            adt_def.non_enum_variant(),
+            args,
+        );
+
+        struct_type_id
+    }
+
+    fn union_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        union_type: Ty<'tcx>,
+        adt_def: AdtDef<'tcx>,
+        args: GenericArgsRef<'tcx>,
+    ) -> UnitEntryId {
+        let scope = self.item_namespace(tcx, tcx.parent(adt_def.did()));
+        let name = type_names::compute_debuginfo_type_name(tcx, union_type, false);
+        let layout = RevealAllLayoutCx(tcx).layout_of(union_type);
+
+        let union_type_id = self.composite_type(scope, gimli::DW_TAG_union_type, &name, layout);
+        type_dbg.type_map.insert(union_type, union_type_id);
+
+        self.add_variant_fields(
+            tcx,
+            type_dbg,
+            union_type_id,
+            layout,
+            adt_def.non_enum_variant(),
+            args,
+        );
+
+        union_type_id
+    }
+
+    /// Describes an enum the same way as `rustc_codegen_llvm` does for non-MSVC targets:
+    ///
+    /// ```txt
+    /// DW_TAG_structure_type              (top-level type for the enum)
+    ///   DW_TAG_variant_part
+    ///     DW_AT_discr                    (reference to the tag member)
+    ///     DW_TAG_member                  (tag)
+    ///     DW_TAG_variant                 (one per variant, with DW_AT_discr_value)
+    ///       DW_TAG_member                (of the type of the variant)
+    ///   DW_TAG_structure_type            (type of each variant)
+    /// ```
+    ///
+    /// Fieldless enums are described as a `DW_TAG_enumeration_type` instead.
+    fn enum_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        enum_type: Ty<'tcx>,
+        adt_def: AdtDef<'tcx>,
+        args: GenericArgsRef<'tcx>,
+    ) -> UnitEntryId {
+        let scope = self.item_namespace(tcx, tcx.parent(adt_def.did()));
+        let name = type_names::compute_debuginfo_type_name(tcx, enum_type, false);
+        let layout = RevealAllLayoutCx(tcx).layout_of(enum_type);
+
+        if wants_c_like_enum_debuginfo(layout) {
+            return self.c_like_enum_type(tcx, type_dbg, scope, &name, layout, adt_def);
+        }
+
+        let enum_type_id = self.composite_type(scope, gimli::DW_TAG_structure_type, &name, layout);
+        type_dbg.type_map.insert(enum_type, enum_type_id);
+
+        let variant_part_id = self.variant_part(tcx, type_dbg, enum_type_id, layout);
+
+        for (variant_index, variant_def) in adt_def.variants().iter_enumerated() {
+            if matches!(layout.variants, Variants::Single { index } if index != variant_index) {
+                continue;
+            }
+
+            let variant_layout = layout.for_variant(&RevealAllLayoutCx(tcx), variant_index);
+            let variant_name = variant_def.name.as_str();
+            let variant_struct_id = self.composite_type(
+                enum_type_id,
+                gimli::DW_TAG_structure_type,
+                variant_name,
+                variant_layout,
+            );
+            self.add_variant_fields(
+                tcx,
+                type_dbg,
+                variant_struct_id,
+                variant_layout,
+                variant_def,
+                args,
+            );
+
+            self.add_variant(
+                tcx,
+                variant_part_id,
+                layout,
+                variant_index,
+                variant_name,
+                variant_struct_id,
+            );
+        }
+
+        enum_type_id
+    }
+
+    /// Adds the `DW_TAG_variant_part` of an enum-like type, with the tag member if there is one.
+    fn variant_part<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        parent: UnitEntryId,
+        layout: TyAndLayout<'tcx>,
+    ) -> UnitEntryId {
+        let variant_part_id = self.dwarf.unit.add(parent, gimli::DW_TAG_variant_part);
+        if let Variants::Multiple { tag, tag_field, .. } = layout.variants {
+            let tag_ty = tag.primitive().to_int_ty(tcx);
+            let tag_dw_ty = self.debug_type(tcx, type_dbg, tag_ty);
+            let tag_member_id = self.add_member(
+                variant_part_id,
+                "",
+                tag_dw_ty,
+                layout.fields.offset(tag_field),
+                RevealAllLayoutCx(tcx).layout_of(tag_ty).align.pref,
+            );
+            let tag_member_entry = self.dwarf.unit.get_mut(tag_member_id);
+            tag_member_entry.set(gimli::DW_AT_artificial, AttributeValue::Flag(true));
+            let variant_part_entry = self.dwarf.unit.get_mut(variant_part_id);
+            variant_part_entry.set(gimli::DW_AT_discr, AttributeValue::UnitRef(tag_member_id));
+        }
+
+        variant_part_id
+    }
+
+    /// Adds the `DW_TAG_variant` for the variant with the given index, whose fields are described
+    /// by `variant_struct_id`.
+    fn add_variant<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        variant_part_id: UnitEntryId,
+        layout: TyAndLayout<'tcx>,
+        variant_index: VariantIdx,
+        variant_name: &str,
+        variant_struct_id: UnitEntryId,
+    ) {
+        let variant_id = self.dwarf.unit.add(variant_part_id, gimli::DW_TAG_variant);
+        // The untagged variant of a niche-encoded enum is the default variant, which doesn't
+        // have a discriminant value.
+        if let Some(discr_value) = discriminant_value(tcx, layout, variant_index) {
+            let variant_entry = self.dwarf.unit.get_mut(variant_id);
+            variant_entry.set(gimli::DW_AT_discr_value, AttributeValue::Udata(discr_value as u64));
+        }
+        self.add_member(variant_id, variant_name, variant_struct_id, Size::ZERO, layout.align.pref);
+    }
+
+    /// Describes a coroutine like an enum with one variant per state, the same way as
+    /// `rustc_codegen_llvm` does for non-MSVC targets. Every variant holds the upvars and the
+    /// locals that are saved in that state.
+    fn coroutine_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        coroutine_type: Ty<'tcx>,
+        def_id: DefId,
+        args: GenericArgsRef<'tcx>,
+    ) -> UnitEntryId {
+        let scope = self.item_namespace(tcx, tcx.parent(def_id));
+        let name = type_names::compute_debuginfo_type_name(tcx, coroutine_type, false);
+        let layout = RevealAllLayoutCx(tcx).layout_of(coroutine_type);
+
+        let coroutine_type_id =
+            self.composite_type(scope, gimli::DW_TAG_structure_type, &name, layout);
+        type_dbg.type_map.insert(coroutine_type, coroutine_type_id);
+
+        let coroutine_layout = tcx.coroutine_layout(def_id, args.as_coroutine().kind_ty()).unwrap();
+        let upvar_tys = args.as_coroutine().upvar_tys();
+        let upvar_names = tcx.closure_saved_names_of_captured_variables(def_id);
+
+        let variant_part_id = self.variant_part(tcx, type_dbg, coroutine_type_id, layout);
+
+        for (variant_index, saved_locals) in coroutine_layout.variant_fields.iter_enumerated() {
+            let variant_layout = layout.for_variant(&RevealAllLayoutCx(tcx), variant_index);
+            let variant_name = ty::CoroutineArgs::variant_name(variant_index);
+            let variant_struct_id = self.composite_type(
+                coroutine_type_id,
+                gimli::DW_TAG_structure_type,
+                &variant_name,
+                variant_layout,
+            );
+
+            // The upvars are stored in the prefix shared by all variants.
+            for (i, (upvar_ty, upvar_name)) in upvar_tys.iter().zip(upvar_names).enumerate() {
+                let upvar_dw_ty = self.debug_type(tcx, type_dbg, upvar_ty);
+                self.add_member(
+                    variant_struct_id,
+                    upvar_name.as_str(),
+                    upvar_dw_ty,
+                    layout.fields.offset(i),
+                    RevealAllLayoutCx(tcx).layout_of(upvar_ty).align.pref,
+                );
+            }
+
+            for (field_index, &saved_local) in saved_locals.iter_enumerated() {
+                let field_layout =
+                    variant_layout.field(&RevealAllLayoutCx(tcx), field_index.as_usize());
+                let field_dw_ty = self.debug_type(tcx, type_dbg, field_layout.ty);
+                let field_name = match coroutine_layout.field_names[saved_local] {
+                    Some(name) => name.to_string(),
+                    None => format!("__{}", saved_local.as_usize()),
+                };
+                self.add_member(
+                    variant_struct_id,
+                    &field_name,
+                    field_dw_ty,
+                    variant_layout.fields.offset(field_index.as_usize()),
+                    field_layout.align.pref,
+                );
+            }
+
+            self.add_variant(
+                tcx,
+                variant_part_id,
+                layout,
+                variant_index,
+                &variant_name,
+                variant_struct_id,
+            );
+        }
+
+        coroutine_type_id
+    }
+
+    fn c_like_enum_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        scope: UnitEntryId,
+        name: &str,
+        layout: TyAndLayout<'tcx>,
+        adt_def: AdtDef<'tcx>,
+    ) -> UnitEntryId {
+        let discr_ty = layout.ty.discriminant_ty(tcx);
+        let discr_dw_ty = self.debug_type(tcx, type_dbg, discr_ty);
+        let discr_size = RevealAllLayoutCx(tcx).layout_of(discr_ty).size;
+
+        let enum_type_id = self.composite_type(scope, gimli::DW_TAG_enumeration_type, name, layout);
+        let enum_type_entry = self.dwarf.unit.get_mut(enum_type_id);
+        enum_type_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(discr_dw_ty));
+
+        for variant_index in adt_def.variants().indices() {
+            let discr = layout.ty.discriminant_for_variant(tcx, variant_index).unwrap();
+            let value = if discr_ty.is_signed() {
+                AttributeValue::Sdata(discr_size.sign_extend(discr.val) as i128 as i64)
+            } else {
+                AttributeValue::Udata(discr.val as u64)
+            };
+
+            let enumerator_id = self.dwarf.unit.add(enum_type_id, gimli::DW_TAG_enumerator);
+            let enumerator_entry = self.dwarf.unit.get_mut(enumerator_id);
+            enumerator_entry.set(
+                gimli::DW_AT_name,
+                AttributeValue::StringRef(
+                    self.dwarf.strings.add(adt_def.variant(variant_index).name.as_str()),
+                ),
+            );
+            enumerator_entry.set(gimli::DW_AT_const_value, value);
+        }
+
+        enum_type_id
+    }
+
+    fn closure_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        closure_type: Ty<'tcx>,
+        def_id: DefId,
+        upvar_tys: &'tcx ty::List<Ty<'tcx>>,
+    ) -> UnitEntryId {
+        let scope = self.item_namespace(tcx, tcx.parent(def_id));
+        let name = type_names::compute_debuginfo_type_name(tcx, closure_type, false);
+        let layout = RevealAllLayoutCx(tcx).layout_of(closure_type);
+
+        let closure_type_id =
+            self.composite_type(scope, gimli::DW_TAG_structure_type, &name, layout);
+        type_dbg.type_map.insert(closure_type, closure_type_id);
+
+        let upvar_names = tcx.closure_saved_names_of_captured_variables(def_id);
+        for (i, (upvar_ty, upvar_name)) in upvar_tys.iter().zip(upvar_names).enumerate() {
+            let upvar_dw_ty = self.debug_type(tcx, type_dbg, upvar_ty);
+            self.add_member(
+                closure_type_id,
+                upvar_name.as_str(),
+                upvar_dw_ty,
+                layout.fields.offset(i),
+                RevealAllLayoutCx(tcx).layout_of(upvar_ty).align.pref,
+            );
+        }
+
+        closure_type_id
+    }
+
+    fn add_variant_fields<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        parent: UnitEntryId,
+        variant_layout: TyAndLayout<'tcx>,
+        variant_def: &ty::VariantDef,
+        args: GenericArgsRef<'tcx>,
+    ) {
+        for (i, field) in variant_def.fields.iter().enumerate() {
+            let field_ty =
+                tcx.normalize_erasing_regions(ty::ParamEnv::reveal_all(), field.ty(tcx, args));
+            let field_dw_ty = self.debug_type(tcx, type_dbg, field_ty);
+            // Match the field names of tuples for tuple structs and tuple variants.
+            let field_name = if variant_def.ctor_kind() == Some(CtorKind::Fn) {
+                format!("__{i}")
+            } else {
+                field.name.to_string()
+            };
+            self.add_member(
+                parent,
+                &field_name,
+                field_dw_ty,
+                variant_layout.fields.offset(i),
+                RevealAllLayoutCx(tcx).layout_of(field_ty).align.pref,
+            );
+        }
+    }
+
+    fn composite_type(
+        &mut self,
+        scope: UnitEntryId,
+        tag: gimli::DwTag,
+        name: &str,
+        layout: TyAndLayout<'_>,
+    ) -> UnitEntryId {
+        let type_id = self.dwarf.unit.add(scope, tag);
+        let type_entry = self.dwarf.unit.get_mut(type_id);
+        type_entry.set(gimli::DW_AT_name, AttributeValue::StringRef(self.dwarf.strings.add(name)));
+        type_entry.set(gimli::DW_AT_byte_size, AttributeValue::Udata(layout.size.bytes()));
+        type_entry.set(gimli::DW_AT_alignment, AttributeValue::Udata(layout.align.pref.bytes()));
+
+        type_id
+    }
+
+    fn add_member(
+        &mut self,
+        parent: UnitEntryId,
+        name: &str,
+        dw_ty: UnitEntryId,
+        offset: Size,
+        align: Align,
+    ) -> UnitEntryId {
+        let member_id = self.dwarf.unit.add(parent, gimli::DW_TAG_member);
+        let member_entry = self.dwarf.unit.get_mut(member_id);
+        member_entry
+            .set(gimli::DW_AT_name, AttributeValue::StringRef(self.dwarf.strings.add(name)));
+        member_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(dw_ty));
+        member_entry.set(gimli::DW_AT_alignment, AttributeValue::Udata(align.bytes()));
+        member_entry.set(gimli::DW_AT_data_member_location, AttributeValue::Udata(offset.bytes()));
+
+        member_id
+    }
+
     fn placeholder_for_type<'tcx>(
         &mut self,
         tcx: TyCtxt<'tcx>,
@@ -202,3 +724,32 @@ fn placeholder_for_type<'tcx>(
         )
     }
 }
+
+/// Returns the value of the tag for the given variant, or `None` if the variant is not identified
+/// by a single tag value.
+fn discriminant_value<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    enum_layout: TyAndLayout<'tcx>,
+    variant_index: VariantIdx,
+) -> Option<u128> {
+    match enum_layout.variants {
+        Variants::Single { .. } => None,
+        Variants::Multiple { tag_encoding: TagEncoding::Direct, tag, .. } => {
+            let discr = enum_layout.ty.discriminant_for_variant(tcx, variant_index).unwrap();
+            Some(tag.size(&tcx).truncate(discr.val))
+        }
+        Variants::Multiple {
+            tag_encoding: TagEncoding::Niche { untagged_variant, ref niche_variants, niche_start },
+            tag,
+            ..
+        } => {
+            if variant_index == untagged_variant {
+                return None;
+            }
+            let value = (variant_index.as_u32() as u128)
+                .wrapping_sub(niche_variants.start().as_u32() as u128)
+                .wrapping_add(niche_start);
+            Some(tag.size(&tcx).truncate(value))
+        }
+    }
+}
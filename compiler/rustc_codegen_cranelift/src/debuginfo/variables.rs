@@ -0,0 +1,638 @@
+//! Debuginfo for the user variables of a function.
+//!
+//! The location of a variable is only known once the function has been compiled. Variables that
+//! are stored in SSA values are tracked through the value labels of Cranelift, which are turned
+//! into location lists. Variables that are stored in memory are described relative to the stack
+//! slot or the pointer holding their address. Variables that MIR optimizations split across
+//! several locals are described piece by piece, and variables that were replaced by a constant
+//! get the value of the constant.
+//!
+//! Variables are put into a `DW_TAG_lexical_block` for the MIR scope declaring them, so that
+//! debuggers only show them while the code of that scope is executed.
+
+use cranelift_codegen::binemit::CodeOffset;
+use cranelift_codegen::ir::ValueLabel;
+use cranelift_codegen::isa::TargetIsa;
+use cranelift_codegen::{CompiledCode, LabelValueLoc, MachSrcLoc, ValueLocRange};
+use gimli::write::{
+    Address, AttributeValue, Expression, Location, LocationList, Range, RangeList, UnitEntryId,
+};
+use gimli::{Register, RunTimeEndian};
+use rustc_index::bit_set::BitSet;
+use rustc_middle::mir::interpret::{AllocId, Scalar};
+
+use super::emit::{address_for_data, address_for_func};
+use super::{DebugContext, FunctionDebugContext, TypeDebugContext};
+use crate::constant::{global_alloc_base, GlobalAllocBase};
+use crate::pointer::PointerBase;
+use crate::prelude::*;
+use crate::value_and_place::CPlaceInner;
+
+pub(super) struct VariableDebugInfo {
+    entry_id: UnitEntryId,
+    location: Option<VariableLocation>,
+}
+
+/// The `DW_TAG_lexical_block` of a MIR scope that declares variables.
+pub(super) struct LexicalBlock {
+    entry_id: UnitEntryId,
+    /// The lexical block of the closest enclosing scope that has one.
+    parent: Option<usize>,
+}
+
+/// Where a variable is stored, as far as it is known before the function is compiled.
+enum VariableLocation {
+    /// In an SSA value.
+    Value(ValueLabel),
+    /// In two SSA values, one for each half of a scalar pair.
+    ValuePair { first: (ValueLabel, Size), second: (ValueLabel, Size), second_offset: Size },
+    /// In memory, at the address computed by applying `ops` to `base`.
+    Memory { base: MemoryBase, ops: Vec<MemoryOp> },
+    /// In several locals, each holding the part of the variable at the given offset and with the
+    /// given size. The pieces are sorted by offset.
+    Pieces(Vec<(Size, Size, VariableLocation)>),
+    /// Replaced by a constant, described by the given operations for the whole function.
+    Const(Vec<LocOp>),
+}
+
+enum MemoryBase {
+    StackSlot(StackSlot),
+    /// The address is stored in an SSA value.
+    Pointer(ValueLabel),
+}
+
+enum MemoryOp {
+    Offset(u64),
+    Deref,
+}
+
+/// An operation of a DWARF location expression. Locations are first built from these, as the
+/// locations of the pieces of a variable need to be concatenated for every code range.
+#[derive(Clone)]
+enum LocOp {
+    Reg(Register),
+    Breg(Register, i64),
+    Addr(Address),
+    Constu(u64),
+    Deref,
+    PlusUconst(u64),
+    StackValue,
+    ImplicitValue(Box<[u8]>),
+    Piece(u64),
+}
+
+/// Value labels of SSA variables use the index of the variable. Values holding the address of a
+/// local use the index of the local with the highest bit set, so that the two can't collide.
+fn pointer_value_label(local: Local) -> ValueLabel {
+    ValueLabel::from_u32(1 << 31 | local.as_u32())
+}
+
+/// Describes the user variables of the function in the debuginfo. This needs to be called after
+/// the body of the function has been codegened, as the locals are only assigned a place then.
+pub(crate) fn define_variables<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    type_dbg: &mut TypeDebugContext<'tcx>,
+) {
+    define_lexical_blocks(fx);
+
+    let mir = fx.mir;
+    // Variables that were split across several locals have one entry per piece, which are
+    // combined into a single variable.
+    let mut composites: FxIndexMap<_, (&VarDebugInfo<'tcx>, Vec<(Size, Size, VariableLocation)>)> =
+        FxIndexMap::default();
+    for var in &mir.var_debug_info {
+        let location = match &var.value {
+            VarDebugInfoContents::Place(place) => place_location(fx, *place),
+            VarDebugInfoContents::Const(constant) => Some(const_location(fx, constant)),
+        };
+        let Some(composite) = &var.composite else {
+            let ty = match &var.value {
+                VarDebugInfoContents::Place(place) => place.ty(mir, fx.tcx).ty,
+                VarDebugInfoContents::Const(constant) => constant.ty(),
+            };
+            let ty = fx.monomorphize(ty);
+            define_variable(fx, type_dbg, var, ty, location);
+            continue;
+        };
+
+        let ty = fx.monomorphize(composite.ty);
+        let key = (var.name, var.source_info.scope, var.argument_index, ty);
+        let (_, pieces) = composites.entry(key).or_insert_with(|| (var, vec![]));
+        // Pieces without a location are shown as optimized out.
+        if let Some(location) = location {
+            let (offset, size) = fragment_range(fx, composite);
+            push_piece(pieces, offset, size, location);
+        }
+    }
+
+    for ((_, _, _, ty), (var, mut pieces)) in composites {
+        pieces.sort_by_key(|&(offset, _, _)| offset);
+        let location =
+            if pieces.is_empty() { None } else { Some(VariableLocation::Pieces(pieces)) };
+        define_variable(fx, type_dbg, var, ty, location);
+    }
+}
+
+fn define_variable<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    type_dbg: &mut TypeDebugContext<'tcx>,
+    var: &VarDebugInfo<'tcx>,
+    ty: Ty<'tcx>,
+    location: Option<VariableLocation>,
+) {
+    let debug_context = fx.cx.debug_context.as_mut().unwrap();
+    let func_debug_cx = fx.func_debug_cx.as_mut().unwrap();
+
+    let dw_ty = debug_context.debug_type(fx.tcx, type_dbg, ty);
+    let (file_id, line, _column) =
+        debug_context.get_span_loc(fx.tcx, fx.mir.span, var.source_info.span);
+
+    let tag = if var.argument_index.is_some() {
+        gimli::DW_TAG_formal_parameter
+    } else {
+        gimli::DW_TAG_variable
+    };
+    let parent = match func_debug_cx.scope_blocks[var.source_info.scope] {
+        Some(block) => func_debug_cx.lexical_blocks[block].entry_id,
+        None => func_debug_cx.entry_id,
+    };
+    let entry_id = debug_context.dwarf.unit.add(parent, tag);
+    let entry = debug_context.dwarf.unit.get_mut(entry_id);
+    entry.set(
+        gimli::DW_AT_name,
+        AttributeValue::StringRef(debug_context.dwarf.strings.add(var.name.as_str())),
+    );
+    entry.set(gimli::DW_AT_decl_file, AttributeValue::FileIndex(Some(file_id)));
+    entry.set(gimli::DW_AT_decl_line, AttributeValue::Udata(line));
+    entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(dw_ty));
+
+    func_debug_cx.variables.push(VariableDebugInfo { entry_id, location });
+}
+
+/// Creates a lexical block for every MIR scope other than the outermost one that declares
+/// variables. Their code ranges are set once the function has been compiled. Scopes of inlined
+/// functions are treated like any other scope.
+fn define_lexical_blocks(fx: &mut FunctionCx<'_, '_, '_>) {
+    let mir = fx.mir;
+    let debug_context = fx.cx.debug_context.as_mut().unwrap();
+    let func_debug_cx = fx.func_debug_cx.as_mut().unwrap();
+
+    let mut declares_variables = BitSet::new_empty(mir.source_scopes.len());
+    for var in &mir.var_debug_info {
+        declares_variables.insert(var.source_info.scope);
+    }
+
+    // Parent scopes always come before their children.
+    for (scope, scope_data) in mir.source_scopes.iter_enumerated() {
+        let Some(parent_scope) = scope_data.parent_scope else {
+            func_debug_cx.scope_blocks.push(None);
+            continue;
+        };
+        let parent_block = func_debug_cx.scope_blocks[parent_scope];
+        let block = if declares_variables.contains(scope) {
+            let parent_entry = match parent_block {
+                Some(block) => func_debug_cx.lexical_blocks[block].entry_id,
+                None => func_debug_cx.entry_id,
+            };
+            let entry_id = debug_context.dwarf.unit.add(parent_entry, gimli::DW_TAG_lexical_block);
+            func_debug_cx.lexical_blocks.push(LexicalBlock { entry_id, parent: parent_block });
+            Some(func_debug_cx.lexical_blocks.len() - 1)
+        } else {
+            parent_block
+        };
+        func_debug_cx.scope_blocks.push(block);
+    }
+}
+
+/// Returns the offset and size of the part of a composite variable that a fragment describes.
+fn fragment_range<'tcx>(
+    fx: &FunctionCx<'_, '_, 'tcx>,
+    fragment: &VarDebugInfoFragment<'tcx>,
+) -> (Size, Size) {
+    let mut layout = fx.layout_of(fx.monomorphize(fragment.ty));
+    let mut offset = Size::ZERO;
+    for elem in &fragment.projection {
+        let ProjectionElem::Field(field, _) = *elem else {
+            bug!("unexpected projection in debuginfo fragment: {elem:?}");
+        };
+        offset += layout.fields.offset(field.index());
+        layout = layout.field(fx, field.index());
+    }
+    (offset, layout.size)
+}
+
+/// Adds the location of the part of a variable at `offset` to `pieces`, splitting it into the
+/// locations of its own pieces if it has any.
+fn push_piece(
+    pieces: &mut Vec<(Size, Size, VariableLocation)>,
+    offset: Size,
+    size: Size,
+    location: VariableLocation,
+) {
+    match location {
+        VariableLocation::ValuePair { first, second, second_offset } => {
+            pieces.push((offset, first.1, VariableLocation::Value(first.0)));
+            pieces.push((offset + second_offset, second.1, VariableLocation::Value(second.0)));
+        }
+        VariableLocation::Pieces(inner) => {
+            for (inner_offset, inner_size, inner_location) in inner {
+                push_piece(pieces, offset + inner_offset, inner_size, inner_location);
+            }
+        }
+        location => pieces.push((offset, size, location)),
+    }
+}
+
+fn const_location<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    constant: &ConstOperand<'tcx>,
+) -> VariableLocation {
+    let (value, _ty) = crate::constant::eval_mir_constant(fx, constant);
+    let pointer_size = fx.tcx.data_layout.pointer_size.bytes();
+    let ops = match value {
+        ConstValue::ZeroSized => vec![LocOp::ImplicitValue(Box::new([]))],
+        ConstValue::Scalar(Scalar::Int(int)) => {
+            let endian = fx.cx.debug_context.as_ref().unwrap().endian;
+            let bytes = int.to_bits(int.size()).unwrap();
+            let bytes = match endian {
+                RunTimeEndian::Little => bytes.to_le_bytes()[..int.size().bytes_usize()].into(),
+                RunTimeEndian::Big => bytes.to_be_bytes()[16 - int.size().bytes_usize()..].into(),
+            };
+            vec![LocOp::ImplicitValue(bytes)]
+        }
+        ConstValue::Scalar(Scalar::Ptr(ptr, _size)) => {
+            let (prov, offset) = ptr.into_parts();
+            let mut ops = global_alloc_address(fx, prov.alloc_id(), offset);
+            ops.push(LocOp::StackValue);
+            ops
+        }
+        // The value is stored in memory.
+        ConstValue::Indirect { alloc_id, offset } => global_alloc_address(fx, alloc_id, offset),
+        ConstValue::Slice { data, meta } => {
+            let alloc_id = fx.tcx.reserve_and_set_memory_alloc(data);
+            let mut ops = global_alloc_address(fx, alloc_id, Size::ZERO);
+            ops.extend([
+                LocOp::StackValue,
+                LocOp::Piece(pointer_size),
+                LocOp::Constu(meta),
+                LocOp::StackValue,
+                LocOp::Piece(pointer_size),
+            ]);
+            ops
+        }
+    };
+    VariableLocation::Const(ops)
+}
+
+fn global_alloc_address(
+    fx: &mut FunctionCx<'_, '_, '_>,
+    alloc_id: AllocId,
+    offset: Size,
+) -> Vec<LocOp> {
+    let address = match global_alloc_base(fx, alloc_id) {
+        GlobalAllocBase::Data(data_id) => address_for_data(data_id),
+        GlobalAllocBase::Func(func_id) => address_for_func(func_id),
+    };
+    let mut ops = vec![LocOp::Addr(address)];
+    if offset != Size::ZERO {
+        ops.push(LocOp::PlusUconst(offset.bytes()));
+    }
+    ops
+}
+
+fn place_location<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    place: Place<'tcx>,
+) -> Option<VariableLocation> {
+    let cplace = fx.local_map[place.local];
+    let mut layout = cplace.layout();
+    let mut location = match *cplace.inner() {
+        CPlaceInner::Var(_local, var) => VariableLocation::Value(ValueLabel::new(var.index())),
+        CPlaceInner::VarPair(_local, var1, var2) => {
+            let Abi::ScalarPair(a, b) = layout.abi else { unreachable!() };
+            let a_size = a.size(fx);
+            VariableLocation::ValuePair {
+                first: (ValueLabel::new(var1.index()), a_size),
+                second: (ValueLabel::new(var2.index()), b.size(fx)),
+                second_offset: a_size.align_to(b.align(fx).abi),
+            }
+        }
+        // The metadata of unsized locals is not part of the variable.
+        CPlaceInner::Addr(ptr, _) => {
+            let (base, offset) = ptr.debug_base_and_offset();
+            let base = match base {
+                PointerBase::Stack(stack_slot) => MemoryBase::StackSlot(stack_slot),
+                PointerBase::Addr(addr) => {
+                    let label = pointer_value_label(place.local);
+                    fx.bcx.set_val_label(addr, label);
+                    MemoryBase::Pointer(label)
+                }
+                // Zero-sized locals don't have a location.
+                PointerBase::Dangling(_) => return None,
+            };
+            let offset = u64::try_from(i64::from(offset)).ok()?;
+            VariableLocation::Memory { base, ops: vec![MemoryOp::Offset(offset)] }
+        }
+    };
+
+    for elem in place.projection {
+        let (field_offset, field_layout) = match elem {
+            ProjectionElem::Deref => {
+                let pointee = layout.ty.builtin_deref(true).unwrap().ty;
+                location = match location {
+                    VariableLocation::Value(label)
+                    | VariableLocation::ValuePair { first: (label, _), .. } => {
+                        VariableLocation::Memory { base: MemoryBase::Pointer(label), ops: vec![] }
+                    }
+                    VariableLocation::Memory { base, mut ops } => {
+                        ops.push(MemoryOp::Deref);
+                        VariableLocation::Memory { base, ops }
+                    }
+                    VariableLocation::Pieces(_) | VariableLocation::Const(_) => unreachable!(),
+                };
+                layout = fx.layout_of(pointee);
+                continue;
+            }
+            ProjectionElem::Field(field, _) => {
+                (layout.fields.offset(field.index()), layout.field(&*fx, field.index()))
+            }
+            ProjectionElem::ConstantIndex { offset, min_length: _, from_end: false } => {
+                (layout.fields.offset(offset as usize), layout.field(&*fx, offset as usize))
+            }
+            ProjectionElem::Downcast(_, variant) => {
+                layout = layout.for_variant(&*fx, variant);
+                continue;
+            }
+            _ => return None,
+        };
+
+        location = match location {
+            VariableLocation::Memory { base, mut ops } => {
+                ops.push(MemoryOp::Offset(field_offset.bytes()));
+                VariableLocation::Memory { base, ops }
+            }
+            // Projecting to a field that covers the whole value, e.g. of a newtype.
+            location if field_offset == Size::ZERO && field_layout.size == layout.size => location,
+            VariableLocation::ValuePair { first: (label, size), .. }
+                if field_offset == Size::ZERO && field_layout.size == size =>
+            {
+                VariableLocation::Value(label)
+            }
+            VariableLocation::ValuePair { second: (label, size), second_offset, .. }
+                if field_offset == second_offset && field_layout.size == size =>
+            {
+                VariableLocation::Value(label)
+            }
+            _ => return None,
+        };
+        layout = field_layout;
+    }
+
+    Some(location)
+}
+
+impl FunctionDebugContext {
+    /// Sets the code ranges of the lexical blocks now that the function has been compiled. The
+    /// code of a scope is also part of the lexical blocks of all scopes enclosing it.
+    pub(super) fn define_lexical_block_ranges(
+        &self,
+        debug_context: &mut DebugContext,
+        func_id: FuncId,
+        context: &Context,
+    ) {
+        if self.lexical_blocks.is_empty() {
+            return;
+        }
+
+        let mut block_ranges: Vec<Vec<(CodeOffset, CodeOffset)>> =
+            (0..self.lexical_blocks.len()).map(|_| vec![]).collect();
+        let mcr = context.compiled_code().unwrap();
+        for &MachSrcLoc { start, end, loc } in mcr.buffer.get_srclocs_sorted() {
+            if loc.is_default() {
+                continue;
+            }
+            let (_file_id, _line, _column, scope) = self.source_loc_set[loc.bits() as usize];
+            let mut block = self.scope_blocks[scope];
+            while let Some(index) = block {
+                let ranges = &mut block_ranges[index];
+                match ranges.last_mut() {
+                    Some((_, last_end)) if *last_end == start => *last_end = end,
+                    _ => ranges.push((start, end)),
+                }
+                block = self.lexical_blocks[index].parent;
+            }
+        }
+
+        let func_address = address_for_func(func_id);
+        let Address::Symbol { symbol, addend: _ } = func_address else { unreachable!() };
+        for (block, ranges) in self.lexical_blocks.iter().zip(block_ranges) {
+            if ranges.is_empty() {
+                continue;
+            }
+            let range_list = RangeList(
+                ranges
+                    .into_iter()
+                    .map(|(start, end)| Range::StartEnd {
+                        begin: Address::Symbol { symbol, addend: i64::from(start) },
+                        end: Address::Symbol { symbol, addend: i64::from(end) },
+                    })
+                    .collect(),
+            );
+            let range_list_id = debug_context.dwarf.unit.ranges.add(range_list);
+            let entry = debug_context.dwarf.unit.get_mut(block.entry_id);
+            entry.set(gimli::DW_AT_ranges, AttributeValue::RangeListRef(range_list_id));
+        }
+    }
+
+    /// Sets the location of all variables now that the function has been compiled.
+    pub(super) fn define_variable_locations(
+        &self,
+        debug_context: &mut DebugContext,
+        isa: &dyn TargetIsa,
+        func_id: FuncId,
+        context: &Context,
+        func_end: CodeOffset,
+    ) {
+        let stack_pointer_register = debug_context.stack_pointer_register;
+        let translator = LocationTranslator {
+            isa,
+            // The stack pointer is not known for all architectures.
+            stack_pointer_register: (stack_pointer_register != Register(u16::MAX))
+                .then_some(stack_pointer_register),
+            compiled_code: context.compiled_code().unwrap(),
+            func_end,
+        };
+
+        for variable in &self.variables {
+            let Some(location) = &variable.location else { continue };
+
+            let value = match &translator.ranges(location)[..] {
+                [] => continue,
+                // Locations that are valid in the whole function, like those of stack slots and
+                // constants, don't need a location list.
+                [(0, end, ops)] if *end == func_end => AttributeValue::Exprloc(to_expression(ops)),
+                ranges => {
+                    let func_address = address_for_func(func_id);
+                    let Address::Symbol { symbol, addend: _ } = func_address else {
+                        unreachable!()
+                    };
+                    let location_list = LocationList(
+                        ranges
+                            .iter()
+                            .map(|(start, end, ops)| Location::StartEnd {
+                                begin: Address::Symbol { symbol, addend: i64::from(*start) },
+                                end: Address::Symbol { symbol, addend: i64::from(*end) },
+                                data: to_expression(ops),
+                            })
+                            .collect(),
+                    );
+                    AttributeValue::LocationListRef(
+                        debug_context.dwarf.unit.locations.add(location_list),
+                    )
+                }
+            };
+            let entry = debug_context.dwarf.unit.get_mut(variable.entry_id);
+            entry.set(gimli::DW_AT_location, value);
+        }
+    }
+}
+
+struct LocationTranslator<'a> {
+    isa: &'a dyn TargetIsa,
+    stack_pointer_register: Option<Register>,
+    compiled_code: &'a CompiledCode,
+    func_end: CodeOffset,
+}
+
+impl LocationTranslator<'_> {
+    /// Returns the code ranges in which the location of a variable is known, together with its
+    /// location in each of them.
+    fn ranges(&self, location: &VariableLocation) -> Vec<(CodeOffset, CodeOffset, Vec<LocOp>)> {
+        match location {
+            VariableLocation::Value(label) => self
+                .value_ranges(*label)
+                .iter()
+                .filter_map(|range| Some((range.start, range.end, self.value(range.loc)?)))
+                .collect(),
+            VariableLocation::ValuePair { first, second, second_offset } => self.pieces_ranges(&[
+                (Size::ZERO, first.1, VariableLocation::Value(first.0)),
+                (*second_offset, second.1, VariableLocation::Value(second.0)),
+            ]),
+            VariableLocation::Memory { base: MemoryBase::StackSlot(stack_slot), ops } => {
+                let Some(stack_pointer_register) = self.stack_pointer_register else {
+                    return vec![];
+                };
+                // Stack slots are live for the whole function.
+                let offset = self.compiled_code.sized_stackslot_offsets[*stack_slot];
+                let mut loc_ops = vec![LocOp::Breg(stack_pointer_register, i64::from(offset))];
+                push_memory_ops(&mut loc_ops, ops);
+                vec![(0, self.func_end, loc_ops)]
+            }
+            VariableLocation::Memory { base: MemoryBase::Pointer(label), ops } => self
+                .value_ranges(*label)
+                .iter()
+                .filter_map(|range| {
+                    let mut loc_ops = self.address(range.loc)?;
+                    push_memory_ops(&mut loc_ops, ops);
+                    Some((range.start, range.end, loc_ops))
+                })
+                .collect(),
+            VariableLocation::Pieces(pieces) => self.pieces_ranges(pieces),
+            VariableLocation::Const(ops) => vec![(0, self.func_end, ops.clone())],
+        }
+    }
+
+    /// Returns the code ranges in which the locations of all pieces are known at the same time.
+    fn pieces_ranges(
+        &self,
+        pieces: &[(Size, Size, VariableLocation)],
+    ) -> Vec<(CodeOffset, CodeOffset, Vec<LocOp>)> {
+        let mut ranges = vec![(0, self.func_end, vec![])];
+        let mut covered = Size::ZERO;
+        for (offset, size, location) in pieces {
+            // Overlapping pieces can't be described.
+            if *offset < covered {
+                return vec![];
+            }
+            let piece_ranges = self.ranges(location);
+            let mut next_ranges = vec![];
+            for (start, end, ops) in &ranges {
+                for (piece_start, piece_end, piece_ops) in &piece_ranges {
+                    let start = (*start).max(*piece_start);
+                    let end = (*end).min(*piece_end);
+                    if start >= end {
+                        continue;
+                    }
+                    let mut ops = ops.clone();
+                    // Parts without a location, like padding, are described by empty pieces.
+                    if *offset > covered {
+                        ops.push(LocOp::Piece((*offset - covered).bytes()));
+                    }
+                    ops.extend(piece_ops.iter().cloned());
+                    ops.push(LocOp::Piece(size.bytes()));
+                    next_ranges.push((start, end, ops));
+                }
+            }
+            ranges = next_ranges;
+            covered = *offset + *size;
+        }
+        ranges
+    }
+
+    fn value_ranges(&self, label: ValueLabel) -> &[ValueLocRange] {
+        self.compiled_code.value_labels_ranges.get(&label).map_or(&[][..], |ranges| &ranges[..])
+    }
+
+    /// Returns the location of a value.
+    fn value(&self, loc: LabelValueLoc) -> Option<Vec<LocOp>> {
+        Some(match loc {
+            LabelValueLoc::Reg(reg) => {
+                vec![LocOp::Reg(Register(self.isa.map_regalloc_reg_to_dwarf(reg).ok()?))]
+            }
+            // Spilled values are stored in memory relative to the stack pointer.
+            LabelValueLoc::SPOffset(offset) => {
+                vec![LocOp::Breg(self.stack_pointer_register?, offset)]
+            }
+        })
+    }
+
+    /// Returns the operations computing the address stored in a value.
+    fn address(&self, loc: LabelValueLoc) -> Option<Vec<LocOp>> {
+        Some(match loc {
+            LabelValueLoc::Reg(reg) => {
+                vec![LocOp::Breg(Register(self.isa.map_regalloc_reg_to_dwarf(reg).ok()?), 0)]
+            }
+            LabelValueLoc::SPOffset(offset) => {
+                vec![LocOp::Breg(self.stack_pointer_register?, offset), LocOp::Deref]
+            }
+        })
+    }
+}
+
+fn push_memory_ops(loc_ops: &mut Vec<LocOp>, ops: &[MemoryOp]) {
+    for op in ops {
+        match *op {
+            MemoryOp::Offset(0) => {}
+            MemoryOp::Offset(offset) => loc_ops.push(LocOp::PlusUconst(offset)),
+            MemoryOp::Deref => loc_ops.push(LocOp::Deref),
+        }
+    }
+}
+
+fn to_expression(ops: &[LocOp]) -> Expression {
+    let mut expr = Expression::new();
+    for op in ops {
+        match *op {
+            LocOp::Reg(reg) => expr.op_reg(reg),
+            LocOp::Breg(reg, offset) => expr.op_breg(reg, offset),
+            LocOp::Addr(address) => expr.op_addr(address),
+            LocOp::Constu(value) => expr.op_constu(value),
+            LocOp::Deref => expr.op_deref(),
+            LocOp::PlusUconst(value) => expr.op_plus_uconst(value),
+            LocOp::StackValue => expr.op(gimli::DW_OP_stack_value),
+            LocOp::ImplicitValue(ref data) => expr.op_implicit_value(data.clone()),
+            LocOp::Piece(size) => expr.op_piece(size),
+        }
+    }
+    expr
+}
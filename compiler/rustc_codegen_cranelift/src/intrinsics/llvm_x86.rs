@@ -229,7 +229,10 @@ pub(crate) fn codegen_x86_llvm_intrinsic_call<'tcx>(
             );
         }
 
-        "llvm.x86.sse.cmp.ps" | "llvm.x86.sse2.cmp.pd" => {
+        "llvm.x86.sse.cmp.ps"
+        | "llvm.x86.sse2.cmp.pd"
+        | "llvm.x86.avx.cmp.ps.256"
+        | "llvm.x86.avx.cmp.pd.256" => {
             let (x, y, kind) = match args {
                 [x, y, kind] => (x, y, kind),
                 _ => bug!("wrong number of args for intrinsic {intrinsic}"),
@@ -241,28 +244,29 @@ pub(crate) fn codegen_x86_llvm_intrinsic_call<'tcx>(
                 Operand::Copy(_) | Operand::Move(_) => unreachable!("{kind:?}"),
             };
 
-            let flt_cc = match kind
+            let kind = kind
                 .try_to_bits(Size::from_bytes(1))
                 .unwrap_or_else(|| panic!("kind not scalar: {:?}", kind))
                 .try_into()
-                .unwrap()
-            {
-                _CMP_EQ_OQ | _CMP_EQ_OS => FloatCC::Equal,
-                _CMP_LT_OS | _CMP_LT_OQ => FloatCC::LessThan,
-                _CMP_LE_OS | _CMP_LE_OQ => FloatCC::LessThanOrEqual,
-                _CMP_UNORD_Q | _CMP_UNORD_S => FloatCC::Unordered,
-                _CMP_NEQ_UQ | _CMP_NEQ_US => FloatCC::NotEqual,
-                _CMP_NLT_US | _CMP_NLT_UQ => FloatCC::UnorderedOrGreaterThanOrEqual,
-                _CMP_NLE_US | _CMP_NLE_UQ => FloatCC::UnorderedOrGreaterThan,
-                _CMP_ORD_Q | _CMP_ORD_S => FloatCC::Ordered,
-                _CMP_EQ_UQ | _CMP_EQ_US => FloatCC::UnorderedOrEqual,
-                _CMP_NGE_US | _CMP_NGE_UQ => FloatCC::UnorderedOrLessThan,
-                _CMP_NGT_US | _CMP_NGT_UQ => FloatCC::UnorderedOrLessThanOrEqual,
-                _CMP_FALSE_OQ | _CMP_FALSE_OS => todo!(),
-                _CMP_NEQ_OQ | _CMP_NEQ_OS => FloatCC::OrderedNotEqual,
-                _CMP_GE_OS | _CMP_GE_OQ => FloatCC::GreaterThanOrEqual,
-                _CMP_GT_OS | _CMP_GT_OQ => FloatCC::GreaterThan,
-                _CMP_TRUE_UQ | _CMP_TRUE_US => todo!(),
+                .unwrap();
+            // FALSE and TRUE don't compare the lanes. Only the 256-bit variants accept them.
+            let flt_cc = match kind {
+                _CMP_EQ_OQ | _CMP_EQ_OS => Some(FloatCC::Equal),
+                _CMP_LT_OS | _CMP_LT_OQ => Some(FloatCC::LessThan),
+                _CMP_LE_OS | _CMP_LE_OQ => Some(FloatCC::LessThanOrEqual),
+                _CMP_UNORD_Q | _CMP_UNORD_S => Some(FloatCC::Unordered),
+                _CMP_NEQ_UQ | _CMP_NEQ_US => Some(FloatCC::NotEqual),
+                _CMP_NLT_US | _CMP_NLT_UQ => Some(FloatCC::UnorderedOrGreaterThanOrEqual),
+                _CMP_NLE_US | _CMP_NLE_UQ => Some(FloatCC::UnorderedOrGreaterThan),
+                _CMP_ORD_Q | _CMP_ORD_S => Some(FloatCC::Ordered),
+                _CMP_EQ_UQ | _CMP_EQ_US => Some(FloatCC::UnorderedOrEqual),
+                _CMP_NGE_US | _CMP_NGE_UQ => Some(FloatCC::UnorderedOrLessThan),
+                _CMP_NGT_US | _CMP_NGT_UQ => Some(FloatCC::UnorderedOrLessThanOrEqual),
+                _CMP_FALSE_OQ | _CMP_FALSE_OS => None,
+                _CMP_NEQ_OQ | _CMP_NEQ_OS => Some(FloatCC::OrderedNotEqual),
+                _CMP_GE_OS | _CMP_GE_OQ => Some(FloatCC::GreaterThanOrEqual),
+                _CMP_GT_OS | _CMP_GT_OQ => Some(FloatCC::GreaterThan),
+                _CMP_TRUE_UQ | _CMP_TRUE_US => None,
 
                 kind => unreachable!("kind {:?}", kind),
             };
@@ -334,8 +338,12 @@ pub(crate) fn codegen_x86_llvm_intrinsic_call<'tcx>(
             const _CMP_TRUE_US: i32 = 0x1f;
 
             simd_pair_for_each_lane(fx, x, y, ret, &|fx, lane_ty, res_lane_ty, x_lane, y_lane| {
-                let res_lane = match lane_ty.kind() {
-                    ty::Float(_) => fx.bcx.ins().fcmp(flt_cc, x_lane, y_lane),
+                let res_lane = match (lane_ty.kind(), flt_cc) {
+                    (ty::Float(_), Some(flt_cc)) => fx.bcx.ins().fcmp(flt_cc, x_lane, y_lane),
+                    (ty::Float(_), None) => {
+                        let always = matches!(kind, _CMP_TRUE_UQ | _CMP_TRUE_US);
+                        fx.bcx.ins().iconst(types::I8, i64::from(always))
+                    }
                     _ => unreachable!("{:?}", lane_ty),
                 };
                 bool_to_zero_or_max_uint(fx, res_lane_ty, res_lane)
@@ -508,19 +516,91 @@ fn select4(
                 },
             );
         }
-        "llvm.x86.sse2.psra.w" => {
+        "llvm.x86.sse2.psll.w"
+        | "llvm.x86.sse2.psll.d"
+        | "llvm.x86.sse2.psll.q"
+        | "llvm.x86.sse2.psrl.w"
+        | "llvm.x86.sse2.psrl.d"
+        | "llvm.x86.sse2.psrl.q"
+        | "llvm.x86.sse2.psra.w"
+        | "llvm.x86.sse2.psra.d"
+        | "llvm.x86.avx2.psll.w"
+        | "llvm.x86.avx2.psll.d"
+        | "llvm.x86.avx2.psll.q"
+        | "llvm.x86.avx2.psrl.w"
+        | "llvm.x86.avx2.psrl.d"
+        | "llvm.x86.avx2.psrl.q"
+        | "llvm.x86.avx2.psra.w"
+        | "llvm.x86.avx2.psra.d" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sll_epi16&ig_expand=6297
             intrinsic_args!(fx, args => (a, count); intrinsic);
 
-            let count_lane = count.force_stack(fx).0.load(fx, types::I64, MemFlags::trusted());
-            let lane_ty = fx.clif_type(a.layout().ty.simd_size_and_type(fx.tcx).1).unwrap();
-            let max_count = fx.bcx.ins().iconst(types::I64, i64::from(lane_ty.bits() - 1));
-            let saturated_count = fx.bcx.ins().umin(count_lane, max_count);
+            // Only the low 64 bits of the count vector are used
+            let count = count.force_stack(fx).0.load(fx, types::I64, MemFlags::trusted());
+            let kind = ShiftKind::from_intrinsic(intrinsic);
+
+            // FIXME use vector instructions when possible
+            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, a_lane| {
+                shift_lane(fx, kind, a_lane, count)
+            });
+        }
+
+        "llvm.x86.sse2.pslli.w"
+        | "llvm.x86.sse2.pslli.d"
+        | "llvm.x86.sse2.pslli.q"
+        | "llvm.x86.sse2.psrli.w"
+        | "llvm.x86.sse2.psrli.d"
+        | "llvm.x86.sse2.psrli.q"
+        | "llvm.x86.sse2.psrai.w"
+        | "llvm.x86.sse2.psrai.d"
+        | "llvm.x86.avx2.pslli.w"
+        | "llvm.x86.avx2.pslli.d"
+        | "llvm.x86.avx2.pslli.q"
+        | "llvm.x86.avx2.psrli.w"
+        | "llvm.x86.avx2.psrli.d"
+        | "llvm.x86.avx2.psrli.q"
+        | "llvm.x86.avx2.psrai.w"
+        | "llvm.x86.avx2.psrai.d" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_slli_epi16&ig_expand=6316
+            intrinsic_args!(fx, args => (a, imm8); intrinsic);
+
+            let count = imm8.load_scalar(fx);
+            let count = fx.bcx.ins().uextend(types::I64, count);
+            let kind = ShiftKind::from_intrinsic(intrinsic);
 
             // FIXME use vector instructions when possible
             simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, a_lane| {
-                fx.bcx.ins().sshr(a_lane, saturated_count)
+                shift_lane(fx, kind, a_lane, count)
             });
         }
+
+        "llvm.x86.avx2.psllv.d"
+        | "llvm.x86.avx2.psllv.d.256"
+        | "llvm.x86.avx2.psllv.q"
+        | "llvm.x86.avx2.psllv.q.256"
+        | "llvm.x86.avx2.psrlv.d"
+        | "llvm.x86.avx2.psrlv.d.256"
+        | "llvm.x86.avx2.psrlv.q"
+        | "llvm.x86.avx2.psrlv.q.256"
+        | "llvm.x86.avx2.psrav.d"
+        | "llvm.x86.avx2.psrav.d.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sllv_epi32&ig_expand=6344
+            intrinsic_args!(fx, args => (a, count); intrinsic);
+
+            let kind = ShiftKind::from_intrinsic(intrinsic);
+
+            // FIXME use vector instructions when possible
+            simd_pair_for_each_lane(
+                fx,
+                a,
+                count,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, a_lane, count_lane| {
+                    shift_lane(fx, kind, a_lane, count_lane)
+                },
+            );
+        }
+
         "llvm.x86.sse2.psad.bw" | "llvm.x86.avx2.psad.bw" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sad_epu8&ig_expand=5770
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_sad_epu8&ig_expand=5771
@@ -1326,7 +1406,12 @@ fn select4(
             );
         }
 
-        "llvm.x86.avx.ptestz.256" => {
+        "llvm.x86.sse41.ptestz"
+        | "llvm.x86.sse41.ptestc"
+        | "llvm.x86.sse41.ptestnzc"
+        | "llvm.x86.avx.ptestz.256"
+        | "llvm.x86.avx.ptestc.256"
+        | "llvm.x86.avx.ptestnzc.256" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_testz_si256&ig_expand=6945
             intrinsic_args!(fx, args => (a, b); intrinsic);
 
@@ -1336,27 +1421,30 @@ fn select4(
             let (lane_count, lane_ty) = layout.ty.simd_size_and_type(fx.tcx);
             assert_eq!(lane_ty, fx.tcx.types.i64);
             assert_eq!(ret.layout().ty, fx.tcx.types.i32);
-            assert_eq!(lane_count, 4);
-
-            let a_lane0 = a.value_lane(fx, 0).load_scalar(fx);
-            let a_lane1 = a.value_lane(fx, 1).load_scalar(fx);
-            let a_lane2 = a.value_lane(fx, 2).load_scalar(fx);
-            let a_lane3 = a.value_lane(fx, 3).load_scalar(fx);
-            let b_lane0 = b.value_lane(fx, 0).load_scalar(fx);
-            let b_lane1 = b.value_lane(fx, 1).load_scalar(fx);
-            let b_lane2 = b.value_lane(fx, 2).load_scalar(fx);
-            let b_lane3 = b.value_lane(fx, 3).load_scalar(fx);
-
-            let zero0 = fx.bcx.ins().band(a_lane0, b_lane0);
-            let zero1 = fx.bcx.ins().band(a_lane1, b_lane1);
-            let zero2 = fx.bcx.ins().band(a_lane2, b_lane2);
-            let zero3 = fx.bcx.ins().band(a_lane3, b_lane3);
-
-            let all_zero0 = fx.bcx.ins().bor(zero0, zero1);
-            let all_zero1 = fx.bcx.ins().bor(zero2, zero3);
-            let all_zero = fx.bcx.ins().bor(all_zero0, all_zero1);
-
-            let res = fx.bcx.ins().icmp_imm(IntCC::Equal, all_zero, 0);
+
+            // ZF is set when `a & b` is zero, CF is set when `!a & b` is zero.
+            let mut and = fx.bcx.ins().iconst(types::I64, 0);
+            let mut and_not = fx.bcx.ins().iconst(types::I64, 0);
+            for lane_idx in 0..lane_count {
+                let a_lane = a.value_lane(fx, lane_idx).load_scalar(fx);
+                let b_lane = b.value_lane(fx, lane_idx).load_scalar(fx);
+                let and_lane = fx.bcx.ins().band(a_lane, b_lane);
+                let not_a_lane = fx.bcx.ins().bnot(a_lane);
+                let and_not_lane = fx.bcx.ins().band(not_a_lane, b_lane);
+                and = fx.bcx.ins().bor(and, and_lane);
+                and_not = fx.bcx.ins().bor(and_not, and_not_lane);
+            }
+
+            let zf = fx.bcx.ins().icmp_imm(IntCC::Equal, and, 0);
+            let cf = fx.bcx.ins().icmp_imm(IntCC::Equal, and_not, 0);
+            let res = match intrinsic {
+                "llvm.x86.sse41.ptestz" | "llvm.x86.avx.ptestz.256" => zf,
+                "llvm.x86.sse41.ptestc" | "llvm.x86.avx.ptestc.256" => cf,
+                _ => {
+                    let either = fx.bcx.ins().bor(zf, cf);
+                    fx.bcx.ins().bxor_imm(either, 1)
+                }
+            };
             let res = CValue::by_val(
                 fx.bcx.ins().uextend(types::I32, res),
                 fx.layout_of(fx.tcx.types.i32),
@@ -1364,6 +1452,438 @@ fn select4(
             ret.write_cvalue(fx, res);
         }
 
+        "llvm.x86.sse.sfence" | "llvm.x86.sse2.lfence" | "llvm.x86.sse2.mfence" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_mfence&ig_expand=4473
+            // Cranelift only has a full fence, which is stronger than required for sfence and
+            // lfence.
+            fx.bcx.ins().fence();
+        }
+
+        "llvm.x86.sse.max.ss"
+        | "llvm.x86.sse.min.ss"
+        | "llvm.x86.sse2.max.sd"
+        | "llvm.x86.sse2.min.sd" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_max_ss&ig_expand=4365
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            assert_eq!(a.layout(), b.layout());
+            assert_eq!(a.layout(), ret.layout());
+            let (_, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let ret_lane_layout = fx.layout_of(lane_ty);
+
+            ret.write_cvalue(fx, a);
+
+            let a_lane = a.value_lane(fx, 0).load_scalar(fx);
+            let b_lane = b.value_lane(fx, 0).load_scalar(fx);
+
+            // Like maxss and minss, return the second operand when either one is NaN.
+            let cc =
+                if intrinsic.contains(".max.") { FloatCC::GreaterThan } else { FloatCC::LessThan };
+            let a_wins = fx.bcx.ins().fcmp(cc, a_lane, b_lane);
+            let res = fx.bcx.ins().select(a_wins, a_lane, b_lane);
+
+            ret.place_lane(fx, 0).write_cvalue(fx, CValue::by_val(res, ret_lane_layout));
+        }
+
+        "llvm.x86.sse2.pmulh.w" | "llvm.x86.avx2.pmulh.w" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_mulhi_epi16&ig_expand=4782
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            simd_pair_for_each_lane(
+                fx,
+                a,
+                b,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, a_lane, b_lane| fx.bcx.ins().smulhi(a_lane, b_lane),
+            );
+        }
+
+        "llvm.x86.sse2.pmulhu.w" | "llvm.x86.avx2.pmulhu.w" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_mulhi_epu16&ig_expand=4784
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            simd_pair_for_each_lane(
+                fx,
+                a,
+                b,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, a_lane, b_lane| fx.bcx.ins().umulhi(a_lane, b_lane),
+            );
+        }
+
+        "llvm.x86.ssse3.psign.b.128"
+        | "llvm.x86.ssse3.psign.w.128"
+        | "llvm.x86.ssse3.psign.d.128"
+        | "llvm.x86.avx2.psign.b"
+        | "llvm.x86.avx2.psign.w"
+        | "llvm.x86.avx2.psign.d" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sign_epi8&ig_expand=6257
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            simd_pair_for_each_lane(
+                fx,
+                a,
+                b,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, a_lane, b_lane| {
+                    let neg_a = fx.bcx.ins().ineg(a_lane);
+                    let zero = fx.bcx.ins().iconst(fx.bcx.func.dfg.value_type(a_lane), 0);
+                    let b_is_neg = fx.bcx.ins().icmp_imm(IntCC::SignedLessThan, b_lane, 0);
+                    let b_is_zero = fx.bcx.ins().icmp_imm(IntCC::Equal, b_lane, 0);
+                    let res = fx.bcx.ins().select(b_is_neg, neg_a, a_lane);
+                    fx.bcx.ins().select(b_is_zero, zero, res)
+                },
+            );
+        }
+
+        "llvm.x86.ssse3.phadd.w.128"
+        | "llvm.x86.ssse3.phadd.d.128"
+        | "llvm.x86.avx2.phadd.w"
+        | "llvm.x86.avx2.phadd.d"
+        | "llvm.x86.sse3.hadd.ps"
+        | "llvm.x86.sse3.hadd.pd"
+        | "llvm.x86.avx.hadd.ps.256"
+        | "llvm.x86.avx.hadd.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_hadd_epi16&ig_expand=3604
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            horizontal_pair_for_each_128bit_lane(fx, a, b, ret, &|fx, lhs, rhs| {
+                if fx.bcx.func.dfg.value_type(lhs).is_float() {
+                    fx.bcx.ins().fadd(lhs, rhs)
+                } else {
+                    fx.bcx.ins().iadd(lhs, rhs)
+                }
+            });
+        }
+
+        "llvm.x86.ssse3.phsub.w.128"
+        | "llvm.x86.ssse3.phsub.d.128"
+        | "llvm.x86.avx2.phsub.w"
+        | "llvm.x86.avx2.phsub.d"
+        | "llvm.x86.sse3.hsub.ps"
+        | "llvm.x86.sse3.hsub.pd"
+        | "llvm.x86.avx.hsub.ps.256"
+        | "llvm.x86.avx.hsub.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_hsub_epi16&ig_expand=3637
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            horizontal_pair_for_each_128bit_lane(fx, a, b, ret, &|fx, lhs, rhs| {
+                if fx.bcx.func.dfg.value_type(lhs).is_float() {
+                    fx.bcx.ins().fsub(lhs, rhs)
+                } else {
+                    fx.bcx.ins().isub(lhs, rhs)
+                }
+            });
+        }
+
+        "llvm.x86.ssse3.phadd.sw.128" | "llvm.x86.avx2.phadd.sw" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_hadds_epi16&ig_expand=3610
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            let lane_layout = fx.layout_of(fx.tcx.types.i16);
+            horizontal_pair_for_each_128bit_lane(fx, a, b, ret, &|fx, lhs, rhs| {
+                let lhs = CValue::by_val(lhs, lane_layout);
+                let rhs = CValue::by_val(rhs, lane_layout);
+                crate::num::codegen_saturating_int_binop(fx, BinOp::Add, lhs, rhs).load_scalar(fx)
+            });
+        }
+
+        "llvm.x86.ssse3.phsub.sw.128" | "llvm.x86.avx2.phsub.sw" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_hsubs_epi16&ig_expand=3641
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            let lane_layout = fx.layout_of(fx.tcx.types.i16);
+            horizontal_pair_for_each_128bit_lane(fx, a, b, ret, &|fx, lhs, rhs| {
+                let lhs = CValue::by_val(lhs, lane_layout);
+                let rhs = CValue::by_val(rhs, lane_layout);
+                crate::num::codegen_saturating_int_binop(fx, BinOp::Sub, lhs, rhs).load_scalar(fx)
+            });
+        }
+
+        "llvm.x86.sse3.addsub.ps"
+        | "llvm.x86.sse3.addsub.pd"
+        | "llvm.x86.avx.addsub.ps.256"
+        | "llvm.x86.avx.addsub.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_addsub_ps&ig_expand=167
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            assert_eq!(a.layout(), b.layout());
+            assert_eq!(a.layout(), ret.layout());
+            let (lane_count, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let lane_layout = fx.layout_of(lane_ty);
+
+            for lane_idx in 0..lane_count {
+                let a_lane = a.value_lane(fx, lane_idx).load_scalar(fx);
+                let b_lane = b.value_lane(fx, lane_idx).load_scalar(fx);
+                let res = if lane_idx % 2 == 0 {
+                    fx.bcx.ins().fsub(a_lane, b_lane)
+                } else {
+                    fx.bcx.ins().fadd(a_lane, b_lane)
+                };
+                ret.place_lane(fx, lane_idx).write_cvalue(fx, CValue::by_val(res, lane_layout));
+            }
+        }
+
+        "llvm.x86.sse41.pblendvb"
+        | "llvm.x86.sse41.blendvps"
+        | "llvm.x86.sse41.blendvpd"
+        | "llvm.x86.avx2.pblendvb"
+        | "llvm.x86.avx.blendv.ps.256"
+        | "llvm.x86.avx.blendv.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_blendv_epi8&ig_expand=389
+            intrinsic_args!(fx, args => (a, b, mask); intrinsic);
+
+            simd_trio_for_each_lane(
+                fx,
+                a,
+                b,
+                mask,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, a_lane, b_lane, mask_lane| {
+                    // Only the most significant bit of each mask lane is used.
+                    let mask_ty = fx.bcx.func.dfg.value_type(mask_lane);
+                    let mask_lane = if mask_ty.is_float() {
+                        let int_ty = Type::int(mask_ty.bits() as u16).unwrap();
+                        codegen_bitcast(fx, int_ty, mask_lane)
+                    } else {
+                        mask_lane
+                    };
+                    let take_b = fx.bcx.ins().icmp_imm(IntCC::SignedLessThan, mask_lane, 0);
+                    fx.bcx.ins().select(take_b, b_lane, a_lane)
+                },
+            );
+        }
+
+        "llvm.x86.sse41.round.ps"
+        | "llvm.x86.sse41.round.pd"
+        | "llvm.x86.avx.round.ps.256"
+        | "llvm.x86.avx.round.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_round_ps&ig_expand=5849
+            intrinsic_args!(fx, args => (a, _rounding); intrinsic);
+
+            let rounding = if let Some(rounding) =
+                crate::constant::mir_operand_get_const_val(fx, &args[1].node)
+            {
+                rounding
+            } else {
+                fx.tcx
+                    .dcx()
+                    .span_fatal(span, "Rounding argument for `_mm_round_ps` is not a constant");
+            };
+            let rounding =
+                rounding.try_to_u32().unwrap_or_else(|_| panic!("kind not scalar: {:?}", rounding));
+
+            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, lane| {
+                // Bit 2 selects the rounding mode from MXCSR, which is round to nearest unless
+                // changed by the program. Bit 3 only suppresses the precision exception.
+                if rounding & 0b100 != 0 {
+                    return fx.bcx.ins().nearest(lane);
+                }
+                match rounding & 0b11 {
+                    0 => fx.bcx.ins().nearest(lane),
+                    1 => fx.bcx.ins().floor(lane),
+                    2 => fx.bcx.ins().ceil(lane),
+                    3 => fx.bcx.ins().trunc(lane),
+                    _ => unreachable!(),
+                }
+            });
+        }
+
+        "llvm.x86.avx2.permd" | "llvm.x86.avx2.permps" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_permutevar8x32_epi32&ig_expand=5322
+            intrinsic_args!(fx, args => (a, idx); intrinsic);
+
+            let (lane_count, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            assert_eq!(lane_count, 8);
+            let lane_layout = fx.layout_of(lane_ty);
+
+            // FIXME use a jump table or vector instructions instead
+            for lane_idx in 0..lane_count {
+                let idx_lane = idx.value_lane(fx, lane_idx).load_scalar(fx);
+                let idx_lane = fx.bcx.ins().band_imm(idx_lane, 0b111);
+                let res = select_dynamic_lane(fx, a, 0, 8, idx_lane);
+                ret.place_lane(fx, lane_idx).write_cvalue(fx, CValue::by_val(res, lane_layout));
+            }
+        }
+
+        "llvm.x86.avx.vpermilvar.ps"
+        | "llvm.x86.avx.vpermilvar.ps.256"
+        | "llvm.x86.avx.vpermilvar.pd"
+        | "llvm.x86.avx.vpermilvar.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_permutevar_ps&ig_expand=5332
+            intrinsic_args!(fx, args => (a, idx); intrinsic);
+
+            let (lane_count, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let lane_layout = fx.layout_of(lane_ty);
+            let lanes_per_128bit = 128 / lane_layout.size.bits();
+
+            // FIXME use a jump table or vector instructions instead
+            for lane_idx in 0..lane_count {
+                let idx_lane = idx.value_lane(fx, lane_idx).load_scalar(fx);
+                // The pd variants use bit 1 of each index lane instead of bit 0.
+                let idx_lane = if lanes_per_128bit == 2 {
+                    fx.bcx.ins().ushr_imm(idx_lane, 1)
+                } else {
+                    idx_lane
+                };
+                let idx_lane = fx.bcx.ins().band_imm(idx_lane, lanes_per_128bit as i64 - 1);
+                let half_start = lane_idx / lanes_per_128bit * lanes_per_128bit;
+                let res = select_dynamic_lane(fx, a, half_start, lanes_per_128bit, idx_lane);
+                ret.place_lane(fx, lane_idx).write_cvalue(fx, CValue::by_val(res, lane_layout));
+            }
+        }
+
+        "llvm.x86.sse.comieq.ss"
+        | "llvm.x86.sse.comilt.ss"
+        | "llvm.x86.sse.comile.ss"
+        | "llvm.x86.sse.comigt.ss"
+        | "llvm.x86.sse.comige.ss"
+        | "llvm.x86.sse.comineq.ss"
+        | "llvm.x86.sse.ucomieq.ss"
+        | "llvm.x86.sse.ucomilt.ss"
+        | "llvm.x86.sse.ucomile.ss"
+        | "llvm.x86.sse.ucomigt.ss"
+        | "llvm.x86.sse.ucomige.ss"
+        | "llvm.x86.sse.ucomineq.ss"
+        | "llvm.x86.sse2.comieq.sd"
+        | "llvm.x86.sse2.comilt.sd"
+        | "llvm.x86.sse2.comile.sd"
+        | "llvm.x86.sse2.comigt.sd"
+        | "llvm.x86.sse2.comige.sd"
+        | "llvm.x86.sse2.comineq.sd"
+        | "llvm.x86.sse2.ucomieq.sd"
+        | "llvm.x86.sse2.ucomilt.sd"
+        | "llvm.x86.sse2.ucomile.sd"
+        | "llvm.x86.sse2.ucomigt.sd"
+        | "llvm.x86.sse2.ucomige.sd"
+        | "llvm.x86.sse2.ucomineq.sd" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_comieq_ss&ig_expand=1176
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            let a_lane = a.value_lane(fx, 0).load_scalar(fx);
+            let b_lane = b.value_lane(fx, 0).load_scalar(fx);
+
+            // The signaling and quiet variants only differ in which NaNs raise an exception.
+            let predicate = intrinsic.rsplit_once("comi").unwrap().1;
+            let cc = match predicate {
+                "eq.ss" | "eq.sd" => FloatCC::Equal,
+                "lt.ss" | "lt.sd" => FloatCC::LessThan,
+                "le.ss" | "le.sd" => FloatCC::LessThanOrEqual,
+                "gt.ss" | "gt.sd" => FloatCC::GreaterThan,
+                "ge.ss" | "ge.sd" => FloatCC::GreaterThanOrEqual,
+                "neq.ss" | "neq.sd" => FloatCC::UnorderedOrNotEqual,
+                _ => unreachable!("{intrinsic}"),
+            };
+            let res = fx.bcx.ins().fcmp(cc, a_lane, b_lane);
+            let res = fx.bcx.ins().uextend(types::I32, res);
+            ret.write_cvalue(fx, CValue::by_val(res, fx.layout_of(fx.tcx.types.i32)));
+        }
+
+        "llvm.x86.sse.rcp.ps" | "llvm.x86.avx.rcp.ps.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_rcp_ps&ig_expand=5490
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            // The exact result is within the precision guaranteed by rcpps.
+            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, lane| {
+                let one = fx.bcx.ins().f32const(1.0);
+                fx.bcx.ins().fdiv(one, lane)
+            });
+        }
+
+        "llvm.x86.sse.rsqrt.ps" | "llvm.x86.avx.rsqrt.ps.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_rsqrt_ps&ig_expand=5593
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            // The exact result is within the precision guaranteed by rsqrtps.
+            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, lane| {
+                let one = fx.bcx.ins().f32const(1.0);
+                let sqrt = fx.bcx.ins().sqrt(lane);
+                fx.bcx.ins().fdiv(one, sqrt)
+            });
+        }
+
+        "llvm.x86.avx.movmsk.ps.256" | "llvm.x86.avx.movmsk.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_movemask_ps&ig_expand=4636
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            let (lane_count, _) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let mut res = fx.bcx.ins().iconst(types::I32, 0);
+            for lane_idx in 0..lane_count {
+                let lane = a.value_lane(fx, lane_idx).load_scalar(fx);
+                let lane_ty = fx.bcx.func.dfg.value_type(lane);
+                let lane = codegen_bitcast(fx, Type::int(lane_ty.bits() as u16).unwrap(), lane);
+                let sign = fx.bcx.ins().icmp_imm(IntCC::SignedLessThan, lane, 0);
+                let sign = fx.bcx.ins().uextend(types::I32, sign);
+                let sign = fx.bcx.ins().ishl_imm(sign, lane_idx as i64);
+                res = fx.bcx.ins().bor(res, sign);
+            }
+            ret.write_cvalue(fx, CValue::by_val(res, fx.layout_of(fx.tcx.types.i32)));
+        }
+
+        "llvm.x86.sse41.phminposuw" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_minpos_epu16&ig_expand=4510
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            let (lane_count, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            assert_eq!(lane_count, 8);
+            let lane_layout = fx.layout_of(lane_ty);
+
+            let mut min = a.value_lane(fx, 0).load_scalar(fx);
+            let mut min_idx = fx.bcx.ins().iconst(types::I16, 0);
+            for lane_idx in 1..lane_count {
+                let lane = a.value_lane(fx, lane_idx).load_scalar(fx);
+                // Strictly less than, so that the lowest index wins for equal values.
+                let is_less = fx.bcx.ins().icmp(IntCC::UnsignedLessThan, lane, min);
+                let idx = fx.bcx.ins().iconst(types::I16, lane_idx as i64);
+                min = fx.bcx.ins().select(is_less, lane, min);
+                min_idx = fx.bcx.ins().select(is_less, idx, min_idx);
+            }
+
+            let zero = fx.bcx.ins().iconst(types::I16, 0);
+            ret.place_lane(fx, 0).write_cvalue(fx, CValue::by_val(min, lane_layout));
+            ret.place_lane(fx, 1).write_cvalue(fx, CValue::by_val(min_idx, lane_layout));
+            for lane_idx in 2..lane_count {
+                ret.place_lane(fx, lane_idx).write_cvalue(fx, CValue::by_val(zero, lane_layout));
+            }
+        }
+
+        "llvm.x86.sse42.crc32.32.8"
+        | "llvm.x86.sse42.crc32.32.16"
+        | "llvm.x86.sse42.crc32.32.32"
+        | "llvm.x86.sse42.crc32.64.64" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_crc32_u8&ig_expand=1435
+            intrinsic_args!(fx, args => (crc, v); intrinsic);
+
+            let crc = crc.load_scalar(fx);
+            let v = v.load_scalar(fx);
+
+            let asm = match intrinsic {
+                "llvm.x86.sse42.crc32.32.8" => "crc32 eax, dl",
+                "llvm.x86.sse42.crc32.32.16" => "crc32 eax, dx",
+                "llvm.x86.sse42.crc32.32.32" => "crc32 eax, edx",
+                "llvm.x86.sse42.crc32.64.64" => "crc32 rax, rdx",
+                _ => unreachable!(),
+            };
+
+            codegen_inline_asm_inner(
+                fx,
+                &[InlineAsmTemplatePiece::String(asm.to_string())],
+                &[
+                    CInlineAsmOperand::InOut {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::ax)),
+                        _late: true,
+                        in_value: crc,
+                        out_place: Some(ret),
+                    },
+                    CInlineAsmOperand::In {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::dx)),
+                        value: v,
+                    },
+                ],
+                InlineAsmOptions::NOSTACK | InlineAsmOptions::PURE | InlineAsmOptions::NOMEM,
+            );
+        }
+
         _ => {
             fx.tcx
                 .dcx()
@@ -1382,6 +1902,96 @@ fn select4(
 // llvm.x86.ssse3.pshuf.b.128
 // llvm.x86.avx2.pshuf.b
 
+#[derive(Copy, Clone)]
+enum ShiftKind {
+    Left,
+    LogicalRight,
+    ArithmeticRight,
+}
+
+impl ShiftKind {
+    fn from_intrinsic(intrinsic: &str) -> Self {
+        if intrinsic.contains(".psll") {
+            Self::Left
+        } else if intrinsic.contains(".psrl") {
+            Self::LogicalRight
+        } else if intrinsic.contains(".psra") {
+            Self::ArithmeticRight
+        } else {
+            unreachable!("{intrinsic}")
+        }
+    }
+}
+
+/// Shift `lane` by `count` with x86 semantics: unlike Cranelift's shifts the count isn't masked,
+/// so logical shifts by at least the lane width give zero and arithmetic shifts give the sign.
+fn shift_lane(
+    fx: &mut FunctionCx<'_, '_, '_>,
+    kind: ShiftKind,
+    lane: Value,
+    count: Value,
+) -> Value {
+    let lane_ty = fx.bcx.func.dfg.value_type(lane);
+    let count_ty = fx.bcx.func.dfg.value_type(count);
+    let max_count = fx.bcx.ins().iconst(count_ty, i64::from(lane_ty.bits() - 1));
+    let saturated_count = fx.bcx.ins().umin(count, max_count);
+    let res = match kind {
+        ShiftKind::Left => fx.bcx.ins().ishl(lane, saturated_count),
+        ShiftKind::LogicalRight => fx.bcx.ins().ushr(lane, saturated_count),
+        ShiftKind::ArithmeticRight => return fx.bcx.ins().sshr(lane, saturated_count),
+    };
+    let in_range = fx.bcx.ins().icmp_imm(IntCC::UnsignedLessThan, count, i64::from(lane_ty.bits()));
+    let zero = fx.bcx.ins().iconst(lane_ty, 0);
+    fx.bcx.ins().select(in_range, res, zero)
+}
+
+/// Apply `f` to adjacent pairs of lanes like the x86 horizontal add and subtract instructions.
+/// The results for `a` go to the lower half of each 128-bit lane and those for `b` to the upper
+/// half. For 256-bit vectors both 128-bit lanes are handled independently.
+fn horizontal_pair_for_each_128bit_lane<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    a: CValue<'tcx>,
+    b: CValue<'tcx>,
+    ret: CPlace<'tcx>,
+    f: &dyn Fn(&mut FunctionCx<'_, '_, 'tcx>, Value, Value) -> Value,
+) {
+    assert_eq!(a.layout(), b.layout());
+    assert_eq!(a.layout(), ret.layout());
+    let (lane_count, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+    let lane_layout = fx.layout_of(lane_ty);
+    let lanes_per_128bit = 128 / lane_layout.size.bits();
+    let pairs_per_128bit = lanes_per_128bit / 2;
+
+    for half_start in (0..lane_count).step_by(lanes_per_128bit as usize) {
+        for (src_idx, src) in [a, b].into_iter().enumerate() {
+            for pair_idx in 0..pairs_per_128bit {
+                let lhs = src.value_lane(fx, half_start + pair_idx * 2).load_scalar(fx);
+                let rhs = src.value_lane(fx, half_start + pair_idx * 2 + 1).load_scalar(fx);
+                let res = f(fx, lhs, rhs);
+                let dest_idx = half_start + src_idx as u64 * pairs_per_128bit + pair_idx;
+                ret.place_lane(fx, dest_idx).write_cvalue(fx, CValue::by_val(res, lane_layout));
+            }
+        }
+    }
+}
+
+/// Select lane `first_lane + idx` of `a` for a runtime `idx` in `0..lane_count`.
+fn select_dynamic_lane<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    a: CValue<'tcx>,
+    first_lane: u64,
+    lane_count: u64,
+    idx: Value,
+) -> Value {
+    let mut res = a.value_lane(fx, first_lane).load_scalar(fx);
+    for i in 1..lane_count {
+        let lane = a.value_lane(fx, first_lane + i).load_scalar(fx);
+        let is_idx = fx.bcx.ins().icmp_imm(IntCC::Equal, idx, i as i64);
+        res = fx.bcx.ins().select(is_idx, lane, res);
+    }
+    res
+}
+
 fn llvm_add_sub<'tcx>(
     fx: &mut FunctionCx<'_, '_, 'tcx>,
     bin_op: BinOp,
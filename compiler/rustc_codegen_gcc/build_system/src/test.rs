@@ -40,6 +40,7 @@ fn get_runners() -> Runners {
     runners.insert("--build-sysroot", ("Build sysroot", build_sysroot));
     runners.insert("--std-tests", ("Run std tests", std_tests));
     runners.insert("--asm-tests", ("Run asm tests", asm_tests));
+    runners.insert("--lto-tests", ("Run LTO tests", lto_tests));
     runners.insert(
         "--extended-tests",
         ("Run extended sysroot tests", extended_sysroot_tests),
@@ -583,6 +584,55 @@ fn setup_rustc(env: &mut Env, args: &TestArg) -> Result<PathBuf, String> {
     Ok(rust_dir_path)
 }
 
+// NOTE: these tests require the LTO setup described in the Readme, so they are not part of
+// `run_all`.
+fn lto_tests(env: &Env, args: &TestArg) -> Result<(), String> {
+    let cargo_target_dir = Path::new(&args.config_info.cargo_target_dir);
+    let incremental_arg = format!(
+        "-Cincremental={}",
+        cargo_target_dir.join("lto_incremental").display()
+    );
+    let builds: &[(&str, &[&str])] = &[
+        ("fat LTO", &["-Clto=fat"]),
+        ("incremental fat LTO", &["-Clto=fat", &incremental_arg]),
+        // Every codegen unit is reused from the previous build, so none of them is in memory
+        // when LTO starts.
+        ("incremental fat LTO reusing every module", &["-Clto=fat", &incremental_arg]),
+        ("ThinLTO, performed as fat LTO", &["-Clto=thin"]),
+        ("linker plugin LTO", &["-Clinker-plugin-lto"]),
+    ];
+    for (name, flags) in builds {
+        // FIXME: create a function "display_if_not_quiet" or something along the line.
+        println!("[AOT] std_example with {}", name);
+        let mut command = args.config_info.rustc_command_vec();
+        command.extend_from_slice(&[
+            &"example/std_example.rs",
+            &"--crate-type",
+            &"bin",
+            &"--target",
+            &args.config_info.target_triple,
+            &"-Copt-level=2",
+        ]);
+        for flag in flags.iter() {
+            command.push(flag);
+        }
+        if args.is_using_gcc_master_branch() {
+            command.extend_from_slice(&[&"--cfg", &"feature=\"master\""]);
+        }
+        run_command_with_env(&command, None, Some(env))?;
+        maybe_run_command_in_vm(
+            &[
+                &cargo_target_dir.join("std_example"),
+                &"--target",
+                &args.config_info.target_triple,
+            ],
+            env,
+            args,
+        )?;
+    }
+    Ok(())
+}
+
 fn asm_tests(env: &Env, args: &TestArg) -> Result<(), String> {
     let mut env = env.clone();
     let rust_dir = setup_rustc(&mut env, args)?;
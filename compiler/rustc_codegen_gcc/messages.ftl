@@ -6,7 +6,7 @@ codegen_gcc_invalid_minimum_alignment =
     invalid minimum global alignment: {$err}
 
 codegen_gcc_lto_not_supported =
-    LTO is not supported. You may get a linker error.
+    ThinLTO is not supported by the GCC backend, fat LTO will be performed instead
 
 codegen_gcc_tied_target_features = the target features {$features} must all be either enabled or disabled together
     .help = add the missing features in a `target_feature` attribute
@@ -26,6 +26,8 @@ codegen_gcc_lto_dylib = lto cannot be used for `dylib` crate type without `-Zdyl
 
 codegen_gcc_lto_bitcode_from_rlib = failed to get bitcode from object file for LTO ({$gcc_err})
 
+codegen_gcc_serialize_module = failed to serialize module `{$name}` for LTO: {$error}
+
 codegen_gcc_unknown_ctarget_feature =
     unknown feature specified for `-Ctarget-feature`: `{$feature}`
     .note = it is still passed through to the codegen backend
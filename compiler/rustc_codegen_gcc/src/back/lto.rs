@@ -19,12 +19,13 @@
 use std::ffi::CString;
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
-use gccjit::OutputKind;
+use gccjit::{Context, OutputKind};
 use object::read::archive::ArchiveFile;
 use rustc_codegen_ssa::back::lto::{LtoModuleCodegen, SerializedModule};
 use rustc_codegen_ssa::back::symbol_export;
-use rustc_codegen_ssa::back::write::{CodegenContext, FatLtoInput};
+use rustc_codegen_ssa::back::write::{CodegenContext, FatLtoInput, TargetMachineFactoryConfig};
 use rustc_codegen_ssa::traits::*;
 use rustc_codegen_ssa::{looks_like_rust_object_file, ModuleCodegen, ModuleKind};
 use rustc_data_structures::memmap::Mmap;
@@ -36,7 +37,9 @@
 use tempfile::{tempdir, TempDir};
 
 use crate::back::write::save_temp_bitcode;
-use crate::errors::{DynamicLinkingWithLTO, LtoBitcodeFromRlib, LtoDisallowed, LtoDylib};
+use crate::errors::{
+    DynamicLinkingWithLTO, LtoBitcodeFromRlib, LtoDisallowed, LtoDylib, SerializeModule,
+};
 use crate::{to_gcc_opt_level, GccCodegenBackend, GccContext};
 
 /// We keep track of the computed LTO cache keys from the previous
@@ -145,7 +148,7 @@ fn prepare_lto(
                 let path = tmp_path.path().join(name);
                 match save_as_file(child.data(&*archive_data).expect("corrupt rlib"), &path) {
                     Ok(()) => {
-                        let buffer = ModuleBuffer::new(path);
+                        let buffer = ModuleBuffer::File(path, OnceLock::new());
                         let module = SerializedModule::Local(buffer);
                         upstream_modules.push((module, CString::new(name).unwrap()));
                     }
@@ -195,7 +198,7 @@ pub(crate) fn run_fat(
 
 fn fat_lto(
     cgcx: &CodegenContext<GccCodegenBackend>,
-    _dcx: &DiagCtxt,
+    dcx: &DiagCtxt,
     modules: Vec<FatLtoInput<GccCodegenBackend>>,
     cached_modules: Vec<(SerializedModule<ModuleBuffer>, WorkProduct)>,
     mut serialized_modules: Vec<(SerializedModule<ModuleBuffer>, CString)>,
@@ -259,15 +262,31 @@ fn fat_lto(
     let mut module: ModuleCodegen<GccContext> = match costliest_module {
         Some((_cost, i)) => in_memory.remove(i),
         None => {
-            unimplemented!("Incremental");
-            /*assert!(!serialized_modules.is_empty(), "must have at least one serialized module");
-            let (buffer, name) = serialized_modules.remove(0);
-            info!("no in-memory regular modules to choose from, parsing {:?}", name);
+            // We cannot parse a serialized module back into a GCC context, so use an empty
+            // context as the base module: every serialized module is added to it as an object
+            // file to combine below. It is configured like the contexts of the codegen units, as
+            // it is the one compiling the combined module.
+            let (_, name) = serialized_modules
+                .iter()
+                .min_by(|module1, module2| module1.1.cmp(&module2.1))
+                .expect("must have at least one serialized module");
+            info!("no in-memory regular modules to choose from, using an empty {:?}", name);
+            let context = Context::default();
+            let tm_factory_config = TargetMachineFactoryConfig::new(cgcx, name.to_str().unwrap());
+            let options =
+                (cgcx.tm_factory)(tm_factory_config).unwrap_or_else(|never| match never {});
+            options.apply(&context);
+            let config = cgcx.config(ModuleKind::Regular);
+            context.set_optimization_level(to_gcc_opt_level(config.opt_level));
             ModuleCodegen {
-                module_llvm: GccContext::parse(cgcx, &name, buffer.data(), dcx)?,
-                name: name.into_string().unwrap(),
+                module_llvm: GccContext {
+                    context,
+                    should_combine_object_files: false,
+                    temp_dir: None,
+                },
+                name: name.to_str().expect("module name").to_string(),
                 kind: ModuleKind::Regular,
-            }*/
+            }
         }
     };
     let mut serialized_bitcode = Vec::new();
@@ -278,15 +297,11 @@ fn fat_lto(
         // Instead, we combine the object files into a single object file.
         for module in in_memory {
             let path = tmp_path.path().to_path_buf().join(&module.name);
-            let path = path.to_str().expect("path");
-            let context = &module.module_llvm.context;
             let config = cgcx.config(module.kind);
             // NOTE: we need to set the optimization level here in order for LTO to do its job.
-            context.set_optimization_level(to_gcc_opt_level(config.opt_level));
-            context.add_command_line_option("-flto=auto");
-            context.add_command_line_option("-flto-partition=one");
-            context.compile_to_file(OutputKind::ObjectFile, path);
-            let buffer = ModuleBuffer::new(PathBuf::from(path));
+            module.module_llvm.context.set_optimization_level(to_gcc_opt_level(config.opt_level));
+            compile_to_lto_object(&module.module_llvm, &path);
+            let buffer = ModuleBuffer::File(path, OnceLock::new());
             let llmod_id = CString::new(&module.name[..]).unwrap();
             serialized_modules.push((SerializedModule::Local(buffer), llmod_id));
         }
@@ -302,19 +317,29 @@ fn fat_lto(
                     recorder.record_arg(format!("{:?}", name))
                 });
             info!("linking {:?}", name);
-            match bc_decoded {
-                SerializedModule::Local(ref module_buffer) => {
-                    module.module_llvm.should_combine_object_files = true;
-                    module
-                        .module_llvm
-                        .context
-                        .add_driver_option(module_buffer.0.to_str().expect("path"));
+            // Modules that are not already on disk in our temporary directory (the ones
+            // serialized for incremental compilation and the ones reused from the incremental
+            // cache) are written there, as GCC can only combine object files.
+            let path = match bc_decoded {
+                SerializedModule::Local(ModuleBuffer::File(ref path, _)) => path.clone(),
+                SerializedModule::Local(ModuleBuffer::Error(ref error)) => {
+                    dcx.emit_err(SerializeModule {
+                        name: name.to_string_lossy().into_owned(),
+                        error: error.clone(),
+                    });
+                    return Err(FatalError);
                 }
-                SerializedModule::FromRlib(_) => unimplemented!("from rlib"),
-                SerializedModule::FromUncompressedFile(_) => {
-                    unimplemented!("from uncompressed file")
+                _ => {
+                    let path = tmp_path.path().join(format!("{}.o", name.to_str().expect("name")));
+                    if let Err(e) = save_as_file(bc_decoded.data(), &path) {
+                        dcx.emit_err(e);
+                        return Err(FatalError);
+                    }
+                    path
                 }
-            }
+            };
+            module.module_llvm.should_combine_object_files = true;
+            module.module_llvm.context.add_driver_option(path.to_str().expect("path"));
             serialized_bitcode.push(bc_decoded);
         }
         save_temp_bitcode(cgcx, &module, "lto.input");
@@ -338,16 +363,60 @@ fn fat_lto(
     Ok(LtoModuleCodegen::Fat { module, _serialized_bitcode: serialized_bitcode })
 }
 
-pub struct ModuleBuffer(PathBuf);
+/// Compiles `module` to an object file containing GIMPLE, GCC's equivalent to LLVM bitcode,
+/// so that `lto1` can later combine it with other modules.
+pub(crate) fn compile_to_lto_object(module: &GccContext, path: &Path) {
+    let context = &module.context;
+    context.add_command_line_option("-flto=auto");
+    context.add_command_line_option("-flto-partition=one");
+    context.compile_to_file(OutputKind::ObjectFile, path.to_str().expect("path to str"));
+}
 
-impl ModuleBuffer {
-    pub fn new(path: PathBuf) -> ModuleBuffer {
-        ModuleBuffer(path)
-    }
+/// Serializes `module` for fat LTO in a later step, e.g. to save it in the incremental cache.
+pub(crate) fn serialize_module(module: ModuleCodegen<GccContext>) -> (String, ModuleBuffer) {
+    let buffer = match tempdir() {
+        Ok(tmp_path) => {
+            let path = tmp_path.path().join(&module.name);
+            compile_to_lto_object(&module.module_llvm, &path);
+            match fs::read(&path) {
+                Ok(data) => ModuleBuffer::Data(data),
+                Err(error) => ModuleBuffer::Error(format!("cannot read object file: {}", error)),
+            }
+        }
+        Err(error) => ModuleBuffer::Error(format!("cannot create temporary directory: {}", error)),
+    };
+    (module.name, buffer)
+}
+
+pub enum ModuleBuffer {
+    /// An object file in the temporary directory used by LTO, which is combined from there. Its
+    /// contents are only read if they are asked for.
+    File(PathBuf, OnceLock<Vec<u8>>),
+    Data(Vec<u8>),
+    /// The module could not be serialized. The error is reported when performing LTO.
+    Error(String),
 }
 
 impl ModuleBufferMethods for ModuleBuffer {
     fn data(&self) -> &[u8] {
-        unimplemented!("data not needed for GCC codegen");
+        match self {
+            // A file that can't be read gives an empty buffer, like `ModuleBuffer::Error`. LTO
+            // then fails when combining it.
+            ModuleBuffer::File(path, data) => {
+                data.get_or_init(|| fs::read(path).unwrap_or_default())
+            }
+            ModuleBuffer::Data(data) => data,
+            // What is saved in the incremental cache for this module is discarded, as LTO then
+            // fails the compilation.
+            ModuleBuffer::Error(_) => &[],
+        }
+    }
+}
+
+// GCC has no equivalent to ThinLTO summaries, so ThinLTO is performed as fat LTO and the same
+// buffer is used for both.
+impl ThinBufferMethods for ModuleBuffer {
+    fn data(&self) -> &[u8] {
+        ModuleBufferMethods::data(self)
     }
 }
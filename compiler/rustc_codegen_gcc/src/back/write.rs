@@ -10,6 +10,7 @@
 use rustc_span::fatal_error::FatalError;
 use rustc_target::spec::SplitDebuginfo;
 
+use crate::back::lto::compile_to_lto_object;
 use crate::errors::CopyBitcode;
 use crate::{GccCodegenBackend, GccContext};
 
@@ -31,7 +32,9 @@ pub(crate) unsafe fn codegen(
 
         // NOTE: Only generate object files with GIMPLE when this environment variable is set for
         // now because this requires a particular setup (same gcc/lto1/lto-wrapper commit as libgccjit).
-        let fat_lto = env::var("EMBED_LTO_BITCODE").as_deref() == Ok("1");
+        // Linker plugin LTO requires that setup anyway, so always do it in that case.
+        let fat_lto = env::var("EMBED_LTO_BITCODE").as_deref() == Ok("1")
+            || cgcx.opts.cg.linker_plugin_lto.enabled();
 
         let bc_out = cgcx.output_filenames.temp_path(OutputType::Bitcode, module_name);
         let obj_out = cgcx.output_filenames.temp_path(OutputType::Object, module_name);
@@ -54,10 +57,7 @@ pub(crate) unsafe fn codegen(
                 let _timer = cgcx
                     .prof
                     .generic_activity_with_arg("GCC_module_codegen_emit_bitcode", &*module.name);
-                context.add_command_line_option("-flto=auto");
-                context.add_command_line_option("-flto-partition=one");
-                context
-                    .compile_to_file(OutputKind::ObjectFile, bc_out.to_str().expect("path to str"));
+                compile_to_lto_object(&module.module_llvm, &bc_out);
             }
 
             if config.emit_obj == EmitObj::ObjectCode(BitcodeSection::Full) {
@@ -113,7 +113,9 @@ pub(crate) unsafe fn codegen(
                     context.set_debug_info(true);
                     context.dump_to_file(path, true);
                 }
-                if should_combine_object_files && fat_lto {
+                // NOTE: the object files to combine come from fat LTO, which was explicitly
+                // requested, so don't check for `fat_lto` here: otherwise, they would be ignored.
+                if should_combine_object_files {
                     context.add_command_line_option("-flto=auto");
                     context.add_command_line_option("-flto-partition=one");
 
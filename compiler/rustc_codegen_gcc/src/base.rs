@@ -2,7 +2,7 @@
 use std::env;
 use std::time::Instant;
 
-use gccjit::{FunctionType, GlobalKind};
+use gccjit::{Context, FunctionType, GlobalKind};
 use rustc_codegen_ssa::base::maybe_create_entry_wrapper;
 use rustc_codegen_ssa::mono_item::MonoItemExt;
 use rustc_codegen_ssa::traits::DebugInfoMethods;
@@ -13,6 +13,7 @@
 use rustc_middle::mir::mono::Visibility;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::DebugInfo;
+use rustc_session::Session;
 use rustc_span::Symbol;
 use rustc_target::spec::PanicStrategy;
 
@@ -63,6 +64,80 @@ pub fn linkage_to_gcc(linkage: Linkage) -> FunctionType {
     }
 }
 
+/// The options of a GCC context that only depend on the session, which are also needed by the
+/// contexts created during LTO.
+#[derive(Clone)]
+pub struct ContextOptions {
+    command_line_options: Vec<String>,
+    driver_options: Vec<String>,
+}
+
+impl ContextOptions {
+    pub fn new(sess: &Session) -> Self {
+        let mut command_line_options = vec![];
+        let mut driver_options = vec![];
+
+        if sess.panic_strategy() == PanicStrategy::Unwind {
+            command_line_options.push("-fexceptions".to_string());
+            driver_options.push("-fexceptions".to_string());
+        }
+
+        let disabled_features: HashSet<_> = sess
+            .opts
+            .cg
+            .target_feature
+            .split(',')
+            .filter(|feature| feature.starts_with('-'))
+            .map(|string| &string[1..])
+            .collect();
+
+        if !disabled_features.contains("avx") && sess.target.arch == "x86_64" {
+            // NOTE: we always enable AVX because the equivalent of llvm.x86.sse2.cmp.pd in GCC for
+            // SSE2 is multiple builtins, so we use the AVX __builtin_ia32_cmppd instead.
+            // FIXME(antoyo): use the proper builtins for llvm.x86.sse2.cmp.pd and similar.
+            command_line_options.push("-mavx".to_string());
+        }
+
+        for arg in &sess.opts.cg.llvm_args {
+            command_line_options.push(arg.clone());
+        }
+        // NOTE: This is needed to compile the file src/intrinsic/archs.rs during a bootstrap of rustc.
+        command_line_options.push("-fno-var-tracking-assignments".to_string());
+        // NOTE: an optimization (https://github.com/rust-lang/rustc_codegen_gcc/issues/53).
+        command_line_options.push("-fno-semantic-interposition".to_string());
+        // NOTE: Rust relies on LLVM not doing TBAA (https://github.com/rust-lang/unsafe-code-guidelines/issues/292).
+        command_line_options.push("-fno-strict-aliasing".to_string());
+        // NOTE: Rust relies on LLVM doing wrapping on overflow.
+        command_line_options.push("-fwrapv".to_string());
+
+        if sess.relocation_model() == rustc_target::spec::RelocModel::Static {
+            command_line_options.push("-mcmodel=kernel".to_string());
+            command_line_options.push("-fno-pie".to_string());
+        }
+
+        let target_cpu = gcc_util::target_cpu(sess);
+        if target_cpu != "generic" {
+            command_line_options.push(format!("-march={}", target_cpu));
+        }
+
+        if sess.opts.unstable_opts.function_sections.unwrap_or(sess.target.function_sections) {
+            command_line_options.push("-ffunction-sections".to_string());
+            command_line_options.push("-fdata-sections".to_string());
+        }
+
+        ContextOptions { command_line_options, driver_options }
+    }
+
+    pub fn apply(&self, context: &Context<'_>) {
+        for option in &self.command_line_options {
+            context.add_command_line_option(option);
+        }
+        for option in &self.driver_options {
+            context.add_driver_option(option);
+        }
+    }
+}
+
 pub fn compile_codegen_unit(
     tcx: TyCtxt<'_>,
     cgu_name: Symbol,
@@ -94,60 +169,7 @@ fn module_codegen(
         // Instantiate monomorphizations without filling out definitions yet...
         let context = new_context(tcx);
 
-        if tcx.sess.panic_strategy() == PanicStrategy::Unwind {
-            context.add_command_line_option("-fexceptions");
-            context.add_driver_option("-fexceptions");
-        }
-
-        let disabled_features: HashSet<_> = tcx
-            .sess
-            .opts
-            .cg
-            .target_feature
-            .split(',')
-            .filter(|feature| feature.starts_with('-'))
-            .map(|string| &string[1..])
-            .collect();
-
-        if !disabled_features.contains("avx") && tcx.sess.target.arch == "x86_64" {
-            // NOTE: we always enable AVX because the equivalent of llvm.x86.sse2.cmp.pd in GCC for
-            // SSE2 is multiple builtins, so we use the AVX __builtin_ia32_cmppd instead.
-            // FIXME(antoyo): use the proper builtins for llvm.x86.sse2.cmp.pd and similar.
-            context.add_command_line_option("-mavx");
-        }
-
-        for arg in &tcx.sess.opts.cg.llvm_args {
-            context.add_command_line_option(arg);
-        }
-        // NOTE: This is needed to compile the file src/intrinsic/archs.rs during a bootstrap of rustc.
-        context.add_command_line_option("-fno-var-tracking-assignments");
-        // NOTE: an optimization (https://github.com/rust-lang/rustc_codegen_gcc/issues/53).
-        context.add_command_line_option("-fno-semantic-interposition");
-        // NOTE: Rust relies on LLVM not doing TBAA (https://github.com/rust-lang/unsafe-code-guidelines/issues/292).
-        context.add_command_line_option("-fno-strict-aliasing");
-        // NOTE: Rust relies on LLVM doing wrapping on overflow.
-        context.add_command_line_option("-fwrapv");
-
-        if tcx.sess.relocation_model() == rustc_target::spec::RelocModel::Static {
-            context.add_command_line_option("-mcmodel=kernel");
-            context.add_command_line_option("-fno-pie");
-        }
-
-        let target_cpu = gcc_util::target_cpu(tcx.sess);
-        if target_cpu != "generic" {
-            context.add_command_line_option(&format!("-march={}", target_cpu));
-        }
-
-        if tcx
-            .sess
-            .opts
-            .unstable_opts
-            .function_sections
-            .unwrap_or(tcx.sess.target.function_sections)
-        {
-            context.add_command_line_option("-ffunction-sections");
-            context.add_command_line_option("-fdata-sections");
-        }
+        ContextOptions::new(tcx.sess).apply(&context);
 
         if env::var("CG_GCCJIT_DUMP_RTL").as_deref() == Ok("1") {
             context.add_command_line_option("-fdump-rtl-vregs");
@@ -79,6 +79,13 @@ pub(crate) struct LtoBitcodeFromRlib {
     pub gcc_err: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_gcc_serialize_module)]
+pub(crate) struct SerializeModule {
+    pub name: String,
+    pub error: String,
+}
+
 pub(crate) struct TargetFeatureDisableOrEnable<'a> {
     pub features: &'a [&'a str],
     pub span: Option<Span>,
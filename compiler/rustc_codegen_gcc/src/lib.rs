@@ -1,8 +1,8 @@
 /*
  * TODO(antoyo): implement equality in libgccjit based on https://zpz.github.io/blog/overloading-equality-operator-in-cpp-class-hierarchy/ (for type equality?)
  * TODO(antoyo): support #[inline] attributes.
- * TODO(antoyo): support LTO (gcc's equivalent to Full LTO is -flto -flto-partition=one — https://documentation.suse.com/sbp/all/html/SBP-GCC-10/index.html).
- * For Thin LTO, this might be helpful:
+ * NOTE: gcc's equivalent to Full LTO is -flto -flto-partition=one (https://documentation.suse.com/sbp/all/html/SBP-GCC-10/index.html).
+ * TODO(antoyo): support Thin LTO. For now, it is performed as Full LTO. This might be helpful:
  * In gcc 4.6 -fwhopr was removed and became default with -flto. The non-whopr path can still be executed via -flto-partition=none.
  * Or the new incremental LTO?
  *
@@ -79,6 +79,7 @@
 mod type_of;
 
 use std::any::Any;
+use std::convert::Infallible;
 use std::fmt::Debug;
 #[cfg(not(feature = "master"))]
 use std::sync::atomic::AtomicBool;
@@ -99,9 +100,7 @@
     CodegenContext, FatLtoInput, ModuleConfig, TargetMachineFactoryFn,
 };
 use rustc_codegen_ssa::base::codegen_crate;
-use rustc_codegen_ssa::traits::{
-    CodegenBackend, ExtraBackendMethods, ThinBufferMethods, WriteBackendMethods,
-};
+use rustc_codegen_ssa::traits::{CodegenBackend, ExtraBackendMethods, WriteBackendMethods};
 use rustc_codegen_ssa::{CodegenResults, CompiledModule, ModuleCodegen};
 use rustc_data_structures::fx::FxIndexMap;
 use rustc_data_structures::sync::IntoDynSyncSend;
@@ -117,6 +116,7 @@
 use tempfile::TempDir;
 
 use crate::back::lto::ModuleBuffer;
+use crate::base::ContextOptions;
 use crate::gcc_util::target_cpu;
 
 rustc_fluent_macro::fluent_messages! { "../messages.ftl" }
@@ -265,6 +265,17 @@ fn link(
         link_binary(sess, &crate::archive::ArArchiveBuilderBuilder, &codegen_results, outputs)
     }
 
+    fn supports_local_thin_lto(&self) -> bool {
+        // GCC has no equivalent to ThinLTO.
+        false
+    }
+
+    fn linker_plugin_lto_args(&self, _sess: &Session) -> Option<Vec<String>> {
+        // The objects contain GIMPLE, which the compiler driver hands to GCC's own linker plugin
+        // with `-flto`. That plugin reads the optimization options from the objects themselves.
+        Some(vec!["-flto=auto".to_string()])
+    }
+
     fn target_features(&self, sess: &Session, allow_unstable: bool) -> Vec<Symbol> {
         target_features(sess, allow_unstable, &self.target_info)
     }
@@ -321,20 +332,15 @@ fn compile_codegen_unit(
 
     fn target_machine_factory(
         &self,
-        _sess: &Session,
+        sess: &Session,
         _opt_level: OptLevel,
         _features: &[String],
     ) -> TargetMachineFactoryFn<Self> {
+        // NOTE: GCC has no target machine, but the options of the contexts that depend on the
+        // session are computed here, for the contexts created without it during LTO.
         // TODO(antoyo): set opt level.
-        Arc::new(|_| Ok(()))
-    }
-}
-
-pub struct ThinBuffer;
-
-impl ThinBufferMethods for ThinBuffer {
-    fn data(&self) -> &[u8] {
-        unimplemented!();
+        let options = ContextOptions::new(sess);
+        Arc::new(move |_| Ok(options.clone()))
     }
 }
 
@@ -351,11 +357,11 @@ unsafe impl Sync for GccContext {}
 
 impl WriteBackendMethods for GccCodegenBackend {
     type Module = GccContext;
-    type TargetMachine = ();
-    type TargetMachineError = ();
+    type TargetMachine = ContextOptions;
+    type TargetMachineError = Infallible;
     type ModuleBuffer = ModuleBuffer;
     type ThinData = ();
-    type ThinBuffer = ThinBuffer;
+    type ThinBuffer = ModuleBuffer;
 
     fn run_fat_lto(
         cgcx: &CodegenContext<Self>,
@@ -366,11 +372,19 @@ fn run_fat_lto(
     }
 
     fn run_thin_lto(
-        _cgcx: &CodegenContext<Self>,
-        _modules: Vec<(String, Self::ThinBuffer)>,
-        _cached_modules: Vec<(SerializedModule<Self::ModuleBuffer>, WorkProduct)>,
+        cgcx: &CodegenContext<Self>,
+        modules: Vec<(String, Self::ThinBuffer)>,
+        cached_modules: Vec<(SerializedModule<Self::ModuleBuffer>, WorkProduct)>,
     ) -> Result<(Vec<LtoModuleCodegen<Self>>, Vec<WorkProduct>), FatalError> {
-        unimplemented!();
+        // GCC doesn't support ThinLTO, so it is only requested with `-Clto=thin`, ThinLTO across
+        // the codegen units of the crate being disabled by `supports_local_thin_lto`. Merge all
+        // the modules with fat LTO instead. As a result, no module can be reused after LTO.
+        let modules = modules
+            .into_iter()
+            .map(|(name, buffer)| FatLtoInput::Serialized { name, buffer })
+            .collect();
+        let module = back::lto::run_fat(cgcx, modules, cached_modules)?;
+        Ok((vec![module], vec![]))
     }
 
     fn print_pass_timings(&self) {
@@ -415,12 +429,12 @@ unsafe fn codegen(
         back::write::codegen(cgcx, dcx, module, config)
     }
 
-    fn prepare_thin(_module: ModuleCodegen<Self::Module>) -> (String, Self::ThinBuffer) {
-        unimplemented!();
+    fn prepare_thin(module: ModuleCodegen<Self::Module>) -> (String, Self::ThinBuffer) {
+        back::lto::serialize_module(module)
     }
 
-    fn serialize_module(_module: ModuleCodegen<Self::Module>) -> (String, Self::ModuleBuffer) {
-        unimplemented!();
+    fn serialize_module(module: ModuleCodegen<Self::Module>) -> (String, Self::ModuleBuffer) {
+        back::lto::serialize_module(module)
     }
 
     fn run_link(
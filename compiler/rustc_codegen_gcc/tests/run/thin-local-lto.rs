@@ -0,0 +1,25 @@
+// Compiler:
+//   extra-args: -Zthinlto=yes
+//
+// Run-time:
+//   status: 0
+
+// `-Zthinlto=yes` requests ThinLTO across the codegen units of the crate, which the GCC backend
+// doesn't perform: each codegen unit is compiled on its own instead.
+
+mod a {
+    #[inline(never)]
+    pub fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+mod b {
+    pub fn quadruple(x: u32) -> u32 {
+        super::a::double(super::a::double(x))
+    }
+}
+
+fn main() {
+    assert_eq!(b::quadruple(std::hint::black_box(3)), 12);
+}
@@ -41,6 +41,10 @@ codegen_llvm_lto_dylib = lto cannot be used for `dylib` crate type without `-Zdy
 
 codegen_llvm_lto_proc_macro = lto cannot be used for `proc-macro` crate type without `-Zdylib-lto`
 
+codegen_llvm_merge_raw_profiles =
+    failed to merge raw profiles passed to `-C profile-use`: {$llvm_err}
+    .note = raw profiles have to be written by a program built with `-C profile-generate` by the same version of rustc
+
 codegen_llvm_mismatch_data_layout =
     data-layout for target `{$rustc_target}`, `{$rustc_layout}`, differs from LLVM target's `{$llvm_target}` default layout, `{$llvm_layout}`
 
@@ -56,6 +60,9 @@ codegen_llvm_parse_bitcode_with_llvm_err = failed to parse bitcode for LTO modul
 codegen_llvm_parse_target_machine_config =
     failed to parse target machine config to target machine: {$error}
 
+codegen_llvm_pgo_profile_mismatch = {$message}
+    .help = the profile data is likely stale; rebuild with `-C profile-generate` and rerun the program to collect a new profile
+
 codegen_llvm_prepare_thin_lto_context = failed to prepare thin LTO context
 codegen_llvm_prepare_thin_lto_context_with_llvm_err = failed to prepare thin LTO context: {$llvm_err}
 
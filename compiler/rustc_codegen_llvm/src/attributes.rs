@@ -298,23 +298,37 @@ pub fn from_fn_attrs<'ll, 'tcx>(
 
     let mut to_add = SmallVec::<[_; 16]>::new();
 
+    // `#[optimize(level = "...")]` with levels `1`, `2` and `3` is handled by placing the function
+    // in a codegen unit of its own, which is optimized at that level, see
+    // `CodegenUnit::opt_level`. The other levels are applied with function attributes.
+    let mut optnone = false;
     match codegen_fn_attrs.optimize {
         OptimizeAttr::None => {
             to_add.extend(default_optimisation_attrs(cx));
         }
-        OptimizeAttr::Size => {
+        OptimizeAttr::Size | OptimizeAttr::Level(OptLevel::SizeMin) => {
             to_add.push(llvm::AttributeKind::MinSize.create_attr(cx.llcx));
             to_add.push(llvm::AttributeKind::OptimizeForSize.create_attr(cx.llcx));
         }
-        OptimizeAttr::Speed => {}
+        OptimizeAttr::Level(OptLevel::Size) => {
+            to_add.push(llvm::AttributeKind::OptimizeForSize.create_attr(cx.llcx));
+        }
+        OptimizeAttr::Level(OptLevel::No) => {
+            to_add.push(llvm::AttributeKind::OptimizeNone.create_attr(cx.llcx));
+            optnone = true;
+        }
+        OptimizeAttr::Speed
+        | OptimizeAttr::Level(OptLevel::Less | OptLevel::Default | OptLevel::Aggressive) => {}
     }
 
-    let inline =
-        if codegen_fn_attrs.inline == InlineAttr::None && instance.def.requires_inline(cx.tcx) {
-            InlineAttr::Hint
-        } else {
-            codegen_fn_attrs.inline
-        };
+    let inline = if optnone {
+        // LLVM requires `optnone` functions to also be `noinline`.
+        InlineAttr::Never
+    } else if codegen_fn_attrs.inline == InlineAttr::None && instance.def.requires_inline(cx.tcx) {
+        InlineAttr::Hint
+    } else {
+        codegen_fn_attrs.inline
+    };
     to_add.extend(inline_attr(cx, inline));
 
     // The `uwtable` attribute according to LLVM is:
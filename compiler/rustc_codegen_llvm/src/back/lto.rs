@@ -616,7 +616,10 @@ pub(crate) fn run_pass_manager(
             );
         }
         let opt_stage = if thin { llvm::OptStage::ThinLTO } else { llvm::OptStage::FatLTO };
-        let opt_level = config.opt_level.unwrap_or(config::OptLevel::No);
+        // Fat LTO merges all modules into one, which is optimized at the crate's level.
+        let opt_level =
+            if thin { cgcx.module_opt_level(&module.name, config) } else { config.opt_level };
+        let opt_level = opt_level.unwrap_or(config::OptLevel::No);
         write::llvm_optimize(cgcx, dcx, module, config, opt_level, opt_stage)?;
     }
     debug!("lto done");
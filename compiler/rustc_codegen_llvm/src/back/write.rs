@@ -6,8 +6,8 @@
 use crate::base;
 use crate::common;
 use crate::errors::{
-    CopyBitcode, FromLlvmDiag, FromLlvmOptimizationDiag, LlvmError, UnknownCompression,
-    WithLlvmError, WriteBytecode,
+    CopyBitcode, FromLlvmDiag, FromLlvmOptimizationDiag, LlvmError, MergeRawProfiles,
+    PgoProfileMismatch, UnknownCompression, WithLlvmError, WriteBytecode,
 };
 use crate::llvm::{self, DiagnosticInfo, PassManager};
 use crate::llvm_util;
@@ -19,8 +19,8 @@
 };
 use rustc_codegen_ssa::back::link::ensure_removed;
 use rustc_codegen_ssa::back::write::{
-    BitcodeSection, CodegenContext, EmitObj, ModuleConfig, TargetMachineFactoryConfig,
-    TargetMachineFactoryFn,
+    merged_pgo_use_profile_path, BitcodeSection, CodegenContext, EmitObj, ModuleConfig,
+    TargetMachineFactoryConfig, TargetMachineFactoryFn,
 };
 use rustc_codegen_ssa::traits::*;
 use rustc_codegen_ssa::{CompiledModule, ModuleCodegen};
@@ -29,7 +29,7 @@
 use rustc_errors::{DiagCtxt, FatalError, Level};
 use rustc_fs_util::{link_or_copy, path_to_c_string};
 use rustc_middle::ty::TyCtxt;
-use rustc_session::config::{self, Lto, OutputType, Passes};
+use rustc_session::config::{self, Lto, OutputFilenames, OutputType, Passes};
 use rustc_session::config::{RemapPathScopeComponents, SplitDwarfKind, SwitchWithOptPath};
 use rustc_session::Session;
 use rustc_span::symbol::sym;
@@ -211,7 +211,8 @@ pub fn target_machine_factory(
     let abi = SmallCStr::new(&sess.target.llvm_abiname);
     let trap_unreachable =
         sess.opts.unstable_opts.trap_unreachable.unwrap_or(sess.target.trap_unreachable);
-    let emit_stack_size_section = sess.opts.unstable_opts.emit_stack_sizes;
+    let emit_stack_size_section = sess.opts.unstable_opts.emit_stack_sizes
+        || sess.opts.output_types.contains_key(&OutputType::StackSizes);
 
     let asm_comments = sess.opts.unstable_opts.asm_comments;
     let relax_elf_relocations =
@@ -452,7 +453,19 @@ fn report_inline_asm(
                 message: &opt.message,
             });
         }
-        llvm::diagnostic::PGO(diagnostic_ref) | llvm::diagnostic::Linker(diagnostic_ref) => {
+        llvm::diagnostic::PGO(diagnostic_ref) => {
+            let message = llvm::build_string(|s| {
+                llvm::LLVMRustWriteDiagnosticInfoToString(diagnostic_ref, s)
+            })
+            .expect("non-UTF8 diagnostic");
+            // These are emitted for functions that changed since the profile was collected.
+            if unsafe { llvm::LLVMRustIsStalePGOProfileDiagnostic(diagnostic_ref) } {
+                dcx.emit_warn(PgoProfileMismatch { message });
+            } else {
+                dcx.emit_warn(FromLlvmDiag { message });
+            }
+        }
+        llvm::diagnostic::Linker(diagnostic_ref) => {
             let message = llvm::build_string(|s| {
                 llvm::LLVMRustWriteDiagnosticInfoToString(diagnostic_ref, s)
             })
@@ -485,6 +498,26 @@ fn get_pgo_gen_path(config: &ModuleConfig) -> Option<CString> {
     }
 }
 
+/// Merges the raw profiles passed to `-C profile-use` into the indexed profile that the PGO
+/// passes read, so that no separate `llvm-profdata merge` step is needed.
+pub(crate) fn merge_raw_pgo_use_profiles(sess: &Session, outputs: &OutputFilenames) {
+    let Some(raw_profiles) = sess.raw_pgo_use_profiles() else {
+        return;
+    };
+    let _timer = sess.timer("llvm_merge_raw_profiles");
+
+    let inputs: Vec<CString> = raw_profiles.iter().map(|path| path_to_c_string(path)).collect();
+    let input_ptrs: Vec<*const c_char> = inputs.iter().map(|input| input.as_ptr()).collect();
+    let output = path_to_c_string(&merged_pgo_use_profile_path(outputs));
+    let result = unsafe {
+        llvm::LLVMRustMergeRawProfiles(input_ptrs.as_ptr(), input_ptrs.len(), output.as_ptr())
+    };
+    if result == llvm::LLVMRustResult::Failure {
+        let llvm_err = llvm::last_error().unwrap_or_else(|| "unknown LLVM error".to_string());
+        sess.dcx().emit_fatal(MergeRawProfiles { llvm_err });
+    }
+}
+
 fn get_pgo_use_path(config: &ModuleConfig) -> Option<CString> {
     config
         .pgo_use
@@ -513,6 +546,11 @@ pub(crate) unsafe fn llvm_optimize(
 ) -> Result<(), FatalError> {
     let unroll_loops =
         opt_level != config::OptLevel::Size && opt_level != config::OptLevel::SizeMin;
+    // Like `ModuleConfig::new`, but for `opt_level`, which differs from the crate's level for
+    // modules holding `#[optimize(level = "...")]` functions.
+    let vectorize_loop = !cgcx.opts.cg.no_vectorize_loops
+        && (opt_level == config::OptLevel::Default || opt_level == config::OptLevel::Aggressive);
+    let vectorize_slp = !cgcx.opts.cg.no_vectorize_slp && opt_level == config::OptLevel::Aggressive;
     let using_thin_buffers = opt_stage == llvm::OptStage::PreLinkThinLTO || config.bitcode_needed();
     let pgo_gen_path = get_pgo_gen_path(config);
     let pgo_use_path = get_pgo_use_path(config);
@@ -577,8 +615,8 @@ pub(crate) unsafe fn llvm_optimize(
         using_thin_buffers,
         config.merge_functions,
         unroll_loops,
-        config.vectorize_slp,
-        config.vectorize_loop,
+        vectorize_slp,
+        vectorize_loop,
         config.no_builtins,
         config.emit_lifetime_markers,
         sanitizer_options.as_ref(),
@@ -622,7 +660,7 @@ pub(crate) unsafe fn optimize(
         llvm::LLVMWriteBitcodeToFile(llmod, out.as_ptr());
     }
 
-    if let Some(opt_level) = config.opt_level {
+    if let Some(opt_level) = cgcx.module_opt_level(&module.name, config) {
         let opt_stage = match cgcx.lto {
             Lto::Fat => llvm::OptStage::PreLinkFatLTO,
             Lto::Thin | Lto::ThinLocal => llvm::OptStage::PreLinkThinLTO,
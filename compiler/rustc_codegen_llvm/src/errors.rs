@@ -151,6 +151,20 @@ pub(crate) struct LtoBitcodeFromRlib {
     pub llvm_err: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_llvm_merge_raw_profiles)]
+#[note]
+pub(crate) struct MergeRawProfiles {
+    pub llvm_err: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_llvm_pgo_profile_mismatch)]
+#[help]
+pub(crate) struct PgoProfileMismatch {
+    pub message: String,
+}
+
 #[derive(Diagnostic)]
 pub enum LlvmError<'a> {
     #[diag(codegen_llvm_write_output)]
@@ -355,6 +355,8 @@ fn codegen_crate<'tcx>(
         metadata: EncodedMetadata,
         need_metadata_module: bool,
     ) -> Box<dyn Any> {
+        back::write::merge_raw_pgo_use_profiles(tcx.sess, tcx.output_filenames(()));
+
         Box::new(rustc_codegen_ssa::base::codegen_crate(
             LlvmCodegenBackend(()),
             tcx,
@@ -375,6 +377,12 @@ fn join_codegen(
             .expect("Expected LlvmCodegenBackend's OngoingCodegen, found Box<Any>")
             .join(sess);
 
+        if sess.raw_pgo_use_profiles().is_some() && !sess.opts.cg.save_temps {
+            let merged_profile =
+                rustc_codegen_ssa::back::write::merged_pgo_use_profile_path(outputs);
+            rustc_codegen_ssa::back::link::ensure_removed(sess.dcx(), &merged_profile);
+        }
+
         if sess.opts.unstable_opts.llvm_time_trace {
             sess.time("llvm_dump_timing_file", || {
                 let file_name = outputs.with_extension("llvm_timings.json");
@@ -990,6 +990,15 @@ pub fn LLVMConstInBoundsGEP2<'a>(
     pub fn LLVMSetGlobalConstant(GlobalVar: &Value, IsConstant: Bool);
     pub fn LLVMSetTailCall(CallInst: &Value, IsTailCall: Bool);
 
+    // Operations on global aliases
+    pub fn LLVMAddAlias2<'a>(
+        M: &'a Module,
+        ValueTy: &'a Type,
+        AddressSpace: c_uint,
+        Aliasee: &'a Value,
+        Name: *const c_char,
+    ) -> &'a Value;
+
     // Operations on attributes
     pub fn LLVMCreateStringAttribute(
         C: &Context,
@@ -2271,6 +2280,7 @@ pub fn LLVMRustUnpackInlineAsmDiagnostic<'a>(
     #[allow(improper_ctypes)]
     pub fn LLVMRustWriteDiagnosticInfoToString(DI: &DiagnosticInfo, s: &RustString);
     pub fn LLVMRustGetDiagInfoKind(DI: &DiagnosticInfo) -> DiagnosticKind;
+    pub fn LLVMRustIsStalePGOProfileDiagnostic(DI: &DiagnosticInfo) -> bool;
 
     pub fn LLVMRustGetSMDiagnostic<'a>(
         DI: &'a DiagnosticInfo,
@@ -2311,6 +2321,12 @@ pub fn LLVMRustWriteImportLibrary(
         MinGW: bool,
     ) -> LLVMRustResult;
 
+    pub fn LLVMRustMergeRawProfiles(
+        Inputs: *const *const c_char,
+        NumInputs: size_t,
+        Output: *const c_char,
+    ) -> LLVMRustResult;
+
     pub fn LLVMRustSetDataLayoutFromTargetMachine<'a>(M: &'a Module, TM: &'a TargetMachine);
 
     pub fn LLVMRustBuildOperandBundleDef(
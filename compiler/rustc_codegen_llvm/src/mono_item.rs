@@ -1,3 +1,4 @@
+use crate::abi::FnAbiLlvmExt;
 use crate::attributes;
 use crate::base;
 use crate::context::CodegenCx;
@@ -5,6 +6,7 @@
 use crate::llvm;
 use crate::type_of::LayoutLlvmExt;
 use rustc_codegen_ssa::traits::*;
+use rustc_data_structures::small_c_str::SmallCStr;
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::{DefId, LOCAL_CRATE};
 use rustc_middle::bug;
@@ -12,6 +14,7 @@
 use rustc_middle::ty::layout::{FnAbiOf, LayoutOf};
 use rustc_middle::ty::{self, Instance, TypeVisitableExt};
 use rustc_session::config::CrateType;
+use rustc_target::abi::HasDataLayout;
 use rustc_target::spec::RelocModel;
 
 impl<'tcx> PreDefineMethods<'tcx> for CodegenCx<'_, 'tcx> {
@@ -97,6 +100,55 @@ fn predefine_fn(
 
         self.instances.borrow_mut().insert(instance, lldecl);
     }
+
+    fn supports_fn_aliases(&self) -> bool {
+        true
+    }
+
+    fn predefine_fn_alias(
+        &self,
+        instance: Instance<'tcx>,
+        target: Instance<'tcx>,
+        linkage: Linkage,
+        visibility: Visibility,
+        symbol_name: &str,
+    ) {
+        assert!(!instance.args.has_infer());
+
+        if self.get_declared_value(symbol_name).is_some() {
+            self.sess().dcx().emit_fatal(SymbolAlreadyDefined {
+                span: self.tcx.def_span(instance.def_id()),
+                symbol_name,
+            });
+        }
+
+        // The target is predefined by this CGU as well, possibly after this alias, in which
+        // case this declares it early.
+        let aliasee = self.get_fn(target);
+        let llty = self.fn_abi_of_instance(target, ty::List::empty()).llvm_type(self);
+        let name = SmallCStr::new(symbol_name);
+        let llalias = unsafe {
+            llvm::LLVMAddAlias2(
+                self.llmod,
+                llty,
+                self.data_layout().instruction_address_space.0,
+                aliasee,
+                name.as_ptr(),
+            )
+        };
+
+        unsafe {
+            llvm::LLVMRustSetLinkage(llalias, base::linkage_to_llvm(linkage));
+            llvm::LLVMRustSetVisibility(llalias, base::visibility_to_llvm(visibility));
+            if self.should_assume_dso_local(llalias, false) {
+                llvm::LLVMRustSetDSOLocal(llalias, true);
+            }
+        }
+
+        debug!("predefine_fn_alias: instance = {:?}, target = {:?}", instance, target);
+
+        self.instances.borrow_mut().insert(instance, llalias);
+    }
 }
 
 impl CodegenCx<'_, '_> {
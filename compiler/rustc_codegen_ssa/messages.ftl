@@ -25,6 +25,9 @@ codegen_ssa_copy_path_buf = unable to copy {$source_file} to {$output_path}: {$e
 
 codegen_ssa_create_temp_dir = couldn't create a temp dir: {$error}
 
+codegen_ssa_dwarf_package_zstd_unsupported = `-Z debuginfo-compression=zstd` is not supported for DWARF packages
+    .note = the DWARF package will be written uncompressed
+
 codegen_ssa_error_creating_remark_dir = failed to create remark directory: {$error}
 
 codegen_ssa_expected_coverage_symbol = expected `coverage(off)` or `coverage(on)`
@@ -152,15 +155,25 @@ codegen_ssa_lib_def_write_failure = failed to write lib.def file: {$error}
 
 codegen_ssa_link_exe_unexpected_error = `link.exe` returned an unexpected error
 
+codegen_ssa_link_map_not_linked = `--emit=link-map` has no effect for crate type `{$crate_type}`, which isn't linked
+
+codegen_ssa_link_map_read_failure = failed to read linker map file {$path}: {$error}
+
+codegen_ssa_link_map_unsupported_linker = `--emit=link-map` is only supported by the GNU ld, LLD and mold linkers on ELF targets
+
 codegen_ssa_link_script_unavailable = can only use link script when linking with GNU-like linker
 
 codegen_ssa_link_script_write_failure = failed to write link script to {$path}: {$error}
 
 codegen_ssa_linker_file_stem = couldn't extract file stem from specified linker
 
+codegen_ssa_linker_flavor_fallback = linker `{$linker}` not found, falling back to linker flavor `{$fallback}`
+
 codegen_ssa_linker_not_found = linker `{$linker_path}` not found
     .note = {$error}
 
+codegen_ssa_linker_threads_unsupported = `-Z linker-threads` is only supported by the mold and LLD linkers on ELF targets
+
 codegen_ssa_linker_unsupported_modifier = `as-needed` modifier not supported for current linker
 
 codegen_ssa_linking_failed = linking with `{$linker_path}` failed: {$exit_status}
@@ -220,6 +233,11 @@ codegen_ssa_static_library_native_artifacts = Link against the following native
 
 codegen_ssa_static_library_native_artifacts_to_file = Native artifacts to link against have been written to {$path}. The order and any duplication can be significant on some platforms.
 
+codegen_ssa_stack_sizes_not_found = no stack sizes were found in the object files, the `--emit=stack-sizes` report is empty
+    .note = stack sizes are only recorded by the LLVM backend, in ELF object files
+
+codegen_ssa_stack_sizes_read_failure = failed to read stack sizes from object file {$path}: {$error}
+
 codegen_ssa_stripping_debug_info_failed = stripping debug info with `{$util}` failed: {$status}
     .note = {$output}
 
@@ -11,7 +11,8 @@
 use rustc_middle::middle::debugger_visualizer::DebuggerVisualizerFile;
 use rustc_middle::middle::dependency_format::Linkage;
 use rustc_middle::middle::exported_symbols::SymbolExportKind;
-use rustc_session::config::{self, CFGuard, CrateType, DebugInfo, OutFileName, Strip};
+use rustc_session::config::{self, CFGuard, CrateType, DebugInfo, DebugInfoCompression};
+use rustc_session::config::{OutFileName, Strip};
 use rustc_session::config::{OutputFilenames, OutputType, PrintKind, SplitDwarfKind};
 use rustc_session::cstore::DllImport;
 use rustc_session::output::{check_file_is_writeable, invalid_output_for_target, out_filename};
@@ -30,6 +31,7 @@
 
 use super::archive::{ArchiveBuilder, ArchiveBuilderBuilder};
 use super::command::Command;
+use super::link_map;
 use super::linker::{self, Linker};
 use super::metadata::{create_wrapper_file, MetadataPosition};
 use super::rpath::{self, RPathConfig};
@@ -50,6 +52,7 @@
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::process::{ExitStatus, Output, Stdio};
+use std::time::UNIX_EPOCH;
 use std::{env, fmt, fs, io, mem, str};
 
 #[derive(Default)]
@@ -118,6 +121,12 @@ pub fn link_binary<'a>(
             let crate_name = format!("{}", codegen_results.crate_info.local_crate_name);
             let out_filename =
                 output.file_for_writing(outputs, OutputType::Exe, Some(crate_name.as_str()));
+            if matches!(crate_type, CrateType::Rlib | CrateType::Staticlib)
+                && sess.opts.output_types.contains_key(&OutputType::LinkMap)
+            {
+                sess.dcx()
+                    .emit_warn(errors::LinkMapNotLinked { crate_type: crate_type.to_string() });
+            }
             match crate_type {
                 CrateType::Rlib => {
                     let _timer = sess.timer("link_rlib");
@@ -149,6 +158,9 @@ pub fn link_binary<'a>(
                         codegen_results,
                         path.as_ref(),
                     )?;
+                    if sess.opts.output_types.contains_key(&OutputType::LinkMap) {
+                        emit_link_map_report(sess, outputs, path.as_ref(), &out_filename);
+                    }
                 }
             }
             if sess.opts.json_artifact_notifications {
@@ -664,22 +676,71 @@ fn read_input(&self, path: &Path) -> std::io::Result<&[u8]> {
         }
     }
 
+    /// Packages `inputs` on their own into `name` in the incremental session directory, and
+    /// returns the objects to add to the final package in their place: that package, if the
+    /// inputs had any split debuginfo, or the inputs themselves outside of incremental builds.
+    ///
+    /// The package is reused for as long as none of the inputs change. `thorin` can't replace the
+    /// units of some of its inputs in an existing package, so it is rebuilt entirely when any of
+    /// them does.
+    fn incremental_dwarf_package(
+        sess: &Session,
+        name: &str,
+        inputs: Vec<PathBuf>,
+    ) -> Result<Vec<PathBuf>, thorin::Error> {
+        let Some(dir) = sess.incr_comp_session_dir_opt() else { return Ok(inputs) };
+        let Some(key) = dwarf_package_key(&inputs) else { return Ok(inputs) };
+        let dwp_path = dir.join(format!("{name}.dwp"));
+        let key_path = dir.join(format!("{name}.dwp.key"));
+
+        if fs::read_to_string(&key_path).ok().as_ref() != Some(&key) {
+            // The files in the session directory can be hard links to the ones of the previous
+            // session, so they are replaced instead of being overwritten.
+            let _ = fs::remove_file(&dwp_path);
+            let _ = fs::remove_file(&key_path);
+            let thorin_sess = ThorinSession::default();
+            let mut package = thorin::DwarfPackage::new(&thorin_sess);
+            for input in &inputs {
+                debug!(?input);
+                package.add_input_object(input)?;
+            }
+            match package.finish() {
+                Ok(dwp) => fs::write(&dwp_path, dwp.write()?)?,
+                // The inputs don't have split debuginfo, as is the case for the standard library
+                // distributed by rustup.
+                Err(thorin::Error::NoOutputObjectCreated) => {}
+                Err(e) => return Err(e),
+            }
+            fs::write(&key_path, key)?;
+        }
+
+        if dwp_path.exists() {
+            debug!("using incremental package {dwp_path:?}");
+            Ok(vec![dwp_path])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    if sess.opts.debuginfo_compression == DebugInfoCompression::Zstd {
+        sess.dcx().emit_warn(errors::DwarfPackageZstdUnsupported);
+    }
+
     match sess.time("run_thorin", || -> Result<(), thorin::Error> {
         let thorin_sess = ThorinSession::default();
         let mut package = thorin::DwarfPackage::new(&thorin_sess);
 
         // Input objs contain .o/.dwo files from the current crate.
-        match sess.opts.unstable_opts.split_dwarf_kind {
+        let input_objs = match sess.opts.unstable_opts.split_dwarf_kind {
             SplitDwarfKind::Single => {
-                for input_obj in cg_results.modules.iter().filter_map(|m| m.object.as_ref()) {
-                    package.add_input_object(input_obj)?;
-                }
+                cg_results.modules.iter().filter_map(|m| m.object.clone()).collect()
             }
             SplitDwarfKind::Split => {
-                for input_obj in cg_results.modules.iter().filter_map(|m| m.dwarf_object.as_ref()) {
-                    package.add_input_object(input_obj)?;
-                }
+                cg_results.modules.iter().filter_map(|m| m.dwarf_object.clone()).collect()
             }
+        };
+        for input_obj in incremental_dwarf_package(sess, "crate", input_objs)? {
+            package.add_input_object(&input_obj)?;
         }
 
         // Input rlibs contain .o/.dwo files from dependencies.
@@ -689,11 +750,13 @@ fn read_input(&self, path: &Path) -> std::io::Result<&[u8]> {
             .items()
             .filter_map(|(_, csource)| csource.rlib.as_ref())
             .map(|(path, _)| path)
-            .into_sorted_stable_ord();
-
-        for input_rlib in input_rlibs {
+            .into_sorted_stable_ord()
+            .into_iter()
+            .cloned()
+            .collect();
+        for input_rlib in incremental_dwarf_package(sess, "dependencies", input_rlibs)? {
             debug!(?input_rlib);
-            package.add_input_object(input_rlib)?;
+            package.add_input_object(&input_rlib)?;
         }
 
         // Failing to read the referenced objects is expected for dependencies where the path in the
@@ -719,7 +782,15 @@ fn read_input(&self, path: &Path) -> std::io::Result<&[u8]> {
                 .open(dwp_out_filename)?,
         );
         let mut output_stream = object::write::StreamingBuffer::new(output_stream);
-        package.finish()?.emit(&mut output_stream)?;
+        match sess.opts.debuginfo_compression {
+            DebugInfoCompression::None | DebugInfoCompression::Zstd => {
+                package.finish()?.emit(&mut output_stream)?
+            }
+            DebugInfoCompression::Zlib => {
+                let dwp = package.finish()?.write()?;
+                compress_dwarf_package(&dwp)?.emit(&mut output_stream)?;
+            }
+        }
         output_stream.result()?;
         output_stream.into_inner().flush()?;
 
@@ -730,6 +801,81 @@ fn read_input(&self, path: &Path) -> std::io::Result<&[u8]> {
     }
 }
 
+/// Returns the key of an incremental DWARF package of `inputs`, which changes when any of them is
+/// rewritten.
+fn dwarf_package_key(inputs: &[PathBuf]) -> Option<String> {
+    let mut key = String::new();
+    for input in inputs {
+        let metadata = fs::metadata(input).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        key.push_str(&format!("{} {} {}\n", input.display(), metadata.len(), modified.as_nanos()));
+    }
+    Some(key)
+}
+
+/// Compresses the debug sections of the ELF DWARF package `dwp` with zlib, like
+/// `-Zdebuginfo-compression` does for the object files written by the codegen backend. The
+/// sections are read uncompressed by `thorin` and written uncompressed by it, so this happens in
+/// a separate pass.
+fn compress_dwarf_package(dwp: &[u8]) -> Result<object::write::Object<'_>, thorin::Error> {
+    use object::elf::{CompressionHeader32, CompressionHeader64, SHF_COMPRESSED};
+    use object::{Object, ObjectSection, SectionFlags, SectionKind, U32, U64};
+
+    let input = object::File::parse(dwp).map_err(thorin::Error::ObjectRead)?;
+    let endian = input.endianness();
+    let mut output = object::write::Object::new(input.format(), input.architecture(), endian);
+    output.flags = input.flags();
+    for section in input.sections() {
+        // The symbol and string tables are written by `object` itself.
+        if section.kind() == SectionKind::Metadata {
+            continue;
+        }
+        let name = section.name_bytes().map_err(thorin::Error::ObjectRead)?;
+        let data = section.data().map_err(thorin::Error::ObjectRead)?;
+        let id = output.add_section(Vec::new(), name.to_vec(), section.kind());
+        output.section_mut(id).flags = section.flags();
+        let SectionFlags::Elf { sh_flags } = section.flags() else {
+            output.set_section_data(id, data, section.align());
+            continue;
+        };
+        if !name.starts_with(b".debug_") || data.is_empty() {
+            output.set_section_data(id, data, section.align());
+            continue;
+        }
+
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data)?;
+        let compressed = encoder.finish()?;
+        let mut compressed_data = if input.is_64() {
+            object::pod::bytes_of(&CompressionHeader64 {
+                ch_type: U32::new(endian, object::elf::ELFCOMPRESS_ZLIB),
+                ch_reserved: U32::new(endian, 0),
+                ch_size: U64::new(endian, data.len() as u64),
+                ch_addralign: U64::new(endian, section.align()),
+            })
+            .to_vec()
+        } else {
+            object::pod::bytes_of(&CompressionHeader32 {
+                ch_type: U32::new(endian, object::elf::ELFCOMPRESS_ZLIB),
+                ch_size: U32::new(endian, data.len() as u32),
+                ch_addralign: U32::new(endian, section.align() as u32),
+            })
+            .to_vec()
+        };
+        compressed_data.extend_from_slice(&compressed);
+        // Small sections can grow when compressed.
+        if compressed_data.len() >= data.len() {
+            output.set_section_data(id, data, section.align());
+            continue;
+        }
+        output.section_mut(id).flags =
+            SectionFlags::Elf { sh_flags: sh_flags | SHF_COMPRESSED as u64 };
+        output.set_section_data(id, compressed_data, if input.is_64() { 8 } else { 4 });
+    }
+    Ok(output)
+}
+
 /// Create a dynamic library or executable.
 ///
 /// This will invoke the system linker/cc to create the resulting file. This links to all upstream
@@ -744,6 +890,7 @@ fn link_natively<'a>(
 ) -> Result<(), ErrorGuaranteed> {
     info!("preparing {:?} to {:?}", crate_type, out_filename);
     let (linker_path, flavor) = linker_and_flavor(sess);
+    let (linker_path, flavor) = fall_back_if_linker_is_missing(sess, linker_path, flavor);
     let self_contained_components = self_contained_components(sess, crate_type);
     let mut cmd = linker_with_args(
         &linker_path,
@@ -802,7 +949,8 @@ fn link_natively<'a>(
         // then it should not default to linking executables as pie. Different
         // versions of gcc seem to use different quotes in the error message so
         // don't check for them.
-        if matches!(flavor, LinkerFlavor::Gnu(Cc::Yes, _))
+        if flavor.is_gnu()
+            && flavor.uses_cc()
             && unknown_arg_regex.is_match(&out)
             && out.contains("-no-pie")
             && cmd.get_args().iter().any(|e| e.to_string_lossy() == "-no-pie")
@@ -820,7 +968,8 @@ fn link_natively<'a>(
 
         // Detect '-static-pie' used with an older version of gcc or clang not supporting it.
         // Fallback from '-static-pie' to '-static' in that case.
-        if matches!(flavor, LinkerFlavor::Gnu(Cc::Yes, _))
+        if flavor.is_gnu()
+            && flavor.uses_cc()
             && unknown_arg_regex.is_match(&out)
             && (out.contains("-static-pie") || out.contains("--no-dynamic-linker"))
             && cmd.get_args().iter().any(|e| e.to_string_lossy() == "-static-pie")
@@ -1346,7 +1495,8 @@ fn infer_from(
                     LinkerFlavor::Gnu(Cc::Yes, _)
                     | LinkerFlavor::Darwin(Cc::Yes, _)
                     | LinkerFlavor::WasmLld(Cc::Yes)
-                    | LinkerFlavor::Unix(Cc::Yes) => {
+                    | LinkerFlavor::Unix(Cc::Yes)
+                    | LinkerFlavor::Mold(Cc::Yes) => {
                         if cfg!(any(target_os = "solaris", target_os = "illumos")) {
                             // On historical Solaris systems, "cc" may have
                             // been Sun Studio, which is not flag-compatible
@@ -1366,6 +1516,7 @@ fn infer_from(
                     LinkerFlavor::Gnu(..) | LinkerFlavor::Darwin(..) | LinkerFlavor::Unix(..) => {
                         "ld"
                     }
+                    LinkerFlavor::Mold(..) => "mold",
                     LinkerFlavor::Msvc(..) => "link.exe",
                     LinkerFlavor::EmCc => {
                         if cfg!(windows) {
@@ -1397,6 +1548,8 @@ fn infer_from(
         // The linker flavors that are non-target specific can be directly translated to LinkerFlavor
         Some(LinkerFlavorCli::Llbc) => Some(LinkerFlavor::Llbc),
         Some(LinkerFlavorCli::Ptx) => Some(LinkerFlavor::Ptx),
+        // Mold replaces the target's GNU linker, so it isn't a hint for its flavor.
+        Some(LinkerFlavorCli::Mold(cc)) => Some(LinkerFlavor::Mold(cc)),
         // The linker flavors that corresponds to targets needs logic that keeps the base LinkerFlavor
         _ => sess
             .opts
@@ -1419,6 +1572,48 @@ fn infer_from(
     bug!("Not enough information provided to determine how to invoke the linker");
 }
 
+/// Looks for `program` in the rustc tools directories and the `PATH`, unless it's already a path.
+fn find_linker_program(sess: &Session, program: &Path) -> Option<PathBuf> {
+    if program.components().count() > 1 {
+        return program.is_file().then(|| program.to_path_buf());
+    }
+    let path = env::var_os("PATH").unwrap_or_default();
+    sess.get_tools_search_paths(false)
+        .into_iter()
+        .chain(env::split_paths(&path))
+        .map(|dir| dir.join(program))
+        .find(|full_path| full_path.is_file())
+}
+
+/// Mold is often not installed, so when it was requested via the linker flavor but can't be
+/// found, we warn and use the target's GNU linker instead of failing the build.
+fn fall_back_if_linker_is_missing(
+    sess: &Session,
+    linker_path: PathBuf,
+    flavor: LinkerFlavor,
+) -> (PathBuf, LinkerFlavor) {
+    let LinkerFlavor::Mold(cc) = flavor else {
+        return (linker_path, flavor);
+    };
+
+    // An explicitly specified linker is used as is, missing or not.
+    if cc == Cc::No && sess.opts.cg.linker.is_some() {
+        return (linker_path, flavor);
+    }
+
+    // When the linker is invoked through `cc`, `cc` itself looks it up in the `PATH`.
+    let linker = if cc == Cc::No { linker_path.clone() } else { PathBuf::from("mold") };
+    if find_linker_program(sess, &linker).is_some() {
+        return (linker_path, flavor);
+    }
+
+    let fallback = LinkerFlavor::Gnu(cc, Lld::No);
+    let fallback_desc = if cc == Cc::Yes { "gnu-cc" } else { "gnu" };
+    sess.dcx().emit_warn(errors::LinkerFlavorFallback { linker, fallback: fallback_desc });
+    let linker_path = if cc == Cc::No { PathBuf::from("ld") } else { linker_path };
+    (linker_path, fallback)
+}
+
 /// Returns a pair of boolean indicating whether we should preserve the object and
 /// dwarf object files on the filesystem for their debug information. This is often
 /// useful with split-dwarf like schemes.
@@ -1864,7 +2059,7 @@ fn add_post_link_objects(
 /// Add arbitrary "pre-link" args defined by the target spec or from command line.
 /// FIXME: Determine where exactly these args need to be inserted.
 fn add_pre_link_args(cmd: &mut dyn Linker, sess: &Session, flavor: LinkerFlavor) {
-    if let Some(args) = sess.target.pre_link_args.get(&flavor) {
+    if let Some(args) = sess.target.pre_link_args.get(&flavor.link_args_flavor()) {
         cmd.args(args.iter().map(Deref::deref));
     }
     cmd.args(&sess.opts.unstable_opts.pre_link_args);
@@ -1912,15 +2107,15 @@ fn add_late_link_args(
             *ty == crate_type && list.iter().any(|&linkage| linkage == Linkage::Dynamic)
         });
     if any_dynamic_crate {
-        if let Some(args) = sess.target.late_link_args_dynamic.get(&flavor) {
+        if let Some(args) = sess.target.late_link_args_dynamic.get(&flavor.link_args_flavor()) {
             cmd.args(args.iter().map(Deref::deref));
         }
     } else {
-        if let Some(args) = sess.target.late_link_args_static.get(&flavor) {
+        if let Some(args) = sess.target.late_link_args_static.get(&flavor.link_args_flavor()) {
             cmd.args(args.iter().map(Deref::deref));
         }
     }
-    if let Some(args) = sess.target.late_link_args.get(&flavor) {
+    if let Some(args) = sess.target.late_link_args.get(&flavor.link_args_flavor()) {
         cmd.args(args.iter().map(Deref::deref));
     }
 }
@@ -1928,7 +2123,7 @@ fn add_late_link_args(
 /// Add arbitrary "post-link" args defined by the target spec.
 /// FIXME: Determine where exactly these args need to be inserted.
 fn add_post_link_args(cmd: &mut dyn Linker, sess: &Session, flavor: LinkerFlavor) {
-    if let Some(args) = sess.target.post_link_args.get(&flavor) {
+    if let Some(args) = sess.target.post_link_args.get(&flavor.link_args_flavor()) {
         cmd.args(args.iter().map(Deref::deref));
     }
 }
@@ -2126,6 +2321,7 @@ fn linker_with_args<'a>(
         flavor,
         self_contained_components.are_any_components_enabled(),
         &codegen_results.crate_info.target_cpu,
+        codegen_results.crate_info.linker_plugin_lto_args.as_deref(),
     );
     let link_output_kind = link_output_kind(sess, crate_type);
 
@@ -2342,6 +2538,13 @@ fn add_order_independent_options(
     // Take care of the flavors and CLI options requesting the `lld` linker.
     add_lld_args(cmd, sess, flavor, self_contained_components);
 
+    // Take care of the flavors requesting the mold linker.
+    add_mold_args(cmd, flavor);
+
+    add_link_map_args(cmd, sess, flavor, tmpdir);
+
+    add_linker_threads_args(cmd, sess, flavor);
+
     add_apple_sdk(cmd, sess, flavor);
 
     add_link_script(cmd, sess, tmpdir, crate_type);
@@ -3051,6 +3254,74 @@ fn get_apple_sdk_root(sdk_name: &str) -> Result<String, errors::AppleSdkRootErro
     }
 }
 
+/// The name of the map file requested from the linker for `--emit=link-map`, in the temporary
+/// directory of the link.
+const LINK_MAP_FILENAME: &str = "linker.map";
+
+/// Asks the linker to write the map file that `--emit=link-map` reports on.
+fn add_link_map_args(cmd: &mut dyn Linker, sess: &Session, flavor: LinkerFlavor, tmpdir: &Path) {
+    if !sess.opts.output_types.contains_key(&OutputType::LinkMap) {
+        return;
+    }
+    // GNU ld, LLD and mold all support `-Map`, in formats that `link_map` can parse.
+    if !flavor.is_gnu() {
+        sess.dcx().emit_warn(errors::LinkMapUnsupportedLinker);
+        return;
+    }
+    let mut arg = OsString::from("-Map=");
+    arg.push(tmpdir.join(LINK_MAP_FILENAME));
+    cmd.linker_arg(&arg, false);
+}
+
+/// Writes the `--emit=link-map` report for the map file the linker wrote into `tmpdir`.
+fn emit_link_map_report(
+    sess: &Session,
+    outputs: &OutputFilenames,
+    tmpdir: &Path,
+    out_filename: &Path,
+) {
+    let map_path = tmpdir.join(LINK_MAP_FILENAME);
+    let map = match fs::read_to_string(&map_path) {
+        Ok(map) => map,
+        // The linker doesn't support map files, which was already reported.
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return,
+        Err(error) => {
+            sess.dcx().emit_err(errors::LinkMapReadFailure { path: map_path, error });
+            return;
+        }
+    };
+    let report = link_map::render_report(out_filename, &link_map::parse_map(&map));
+    let out = outputs.path(OutputType::LinkMap);
+    out.overwrite(&report, sess);
+    if sess.opts.json_artifact_notifications && !out.is_stdout() {
+        sess.dcx().emit_artifact_notification(out.as_path(), "link-map");
+    }
+}
+
+/// When using the mold linker flavor through a C/C++ compiler, ask it to use mold instead of the
+/// system linker. Both GCC (since version 12.1) and Clang (since version 12) support this.
+fn add_mold_args(cmd: &mut dyn Linker, flavor: LinkerFlavor) {
+    if flavor == LinkerFlavor::Mold(Cc::Yes) {
+        cmd.arg("-fuse-ld=mold");
+    }
+}
+
+/// Passes `-Z linker-threads` on to the linkers that can be told how many threads to use.
+fn add_linker_threads_args(cmd: &mut dyn Linker, sess: &Session, flavor: LinkerFlavor) {
+    let Some(threads) = sess.opts.unstable_opts.linker_threads else {
+        return;
+    };
+    let arg = match flavor {
+        LinkerFlavor::Mold(..) => format!("--thread-count={threads}"),
+        LinkerFlavor::Gnu(_, Lld::Yes) => format!("--threads={threads}"),
+        _ => {
+            sess.dcx().emit_warn(errors::LinkerThreadsUnsupported);
+            return;
+        }
+    };
+    cmd.linker_arg(OsStr::new(&arg), false);
+}
+
 /// When using the linker flavors opting in to `lld`, add the necessary paths and arguments to
 /// invoke it:
 /// - when the self-contained linker flag is active: the build of `lld` distributed with rustc,
@@ -0,0 +1,345 @@
+//! Postprocessing of the map files written by GNU ld, LLD and mold with `-Map`, for
+//! `--emit=link-map`. The report lists the symbols that survived linking with their sizes,
+//! demangled and grouped by crate and by generic definition, and the input file each symbol was
+//! linked from.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+/// A symbol that ended up in the linked output.
+#[derive(Debug, PartialEq)]
+pub(crate) struct LinkedSymbol {
+    pub name: String,
+    pub size: u64,
+    /// The object file or archive member the symbol was linked from.
+    pub input: String,
+}
+
+/// An input section listed in a map file, with the symbols it defines.
+struct InputSection {
+    name: String,
+    addr: u64,
+    size: u64,
+    input: String,
+    /// The address, size (zero if unknown) and name of the symbols in this section.
+    symbols: Vec<(u64, u64, String)>,
+}
+
+impl InputSection {
+    /// Returns the symbols defined by this section. When the map file doesn't give the size of a
+    /// symbol, it extends to the next symbol or to the end of the section. Sections without any
+    /// listed symbol, like the ones of local symbols in GNU ld maps, are named after the symbol in
+    /// their section name, as rustc puts every function and static into its own section.
+    fn into_symbols(mut self) -> Vec<LinkedSymbol> {
+        // Sections that aren't loaded, like debuginfo, have no address.
+        if self.addr == 0 || self.size == 0 {
+            return Vec::new();
+        }
+
+        if self.symbols.is_empty() {
+            let Some(name) = symbol_from_section_name(&self.name) else {
+                return Vec::new();
+            };
+            return vec![LinkedSymbol {
+                name: name.to_owned(),
+                size: self.size,
+                input: self.input,
+            }];
+        }
+
+        self.symbols.sort_by_key(|&(addr, ..)| addr);
+        let end = self.addr + self.size;
+        let mut linked_symbols = Vec::with_capacity(self.symbols.len());
+        for (i, (addr, size, name)) in self.symbols.iter().enumerate() {
+            let size = if *size != 0 {
+                *size
+            } else {
+                let next = self.symbols.get(i + 1).map_or(end, |&(next, ..)| next);
+                next.saturating_sub(*addr)
+            };
+            linked_symbols.push(LinkedSymbol {
+                name: name.clone(),
+                size,
+                input: self.input.clone(),
+            });
+        }
+        linked_symbols
+    }
+}
+
+/// Returns the symbol that a `-ffunction-sections`/`-fdata-sections` style section is named
+/// after, e.g. `foo` for `.text.foo`.
+fn symbol_from_section_name(section: &str) -> Option<&str> {
+    const PREFIXES: &[&str] = &[
+        ".text.unlikely.",
+        ".text.startup.",
+        ".text.hot.",
+        ".text.",
+        ".rodata.",
+        ".data.rel.ro.local.",
+        ".data.rel.ro.",
+        ".data.",
+        ".bss.",
+        ".tdata.",
+        ".tbss.",
+    ];
+    PREFIXES
+        .iter()
+        .find_map(|prefix| section.strip_prefix(prefix))
+        .filter(|symbol| !symbol.is_empty())
+}
+
+/// Parses a map file written by GNU ld, LLD or mold into the symbols of the linked output.
+pub(crate) fn parse_map(map: &str) -> Vec<LinkedSymbol> {
+    let sections = if map.lines().next().is_some_and(|header| header.contains("VMA")) {
+        parse_lld_or_mold_map(map)
+    } else {
+        parse_gnu_ld_map(map)
+    };
+    sections.into_iter().flat_map(InputSection::into_symbols).collect()
+}
+
+/// LLD and mold write a table with the header `VMA LMA Size Align Out In Symbol`, where mold
+/// omits the `LMA` column and writes numbers in decimal instead of hexadecimal. Output sections,
+/// input sections and symbols are told apart by their indentation in the last column.
+fn parse_lld_or_mold_map(map: &str) -> Vec<InputSection> {
+    let mut lines = map.lines();
+    let header = lines.next().unwrap_or_default();
+    let (columns, radix) = if header.contains("LMA") { (4, 16) } else { (3, 10) };
+    // `Size` is always the column before `Align`, the last numeric one.
+    let size_column = columns - 2;
+
+    let mut sections: Vec<InputSection> = Vec::new();
+    for line in lines {
+        let mut numbers = Vec::with_capacity(columns);
+        let mut rest = line;
+        for _ in 0..columns {
+            rest = rest.trim_start();
+            let end = rest.find(' ').unwrap_or(rest.len());
+            let Ok(number) = u64::from_str_radix(&rest[..end], radix) else {
+                break;
+            };
+            numbers.push(number);
+            rest = &rest[end..];
+        }
+        let Some(rest) = rest.strip_prefix(' ').filter(|_| numbers.len() == columns) else {
+            continue;
+        };
+
+        let indent = rest.len() - rest.trim_start().len();
+        let rest = rest.trim();
+        if indent == 0 {
+            // An output section.
+            continue;
+        }
+        let input_section =
+            rest.rsplit_once(":(").and_then(|(input, name)| Some((input, name.strip_suffix(')')?)));
+        match input_section {
+            Some((input, name)) if indent <= 8 => sections.push(InputSection {
+                name: name.to_owned(),
+                addr: numbers[0],
+                size: numbers[size_column],
+                input: input.to_owned(),
+                symbols: Vec::new(),
+            }),
+            _ => {
+                if let Some(section) = sections.last_mut() {
+                    section.symbols.push((numbers[0], numbers[size_column], rest.to_owned()));
+                }
+            }
+        }
+    }
+    sections
+}
+
+/// GNU ld writes the memory map after a `Linker script and memory map` line. Input sections are
+/// indented by one space and followed by their address, size and input file, on the next line if
+/// the section name is long. The global symbols they define follow, on lines with only an
+/// address and a name.
+fn parse_gnu_ld_map(map: &str) -> Vec<InputSection> {
+    fn hex(s: &str) -> Option<u64> {
+        u64::from_str_radix(s.strip_prefix("0x")?, 16).ok()
+    }
+
+    let mut sections: Vec<InputSection> = Vec::new();
+    let mut long_section_name: Option<&str> = None;
+    let lines = map.lines().skip_while(|line| !line.starts_with("Linker script and memory map"));
+    for line in lines {
+        // Output sections and linker script statements.
+        if !line.starts_with(' ') {
+            long_section_name = None;
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let section_name = if line.starts_with("  ") {
+            long_section_name.take()
+        } else {
+            let Some(name) = fields.next().filter(|name| !name.starts_with('*')) else {
+                continue;
+            };
+            Some(name)
+        };
+
+        let Some(addr) = fields.next().and_then(hex) else {
+            // A long section name, its address and size follow on the next line.
+            long_section_name = section_name.filter(|_| !line.starts_with("  "));
+            continue;
+        };
+
+        match section_name {
+            Some(name) => {
+                let Some(size) = fields.next().and_then(hex) else { continue };
+                let input = fields.collect::<Vec<_>>().join(" ");
+                if input.is_empty() {
+                    continue;
+                }
+                sections.push(InputSection {
+                    name: name.to_owned(),
+                    addr,
+                    size,
+                    input,
+                    symbols: Vec::new(),
+                });
+            }
+            None => {
+                // Symbol names can contain spaces when GNU ld demangled them, and assignments
+                // from the linker script contain `=`.
+                let name = fields.collect::<Vec<_>>().join(" ");
+                if name.is_empty() || name.contains('=') {
+                    continue;
+                }
+                if let Some(section) = sections.last_mut() {
+                    section.symbols.push((addr, 0, name));
+                }
+            }
+        }
+    }
+    sections
+}
+
+/// Demangles Rust symbols without their hash. Names that the linker already demangled only have
+/// their hash removed.
+pub(super) fn demangle(name: &str) -> String {
+    match rustc_demangle::try_demangle(name) {
+        Ok(demangled) => format!("{demangled:#}"),
+        Err(_) => match name.rsplit_once("::h") {
+            Some((path, hash))
+                if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) =>
+            {
+                path.to_owned()
+            }
+            _ => name.to_owned(),
+        },
+    }
+}
+
+/// Returns the crate a demangled symbol most likely comes from: the first path segment in its
+/// name. For `<alloc::vec::Vec<T> as core::ops::drop::Drop>::drop` that's `alloc`, the crate
+/// with the impl.
+fn crate_name(demangled: &str) -> Option<&str> {
+    let end = demangled.find("::")?;
+    let start =
+        demangled[..end].rfind(|c: char| !(c.is_alphanumeric() || c == '_')).map_or(0, |i| i + 1);
+    Some(&demangled[start..end]).filter(|name| !name.is_empty())
+}
+
+/// Removes the generic arguments from a demangled symbol, so that all the instances of a generic
+/// function map to the same definition, e.g. `core::ptr::drop_in_place` for
+/// `core::ptr::drop_in_place::<alloc::string::String>`.
+fn generic_definition(demangled: &str) -> String {
+    let mut definition = String::with_capacity(demangled.len());
+    let mut depth = 0;
+    let mut prev = None;
+    for c in demangled.chars() {
+        if depth > 0 {
+            match c {
+                '<' => depth += 1,
+                // Not the `>` of a `->` in a function pointer or closure signature.
+                '>' if prev != Some('-') => depth -= 1,
+                _ => {}
+            }
+            prev = Some(c);
+            continue;
+        }
+        // Generic arguments follow a path segment or a turbofish, unlike the `<` of a qualified
+        // path like `<T as Trait>::f`.
+        if c == '<' && prev.is_some_and(|p: char| p.is_alphanumeric() || p == '_' || p == ':') {
+            if definition.ends_with("::") {
+                definition.truncate(definition.len() - 2);
+            }
+            depth = 1;
+            prev = Some(c);
+            continue;
+        }
+        definition.push(c);
+        prev = Some(c);
+    }
+    definition
+}
+
+/// Renders the `--emit=link-map` report for the symbols of the linked artifact `output`.
+pub(crate) fn render_report(output: &Path, symbols: &[LinkedSymbol]) -> String {
+    let mut by_crate: BTreeMap<&str, (u64, usize)> = BTreeMap::new();
+    let mut by_definition: BTreeMap<String, (u64, usize)> = BTreeMap::new();
+    let mut rows = Vec::with_capacity(symbols.len());
+    for symbol in symbols {
+        let demangled = demangle(&symbol.name);
+        let definition = generic_definition(&demangled);
+        let krate = crate_name(&definition).unwrap_or("<non-Rust>").to_owned();
+        let entry = by_definition.entry(definition).or_default();
+        entry.0 += symbol.size;
+        entry.1 += 1;
+        rows.push((symbol.size, demangled, krate, symbol.input.as_str()));
+    }
+    for (size, _, krate, _) in &rows {
+        let entry = by_crate.entry(krate.as_str()).or_default();
+        entry.0 += size;
+        entry.1 += 1;
+    }
+
+    let total: u64 = symbols.iter().map(|symbol| symbol.size).sum();
+    let mut report = String::new();
+    writeln!(
+        report,
+        "Link map of `{}`: {} symbols, {} bytes",
+        output.display(),
+        symbols.len(),
+        total
+    )
+    .unwrap();
+
+    let mut by_crate: Vec<_> = by_crate.into_iter().collect();
+    by_crate.sort_by(|(a_name, (a_size, _)), (b_name, (b_size, _))| {
+        b_size.cmp(a_size).then(a_name.cmp(b_name))
+    });
+    writeln!(report, "\nSize by crate:\n{:>10}  {:>9}  crate", "bytes", "symbols").unwrap();
+    for (krate, (size, count)) in by_crate {
+        writeln!(report, "{size:>10}  {count:>9}  {krate}").unwrap();
+    }
+
+    let mut by_definition: Vec<_> = by_definition.into_iter().collect();
+    by_definition.sort_by(|(a_name, (a_size, _)), (b_name, (b_size, _))| {
+        b_size.cmp(a_size).then(a_name.cmp(b_name))
+    });
+    writeln!(
+        report,
+        "\nSize by generic definition:\n{:>10}  {:>9}  definition",
+        "bytes", "instances"
+    )
+    .unwrap();
+    for (definition, (size, count)) in by_definition {
+        writeln!(report, "{size:>10}  {count:>9}  {definition}").unwrap();
+    }
+
+    rows.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    writeln!(report, "\nSymbols:\n{:>10}  symbol (crate, linked from)", "bytes").unwrap();
+    for (size, demangled, krate, input) in rows {
+        writeln!(report, "{size:>10}  {demangled} ({krate}, {input})").unwrap();
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,117 @@
+use super::{crate_name, demangle, generic_definition, parse_map, render_report, LinkedSymbol};
+use std::path::Path;
+
+fn symbol(name: &str, size: u64, input: &str) -> LinkedSymbol {
+    LinkedSymbol { name: name.to_owned(), size, input: input.to_owned() }
+}
+
+#[test]
+fn test_parse_gnu_ld_map() {
+    let map = "\
+Discarded input sections
+
+ .text._ZN4main6unused17h0123456789abcdefE
+                0x0000000000000000       0x10 main.main.cgu.0.rcgu.o
+
+Linker script and memory map
+
+LOAD main.main.cgu.0.rcgu.o
+                [!provide]                        PROVIDE (__executable_start = SEGMENT_START (\"text-segment\", 0x0))
+
+.text           0x0000000000001040       0x80
+ *(.text.unlikely .text.*_unlikely .text.unlikely.*)
+ .text          0x0000000000001040       0x26 /usr/lib/Scrt1.o
+                0x0000000000001040                _start
+ .text._ZN4main4main17h0123456789abcdefE
+                0x0000000000001070       0x20 main.main.cgu.0.rcgu.o
+ .text.main     0x0000000000001090       0x30 main.main.cgu.0.rcgu.o
+                0x0000000000001090                main
+ *fill*         0x00000000000010c0        0x0
+                0x00000000000010c0                . = ALIGN (0x8)
+
+.debug_info     0x0000000000000000      0x100
+ .debug_info    0x0000000000000000      0x100 main.main.cgu.0.rcgu.o
+";
+    assert_eq!(
+        parse_map(map),
+        [
+            symbol("_start", 0x26, "/usr/lib/Scrt1.o"),
+            symbol("_ZN4main4main17h0123456789abcdefE", 0x20, "main.main.cgu.0.rcgu.o"),
+            symbol("main", 0x30, "main.main.cgu.0.rcgu.o"),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_lld_map() {
+    let map = "\
+             VMA              LMA     Size Align Out     In      Symbol
+          201120           201120       80    16 .text
+          201120           201120       30    16         libfoo.rlib(foo.o):(.text.foo)
+          201120           201120       10     1                 foo
+          201130           201130        0     1                 foo_alias
+          201150           201150       50    16         main.o:(.text)
+          201150           201150        0     1                 main
+";
+    assert_eq!(
+        parse_map(map),
+        [
+            symbol("foo", 0x10, "libfoo.rlib(foo.o)"),
+            symbol("foo_alias", 0x20, "libfoo.rlib(foo.o)"),
+            symbol("main", 0x50, "main.o"),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_mold_map() {
+    let map = "\
+               VMA       Size Align Out     In      Symbol
+           2101248        128    16 .text
+           2101248         48    16         foo.o:(.text.foo)
+           2101248          0     0                 foo
+";
+    assert_eq!(parse_map(map), [symbol("foo", 48, "foo.o")]);
+}
+
+#[test]
+fn test_demangle() {
+    assert_eq!(demangle("_ZN4main4main17h0123456789abcdefE"), "main::main");
+    assert_eq!(demangle("std::rt::lang_start::h0123456789abcdef"), "std::rt::lang_start");
+    assert_eq!(demangle("memcpy"), "memcpy");
+}
+
+#[test]
+fn test_crate_name() {
+    assert_eq!(crate_name("core::ptr::drop_in_place"), Some("core"));
+    assert_eq!(crate_name("<alloc::vec::Vec as core::ops::drop::Drop>::drop"), Some("alloc"));
+    assert_eq!(crate_name("<&mut W as core::fmt::Write>::write_str"), Some("core"));
+    assert_eq!(crate_name("memcpy"), None);
+}
+
+#[test]
+fn test_generic_definition() {
+    assert_eq!(
+        generic_definition("core::ptr::drop_in_place::<alloc::string::String>"),
+        "core::ptr::drop_in_place"
+    );
+    assert_eq!(
+        generic_definition("<alloc::vec::Vec<u8> as core::ops::drop::Drop>::drop"),
+        "<alloc::vec::Vec as core::ops::drop::Drop>::drop"
+    );
+    assert_eq!(generic_definition("foo::call::<fn() -> u8, alloc::vec::Vec<u8>>"), "foo::call");
+}
+
+#[test]
+fn test_render_report() {
+    let symbols = [
+        symbol("_ZN4core3ptr13drop_in_place17h0123456789abcdefE", 10, "a.o"),
+        symbol("_ZN4main4main17h0123456789abcdefE", 20, "main.o"),
+        symbol("memcpy", 5, "libc.a(memcpy.o)"),
+    ];
+    let report = render_report(Path::new("main"), &symbols);
+    assert!(report.starts_with("Link map of `main`: 3 symbols, 35 bytes\n"));
+    assert!(report.contains("        20          1  main\n        10          1  core\n"));
+    assert!(report.contains("         5          1  <non-Rust>\n"));
+    assert!(report.contains("        20  main::main (main, main.o)\n"));
+}
@@ -45,6 +45,7 @@ pub fn get_linker<'a>(
     flavor: LinkerFlavor,
     self_contained: bool,
     target_cpu: &'a str,
+    linker_plugin_lto_args: Option<&'a [String]>,
 ) -> Box<dyn Linker + 'a> {
     let msvc_tool = windows_registry::find_tool(sess.opts.target_triple.triple(), "link.exe");
 
@@ -142,10 +143,12 @@ pub fn get_linker<'a>(
         LinkerFlavor::Gnu(cc, _)
         | LinkerFlavor::Darwin(cc, _)
         | LinkerFlavor::WasmLld(cc)
-        | LinkerFlavor::Unix(cc) => Box::new(GccLinker {
+        | LinkerFlavor::Unix(cc)
+        | LinkerFlavor::Mold(cc) => Box::new(GccLinker {
             cmd,
             sess,
             target_cpu,
+            linker_plugin_lto_args,
             hinted_static: None,
             is_ld: cc == Cc::No,
             is_gnu: flavor.is_gnu(),
@@ -231,6 +234,8 @@ pub struct GccLinker<'a> {
     cmd: Command,
     sess: &'a Session,
     target_cpu: &'a str,
+    /// The arguments that enable linker plugin LTO, when they aren't the ones of LLVM's plugin.
+    linker_plugin_lto_args: Option<&'a [String]>,
     hinted_static: Option<bool>, // Keeps track of the current hinting mode.
     // Link as ld
     is_ld: bool,
@@ -289,6 +294,14 @@ fn push_linker_plugin_lto_args(&mut self, plugin_path: Option<&OsStr>) {
             self.linker_arg(&arg);
         }
 
+        // The codegen backend provides its own arguments when its objects aren't LLVM bitcode.
+        if let Some(args) = self.linker_plugin_lto_args {
+            if !self.is_ld {
+                self.cmd.args(args);
+            }
+            return;
+        }
+
         let opt_level = match self.sess.opts.optimize {
             config::OptLevel::No => "O0",
             config::OptLevel::Less => "O1",
@@ -0,0 +1,372 @@
+//! The `--emit=stack-sizes` report. It combines the stack frame size of each function, read from
+//! the `.stack_sizes` sections that LLVM writes in ELF object files, with the call graph of the
+//! mono item collector, to find the worst-case stack usage of the call chains starting at each
+//! function. Recursion and indirect calls make that stack usage unbounded.
+
+use std::fmt::Write;
+use std::fs;
+
+use object::{Object, ObjectSection, ObjectSymbol, RelocationTarget, SymbolKind};
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_data_structures::stack::ensure_sufficient_stack;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::{OutputFilenames, OutputType};
+use rustc_session::Session;
+
+use super::link_map::demangle;
+use super::write::CompiledModules;
+use crate::errors;
+
+/// A function codegened in the local crate.
+#[derive(Debug)]
+pub(crate) struct CallGraphFunction {
+    /// The human-readable name of the function.
+    pub name: String,
+    /// The symbol names of the functions it may call.
+    pub callees: Vec<String>,
+    /// Whether it calls functions through pointers, like `dyn Trait` methods.
+    pub has_indirect_calls: bool,
+}
+
+/// The call graph of the functions codegened in the local crate, by symbol name.
+#[derive(Debug)]
+pub(crate) struct CallGraph {
+    pub functions: FxIndexMap<String, CallGraphFunction>,
+}
+
+impl CallGraph {
+    pub(crate) fn new(tcx: TyCtxt<'_>) -> CallGraph {
+        let symbol_name = |instance| tcx.symbol_name(instance).name.to_owned();
+        let mut functions: Vec<_> = rustc_monomorphize::call_graph(tcx)
+            .into_iter()
+            .map(|node| {
+                let mut callees: Vec<_> =
+                    node.callees.iter().map(|&callee| symbol_name(callee)).collect();
+                callees.sort_unstable();
+                let function = CallGraphFunction {
+                    name: with_no_trimmed_paths!(node.instance.to_string()),
+                    callees,
+                    has_indirect_calls: node.has_indirect_calls,
+                };
+                (symbol_name(node.instance), function)
+            })
+            .collect();
+        functions.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        CallGraph { functions: functions.into_iter().collect() }
+    }
+}
+
+/// Reads the stack frame sizes recorded in the `.stack_sizes` sections of an ELF object file, by
+/// symbol name. Each entry of these sections is the address of a function, relocated against its
+/// symbol, followed by the ULEB128-encoded size of its stack frame.
+pub(crate) fn read_stack_sizes(data: &[u8]) -> Result<FxHashMap<String, u64>, String> {
+    let file = object::File::parse(data).map_err(|error| error.to_string())?;
+    let address_size = if file.is_64() { 8 } else { 4 };
+    let mut stack_sizes = FxHashMap::default();
+    for section in file.sections().filter(|section| section.name() == Ok(".stack_sizes")) {
+        let data = section.data().map_err(|error| error.to_string())?;
+        let relocations: FxHashMap<_, _> = section.relocations().collect();
+        let mut offset = 0;
+        while offset < data.len() {
+            let relocation = relocations
+                .get(&(offset as u64))
+                .ok_or_else(|| format!("no relocation for the function at offset {offset}"))?;
+            let address = data
+                .get(offset..offset + address_size)
+                .ok_or_else(|| format!("truncated function address at offset {offset}"))?;
+            let addend = if relocation.has_implicit_addend() {
+                read_address(address, file.is_little_endian())
+            } else {
+                relocation.addend() as u64
+            };
+            let name = function_name(&file, relocation.target(), addend)
+                .ok_or_else(|| format!("unknown function at offset {offset}"))?;
+            offset += address_size;
+            let (size, len) = read_uleb128(&data[offset..])
+                .ok_or_else(|| format!("truncated stack size at offset {offset}"))?;
+            offset += len;
+            stack_sizes.insert(name, size);
+        }
+    }
+    Ok(stack_sizes)
+}
+
+fn read_address(bytes: &[u8], little_endian: bool) -> u64 {
+    let mut address = 0;
+    for i in 0..bytes.len() {
+        let byte = if little_endian { bytes[bytes.len() - 1 - i] } else { bytes[i] };
+        address = address << 8 | u64::from(byte);
+    }
+    address
+}
+
+/// Returns the value of an unsigned LEB128 number, and its length in bytes.
+fn read_uleb128(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0;
+    for (i, &byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Returns the name of the function a relocation points to. Relocations against local functions
+/// can point to their section symbol instead, with an addend to the function in the section.
+fn function_name(file: &object::File<'_>, target: RelocationTarget, addend: u64) -> Option<String> {
+    let RelocationTarget::Symbol(index) = target else {
+        return None;
+    };
+    let symbol = file.symbol_by_index(index).ok()?;
+    if symbol.kind() != SymbolKind::Section {
+        return symbol.name().ok().map(str::to_owned);
+    }
+    // Ignore the lowest bit of the address of functions, which is set for Thumb functions.
+    file.symbols()
+        .find(|function| {
+            function.kind() == SymbolKind::Text
+                && function.section_index() == symbol.section_index()
+                && function.address() & !1 == addend & !1
+        })
+        .and_then(|function| function.name().ok().map(str::to_owned))
+}
+
+/// A function of the report's call graph.
+struct Function<'a> {
+    name: String,
+    frame: Option<u64>,
+    /// Whether the function is in the call graph: otherwise, its callees are unknown.
+    in_call_graph: bool,
+    callees: Vec<usize>,
+    has_indirect_calls: bool,
+    symbol: &'a str,
+}
+
+/// Why the stack usage of a function cannot be bounded.
+#[derive(Clone, Copy)]
+enum Unbounded {
+    /// There is a recursive call to this function in a call chain.
+    Recursion(usize),
+    /// This function makes an indirect call in a call chain.
+    IndirectCall(usize),
+}
+
+/// The worst-case stack usage of the call chains starting at a function.
+#[derive(Clone, Copy)]
+struct Usage {
+    /// The stack usage of the deepest call chain, in bytes.
+    bytes: u64,
+    /// The function called by the deepest call chain.
+    deepest_callee: Option<usize>,
+    unbounded: Option<Unbounded>,
+    /// A function whose stack usage is unknown in a call chain, making `bytes` a lower bound.
+    unknown: Option<usize>,
+}
+
+struct Analysis<'a> {
+    functions: Vec<Function<'a>>,
+    usages: Vec<Option<Usage>>,
+    on_stack: Vec<bool>,
+}
+
+impl<'a> Analysis<'a> {
+    fn new(call_graph: &'a CallGraph, stack_sizes: &'a FxHashMap<String, u64>) -> Analysis<'a> {
+        let mut indices: FxHashMap<&str, usize> = FxHashMap::default();
+        let mut functions = Vec::new();
+        let mut index_of = |functions: &mut Vec<Function<'a>>, symbol: &'a str| {
+            *indices.entry(symbol).or_insert_with(|| {
+                functions.push(Function {
+                    name: demangle(symbol),
+                    frame: stack_sizes.get(symbol).copied(),
+                    in_call_graph: false,
+                    callees: Vec::new(),
+                    has_indirect_calls: false,
+                    symbol,
+                });
+                functions.len() - 1
+            })
+        };
+        for (symbol, function) in &call_graph.functions {
+            let i = index_of(&mut functions, symbol.as_str());
+            let callees = function
+                .callees
+                .iter()
+                .map(|callee| index_of(&mut functions, callee.as_str()))
+                .collect();
+            functions[i] = Function {
+                name: function.name.clone(),
+                frame: stack_sizes.get(symbol.as_str()).copied(),
+                in_call_graph: true,
+                callees,
+                has_indirect_calls: function.has_indirect_calls,
+                symbol,
+            };
+        }
+        // Functions that rustc generates outside of the call graph, like the allocator shims.
+        let mut other_symbols: Vec<_> = stack_sizes.keys().collect();
+        other_symbols.sort_unstable();
+        for symbol in other_symbols {
+            index_of(&mut functions, symbol.as_str());
+        }
+
+        let len = functions.len();
+        Analysis { functions, usages: vec![None; len], on_stack: vec![false; len] }
+    }
+
+    fn usage(&mut self, i: usize) -> Usage {
+        if let Some(usage) = self.usages[i] {
+            return usage;
+        }
+
+        // Functions without a frame size have been inlined into their callers, whose frame size
+        // includes theirs.
+        let frame = self.functions[i].frame.unwrap_or(0);
+        let mut usage = Usage {
+            bytes: frame,
+            deepest_callee: None,
+            unbounded: self.functions[i].has_indirect_calls.then_some(Unbounded::IndirectCall(i)),
+            unknown: (!self.functions[i].in_call_graph).then_some(i),
+        };
+        self.on_stack[i] = true;
+        for j in 0..self.functions[i].callees.len() {
+            let callee = self.functions[i].callees[j];
+            if self.on_stack[callee] {
+                usage.unbounded.get_or_insert(Unbounded::Recursion(callee));
+                continue;
+            }
+            let callee_usage = ensure_sufficient_stack(|| self.usage(callee));
+            if let Some(unbounded) = callee_usage.unbounded {
+                usage.unbounded.get_or_insert(unbounded);
+            }
+            if usage.unknown.is_none() {
+                usage.unknown = callee_usage.unknown;
+            }
+            if usage.deepest_callee.is_none() || frame + callee_usage.bytes > usage.bytes {
+                usage.bytes = frame + callee_usage.bytes;
+                usage.deepest_callee = Some(callee);
+            }
+        }
+        self.on_stack[i] = false;
+
+        self.usages[i] = Some(usage);
+        usage
+    }
+}
+
+/// Renders the `--emit=stack-sizes` report of the functions of `crate_name` whose frame sizes
+/// are known.
+pub(crate) fn render_report(
+    crate_name: &str,
+    call_graph: &CallGraph,
+    stack_sizes: &FxHashMap<String, u64>,
+) -> String {
+    let mut analysis = Analysis::new(call_graph, stack_sizes);
+    let listed: Vec<_> =
+        (0..analysis.functions.len()).filter(|&i| analysis.functions[i].frame.is_some()).collect();
+    let mut rows: Vec<_> = listed.into_iter().map(|i| (i, analysis.usage(i))).collect();
+    // Show the unbounded functions first, then the ones using the most stack.
+    rows.sort_by(|(a, a_usage), (b, b_usage)| {
+        b_usage
+            .unbounded
+            .is_some()
+            .cmp(&a_usage.unbounded.is_some())
+            .then(b_usage.bytes.cmp(&a_usage.bytes))
+            .then_with(|| analysis.functions[*a].name.cmp(&analysis.functions[*b].name))
+            .then_with(|| analysis.functions[*a].symbol.cmp(analysis.functions[*b].symbol))
+    });
+
+    let functions = &analysis.functions;
+    let mut report = String::new();
+    writeln!(report, "Stack usage of `{crate_name}`: {} functions", rows.len()).unwrap();
+    writeln!(
+        report,
+        "\nThe frame is the stack used by a function itself, the worst case the stack used by \
+        the\ndeepest call chain starting at it. Both are in bytes.\n"
+    )
+    .unwrap();
+    writeln!(report, "{:>10}  {:>10}  function", "frame", "worst case").unwrap();
+    for (i, usage) in rows {
+        let worst_case = match (usage.unbounded, usage.unknown) {
+            (Some(_), _) => "unbounded".to_owned(),
+            (None, Some(_)) => format!(">= {}", usage.bytes),
+            (None, None) => usage.bytes.to_string(),
+        };
+        let frame = functions[i].frame.unwrap_or(0);
+        writeln!(report, "{frame:>10}  {worst_case:>10}  {}", functions[i].name).unwrap();
+
+        let note = "                        ";
+        match usage.unbounded {
+            Some(Unbounded::Recursion(j)) => {
+                writeln!(report, "{note}unbounded: recursion through `{}`", functions[j].name)
+                    .unwrap();
+                continue;
+            }
+            Some(Unbounded::IndirectCall(j)) => {
+                writeln!(report, "{note}unbounded: indirect call in `{}`", functions[j].name)
+                    .unwrap();
+                continue;
+            }
+            None => {}
+        }
+        if usage.deepest_callee.is_some() {
+            let mut chain = vec![functions[i].name.as_str()];
+            let mut next = usage.deepest_callee;
+            while let Some(j) = next {
+                chain.push(&functions[j].name);
+                next = analysis.usages[j].and_then(|usage| usage.deepest_callee);
+            }
+            writeln!(report, "{note}deepest call chain: {}", chain.join(" -> ")).unwrap();
+        }
+        if let Some(j) = usage.unknown {
+            let name = &functions[j].name;
+            if functions[j].frame.is_some() {
+                writeln!(report, "{note}lower bound: the functions called by `{name}` are unknown")
+                    .unwrap();
+            } else {
+                writeln!(report, "{note}lower bound: the stack usage of `{name}` is unknown")
+                    .unwrap();
+            }
+        }
+    }
+    report
+}
+
+/// Writes the `--emit=stack-sizes` report for the object files of `compiled_modules`.
+pub(crate) fn emit_report(
+    sess: &Session,
+    compiled_modules: &CompiledModules,
+    call_graph: &CallGraph,
+    crate_name: &str,
+    outputs: &OutputFilenames,
+) {
+    let mut stack_sizes = FxHashMap::default();
+    for module in compiled_modules.modules.iter().chain(&compiled_modules.allocator_module) {
+        let Some(path) = &module.object else {
+            continue;
+        };
+        let module_stack_sizes = fs::read(path)
+            .map_err(|error| error.to_string())
+            .and_then(|data| read_stack_sizes(&data));
+        match module_stack_sizes {
+            Ok(module_stack_sizes) => stack_sizes.extend(module_stack_sizes),
+            Err(error) => {
+                sess.dcx().emit_err(errors::StackSizesReadFailure { path: path.clone(), error });
+            }
+        }
+    }
+    if stack_sizes.is_empty() {
+        sess.dcx().emit_warn(errors::StackSizesNotFound);
+    }
+
+    let report = render_report(crate_name, call_graph, &stack_sizes);
+    let out = outputs.path(OutputType::StackSizes);
+    out.overwrite(&report, sess);
+    if sess.opts.json_artifact_notifications && !out.is_stdout() {
+        sess.dcx().emit_artifact_notification(out.as_path(), "stack-sizes");
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,139 @@
+use super::{read_stack_sizes, read_uleb128, render_report, CallGraph, CallGraphFunction};
+use object::write::{self, Relocation, Symbol, SymbolSection};
+use object::{
+    Architecture, BinaryFormat, Endianness, RelocationEncoding, RelocationKind, SectionKind,
+    SymbolFlags, SymbolKind, SymbolScope,
+};
+use rustc_data_structures::fx::FxHashMap;
+
+#[test]
+fn test_read_uleb128() {
+    assert_eq!(read_uleb128(&[0x30]), Some((48, 1)));
+    assert_eq!(read_uleb128(&[0x80, 0x01, 0xff]), Some((128, 2)));
+    assert_eq!(read_uleb128(&[0xe5, 0x8e, 0x26]), Some((624485, 3)));
+    assert_eq!(read_uleb128(&[0x80]), None);
+}
+
+#[test]
+fn test_read_stack_sizes() {
+    let mut obj = write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = obj.add_section(vec![], b".text.global".to_vec(), SectionKind::Text);
+    obj.append_section_data(text, &[0xc3; 16], 16);
+    let global = obj.add_symbol(Symbol {
+        name: b"global".to_vec(),
+        value: 0,
+        size: 16,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: SymbolSection::Section(text),
+        flags: SymbolFlags::None,
+    });
+    // Two local functions in the same section, referenced through its section symbol.
+    let local_text = obj.add_section(vec![], b".text.local".to_vec(), SectionKind::Text);
+    obj.append_section_data(local_text, &[0xc3; 32], 16);
+    for (name, value) in [(&b"local1"[..], 0), (&b"local2"[..], 16)] {
+        obj.add_symbol(Symbol {
+            name: name.to_vec(),
+            value,
+            size: 16,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Compilation,
+            weak: false,
+            section: SymbolSection::Section(local_text),
+            flags: SymbolFlags::None,
+        });
+    }
+    let local_section = obj.section_symbol(local_text);
+
+    let stack_sizes = obj.add_section(vec![], b".stack_sizes".to_vec(), SectionKind::Other);
+    let entries =
+        [(global, 0, &[0x30][..]), (local_section, 0, &[0x08]), (local_section, 16, &[0x80, 0x01])];
+    for (symbol, addend, size) in entries {
+        let offset = obj.append_section_data(stack_sizes, &[0; 8], 1);
+        obj.append_section_data(stack_sizes, size, 1);
+        obj.add_relocation(
+            stack_sizes,
+            Relocation {
+                offset,
+                size: 64,
+                kind: RelocationKind::Absolute,
+                encoding: RelocationEncoding::Generic,
+                symbol,
+                addend,
+            },
+        )
+        .unwrap();
+    }
+
+    let mut expected = FxHashMap::default();
+    expected.insert("global".to_owned(), 48);
+    expected.insert("local1".to_owned(), 8);
+    expected.insert("local2".to_owned(), 128);
+    assert_eq!(read_stack_sizes(&obj.write().unwrap()), Ok(expected));
+}
+
+fn function(name: &str, callees: &[&str], has_indirect_calls: bool) -> CallGraphFunction {
+    CallGraphFunction {
+        name: name.to_owned(),
+        callees: callees.iter().map(|&callee| callee.to_owned()).collect(),
+        has_indirect_calls,
+    }
+}
+
+#[test]
+fn test_render_report() {
+    const PRINT: &str = "_ZN3std2io5stdio6_print17h0123456789abcdefE";
+    let call_graph = CallGraph {
+        functions: [
+            ("main", function("t::main", &["helper", PRINT], false)),
+            ("helper", function("t::helper", &["inlined"], false)),
+            ("inlined", function("t::inlined", &["leaf"], false)),
+            ("leaf", function("t::leaf", &[], false)),
+            ("rec", function("t::rec", &["rec"], false)),
+            ("caller_of_rec", function("t::caller_of_rec", &["rec"], false)),
+            ("dynamic", function("t::dynamic", &[], true)),
+        ]
+        .into_iter()
+        .map(|(symbol, function)| (symbol.to_owned(), function))
+        .collect(),
+    };
+    let stack_sizes = [
+        ("main", 48),
+        ("helper", 16),
+        ("leaf", 32),
+        ("rec", 24),
+        ("caller_of_rec", 16),
+        ("dynamic", 8),
+        ("__rust_alloc", 8),
+    ]
+    .into_iter()
+    .map(|(symbol, size)| (symbol.to_owned(), size))
+    .collect();
+
+    assert_eq!(
+        render_report("t", &call_graph, &stack_sizes),
+        "\
+Stack usage of `t`: 7 functions
+
+The frame is the stack used by a function itself, the worst case the stack used by the
+deepest call chain starting at it. Both are in bytes.
+
+     frame  worst case  function
+        16   unbounded  t::caller_of_rec
+                        unbounded: recursion through `t::rec`
+        24   unbounded  t::rec
+                        unbounded: recursion through `t::rec`
+         8   unbounded  t::dynamic
+                        unbounded: indirect call in `t::dynamic`
+        48       >= 96  t::main
+                        deepest call chain: t::main -> t::helper -> t::inlined -> t::leaf
+                        lower bound: the stack usage of `std::io::stdio::_print` is unknown
+        16          48  t::helper
+                        deepest call chain: t::helper -> t::inlined -> t::leaf
+        32          32  t::leaf
+         8        >= 8  __rust_alloc
+                        lower bound: the functions called by `__rust_alloc` are unknown
+"
+    );
+}
@@ -1,5 +1,6 @@
 use super::link::{self, ensure_removed};
 use super::lto::{self, SerializedModule};
+use super::stack_sizes::{self, CallGraph};
 use super::symbol_export::symbol_name_for_instance_in_crate;
 
 use crate::errors;
@@ -122,6 +123,11 @@ pub struct ModuleConfig {
     pub llvm_plugins: Vec<String>,
 }
 
+/// The path the backend merges raw profiles passed to `-C profile-use` into before optimizing.
+pub fn merged_pgo_use_profile_path(outputs: &OutputFilenames) -> PathBuf {
+    outputs.temp_path_ext("merged.profdata", None)
+}
+
 impl ModuleConfig {
     fn new(
         kind: ModuleKind,
@@ -143,6 +149,7 @@ macro_rules! if_regular {
         let save_temps = sess.opts.cg.save_temps;
 
         let should_emit_obj = sess.opts.output_types.contains_key(&OutputType::Exe)
+            || sess.opts.output_types.contains_key(&OutputType::StackSizes)
             || match kind {
                 ModuleKind::Regular => sess.opts.output_types.contains_key(&OutputType::Object),
                 ModuleKind::Allocator => false,
@@ -185,7 +192,14 @@ macro_rules! if_regular {
                 sess.opts.cg.profile_generate.clone(),
                 SwitchWithOptPath::Disabled
             ),
-            pgo_use: if_regular!(sess.opts.cg.profile_use.clone(), None),
+            pgo_use: if_regular!(
+                if sess.raw_pgo_use_profiles().is_some() {
+                    Some(merged_pgo_use_profile_path(tcx.output_filenames(())))
+                } else {
+                    sess.opts.cg.profile_use.clone()
+                },
+                None
+            ),
             pgo_sample_use: if_regular!(sess.opts.unstable_opts.profile_sample_use.clone(), None),
             debug_info_for_profiling: sess.opts.unstable_opts.debug_info_for_profiling,
             instrument_coverage: if_regular!(sess.instrument_coverage(), false),
@@ -348,6 +362,9 @@ pub struct CodegenContext<B: WriteBackendMethods> {
     pub regular_module_config: Arc<ModuleConfig>,
     pub metadata_module_config: Arc<ModuleConfig>,
     pub allocator_module_config: Arc<ModuleConfig>,
+    /// The levels of the modules that are not optimized at the crate's level, see
+    /// `CodegenUnit::opt_level`.
+    pub module_opt_levels: Arc<FxHashMap<String, config::OptLevel>>,
     pub tm_factory: TargetMachineFactoryFn<B>,
     pub msvc_imps_needed: bool,
     pub is_pe_coff: bool,
@@ -392,6 +409,17 @@ pub fn config(&self, kind: ModuleKind) -> &ModuleConfig {
             ModuleKind::Allocator => &self.allocator_module_config,
         }
     }
+
+    /// Returns the level to optimize the module called `module_name` at, which is the level of
+    /// `config` unless the module holds `#[optimize(level = "...")]` functions.
+    pub fn module_opt_level(
+        &self,
+        module_name: &str,
+        config: &ModuleConfig,
+    ) -> Option<config::OptLevel> {
+        let opt_level = config.opt_level?;
+        Some(self.module_opt_levels.get(module_name).copied().unwrap_or(opt_level))
+    }
 }
 
 fn generate_lto_work<B: ExtraBackendMethods>(
@@ -470,7 +498,10 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
     let no_builtins = attr::contains_name(crate_attrs, sym::no_builtins);
     let is_compiler_builtins = attr::contains_name(crate_attrs, sym::compiler_builtins);
 
-    let crate_info = CrateInfo::new(tcx, target_cpu);
+    let mut crate_info = CrateInfo::new(tcx, target_cpu);
+    crate_info.linker_plugin_lto_args = backend.linker_plugin_lto_args(sess);
+    let stack_sizes_call_graph =
+        sess.opts.output_types.contains_key(&OutputType::StackSizes).then(|| CallGraph::new(tcx));
 
     let regular_config =
         ModuleConfig::new(ModuleKind::Regular, tcx, no_builtins, is_compiler_builtins);
@@ -510,6 +541,7 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
             phantom: PhantomData,
         },
         output_filenames: tcx.output_filenames(()).clone(),
+        stack_sizes_call_graph,
     }
 }
 
@@ -630,7 +662,12 @@ fn produce_final_output_artifacts(
                 user_wants_objects = true;
                 copy_if_one_unit(OutputType::Object, true);
             }
-            OutputType::Mir | OutputType::Metadata | OutputType::Exe | OutputType::DepInfo => {}
+            OutputType::Mir
+            | OutputType::Metadata
+            | OutputType::Exe
+            | OutputType::DepInfo
+            | OutputType::LinkMap
+            | OutputType::StackSizes => {}
         }
     }
 
@@ -1119,6 +1156,13 @@ fn start_executing_work<B: ExtraBackendMethods>(
         };
     let backend_features = tcx.global_backend_features(());
 
+    let module_opt_levels = tcx
+        .collect_and_partition_mono_items(())
+        .1
+        .iter()
+        .filter_map(|cgu| Some((cgu.name().to_string(), cgu.opt_level()?)))
+        .collect();
+
     let remark_dir = if let Some(ref dir) = sess.opts.unstable_opts.remark_dir {
         let result = fs::create_dir_all(dir).and_then(|_| dir.canonicalize());
         match result {
@@ -1149,6 +1193,7 @@ fn start_executing_work<B: ExtraBackendMethods>(
         regular_module_config: regular_config,
         metadata_module_config: metadata_config,
         allocator_module_config: allocator_config,
+        module_opt_levels: Arc::new(module_opt_levels),
         tm_factory: backend.target_machine_factory(tcx.sess, ol, backend_features),
         msvc_imps_needed: msvc_imps_needed(tcx),
         is_pe_coff: tcx.sess.target.is_like_windows,
@@ -1980,6 +2025,8 @@ pub struct OngoingCodegen<B: ExtraBackendMethods> {
     pub shared_emitter_main: SharedEmitterMain,
     pub output_filenames: Arc<OutputFilenames>,
     pub coordinator: Coordinator<B>,
+    /// The call graph for `--emit=stack-sizes`, computed before codegen as it needs the `TyCtxt`.
+    stack_sizes_call_graph: Option<CallGraph>,
 }
 
 impl<B: ExtraBackendMethods> OngoingCodegen<B> {
@@ -2002,6 +2049,16 @@ pub fn join(self, sess: &Session) -> (CodegenResults, FxIndexMap<WorkProductId,
 
         let work_products =
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
+        // This reads the object files, so it must happen before the temporary ones are removed.
+        if let Some(call_graph) = &self.stack_sizes_call_graph {
+            stack_sizes::emit_report(
+                sess,
+                &compiled_modules,
+                call_graph,
+                self.crate_info.local_crate_name.as_str(),
+                &self.output_filenames,
+            );
+        }
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
 
         // FIXME: time_llvm_passes support - does this use a global context or
@@ -870,6 +870,7 @@ pub fn new(tcx: TyCtxt<'_>, target_cpu: String) -> CrateInfo {
             dependency_formats: tcx.dependency_formats(()).clone(),
             windows_subsystem,
             natvis_debugger_visualizers: Default::default(),
+            linker_plugin_lto_args: None,
         };
 
         info.native_libraries.reserve(n_crates);
@@ -1001,6 +1002,8 @@ pub fn provide(providers: &mut Providers) {
             match optimize {
                 attr::OptimizeAttr::None | attr::OptimizeAttr::Size => false,
                 attr::OptimizeAttr::Speed => true,
+                // Explicit levels get a codegen unit of their own, see `CodegenUnit::opt_level`.
+                attr::OptimizeAttr::Level(_) => false,
             }
         });
 
@@ -9,9 +9,10 @@
 use rustc_middle::mir::mono::Linkage;
 use rustc_middle::query::Providers;
 use rustc_middle::ty::{self as ty, TyCtxt};
+use rustc_session::config::OptLevel;
 use rustc_session::{lint, parse::feature_err};
 use rustc_span::symbol::Ident;
-use rustc_span::{sym, Span};
+use rustc_span::{sym, Span, Symbol};
 use rustc_target::spec::{abi, SanitizerSet};
 
 use crate::errors;
@@ -505,6 +506,26 @@ fn codegen_fn_attrs(tcx: TyCtxt<'_>, did: LocalDefId) -> CodegenFnAttrs {
                     OptimizeAttr::Size
                 } else if list_contains_name(items, sym::speed) {
                     OptimizeAttr::Speed
+                } else if items[0].has_name(sym::level) {
+                    let level = match items[0].value_str().as_ref().map(Symbol::as_str) {
+                        Some("0") => Some(OptLevel::No),
+                        Some("1") => Some(OptLevel::Less),
+                        Some("2") => Some(OptLevel::Default),
+                        Some("3") => Some(OptLevel::Aggressive),
+                        Some("s") => Some(OptLevel::Size),
+                        Some("z") => Some(OptLevel::SizeMin),
+                        _ => None,
+                    };
+                    if let Some(level) = level {
+                        OptimizeAttr::Level(level)
+                    } else {
+                        err(
+                            items[0].span(),
+                            "invalid optimization level, expected one of `0`, `1`, `2`, `3`, `s` \
+                             or `z`",
+                        );
+                        OptimizeAttr::None
+                    }
                 } else {
                     err(items[0].span(), "invalid argument");
                     OptimizeAttr::None
@@ -157,6 +157,11 @@ fn into_diag_arg(self) -> rustc_errors::DiagArgValue {
     }
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_dwarf_package_zstd_unsupported)]
+#[note]
+pub struct DwarfPackageZstdUnsupported;
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_binary_output_to_tty)]
 pub struct BinaryOutputToTty {
@@ -456,6 +461,17 @@ pub struct UnableToRun<'a> {
 #[diag(codegen_ssa_linker_file_stem)]
 pub struct LinkerFileStem;
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_linker_threads_unsupported)]
+pub struct LinkerThreadsUnsupported;
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_linker_flavor_fallback)]
+pub struct LinkerFlavorFallback {
+    pub linker: PathBuf,
+    pub fallback: &'static str,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_static_library_native_artifacts)]
 pub struct StaticLibraryNativeArtifacts;
@@ -470,6 +486,35 @@ pub struct StaticLibraryNativeArtifactsToFile<'a> {
 #[diag(codegen_ssa_link_script_unavailable)]
 pub struct LinkScriptUnavailable;
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_link_map_not_linked)]
+pub struct LinkMapNotLinked {
+    pub crate_type: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_link_map_read_failure)]
+pub struct LinkMapReadFailure {
+    pub path: PathBuf,
+    pub error: Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_link_map_unsupported_linker)]
+pub struct LinkMapUnsupportedLinker;
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_stack_sizes_not_found)]
+#[note]
+pub struct StackSizesNotFound;
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_stack_sizes_read_failure)]
+pub struct StackSizesReadFailure {
+    pub path: PathBuf,
+    pub error: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_link_script_write_failure)]
 pub struct LinkScriptWriteFailure {
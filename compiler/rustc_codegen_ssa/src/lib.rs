@@ -168,6 +168,7 @@ pub struct CrateInfo {
     pub dependency_formats: Lrc<Dependencies>,
     pub windows_subsystem: Option<String>,
     pub natvis_debugger_visualizers: BTreeSet<DebuggerVisualizerFile>,
+    pub linker_plugin_lto_args: Option<Vec<String>>,
 }
 
 #[derive(Encodable, Decodable)]
@@ -101,7 +101,10 @@ fn define<Bx: BuilderMethods<'a, 'tcx>>(&self, cx: &'a Bx::CodegenCx) {
                 }
             }
             MonoItem::Fn(instance) => {
-                base::codegen_instance::<Bx>(cx, instance);
+                // Aliases share the body of their target, so there is nothing to define.
+                if cx.codegen_unit().alias_target(instance).is_none() || !cx.supports_fn_aliases() {
+                    base::codegen_instance::<Bx>(cx, instance);
+                }
             }
         }
 
@@ -134,9 +137,12 @@ fn predefine<Bx: BuilderMethods<'a, 'tcx>>(
             MonoItem::Static(def_id) => {
                 cx.predefine_static(def_id, linkage, visibility, symbol_name);
             }
-            MonoItem::Fn(instance) => {
-                cx.predefine_fn(instance, linkage, visibility, symbol_name);
-            }
+            MonoItem::Fn(instance) => match cx.codegen_unit().alias_target(instance) {
+                Some(target) if cx.supports_fn_aliases() => {
+                    cx.predefine_fn_alias(instance, target, linkage, visibility, symbol_name);
+                }
+                _ => cx.predefine_fn(instance, linkage, visibility, symbol_name),
+            },
             MonoItem::GlobalAsm(..) => {}
         }
 
@@ -111,6 +111,25 @@ fn link(
     fn supports_parallel(&self) -> bool {
         true
     }
+
+    /// Returns `true` if this backend can perform ThinLTO across the codegen units of the crate
+    /// being compiled, which is done by default for optimized builds with several codegen units.
+    /// Otherwise, each codegen unit is optimized on its own.
+    ///
+    /// Defaults to `true`.
+    fn supports_local_thin_lto(&self) -> bool {
+        true
+    }
+
+    /// Returns the arguments that enable LTO at link time with `-Clinker-plugin-lto` for the
+    /// objects emitted by this backend. They are passed to the C compiler driver that invokes the
+    /// linker, like `cc`.
+    ///
+    /// Defaults to `None`, which passes the optimization level and target CPU of the session to
+    /// LLVM's linker plugin.
+    fn linker_plugin_lto_args(&self, _sess: &Session) -> Option<Vec<String>> {
+        None
+    }
 }
 
 pub trait ExtraBackendMethods:
@@ -18,4 +18,23 @@ fn predefine_fn(
         visibility: Visibility,
         symbol_name: &str,
     );
+
+    /// Whether [`Self::predefine_fn_alias`] is implemented. If it isn't, function items
+    /// that are marked as aliases in their codegen unit get a body of their own instead.
+    fn supports_fn_aliases(&self) -> bool {
+        false
+    }
+
+    /// Declares `instance` as an alias of `target`, which is defined in the same codegen
+    /// unit, instead of giving it a body of its own.
+    fn predefine_fn_alias(
+        &self,
+        instance: Instance<'tcx>,
+        _target: Instance<'tcx>,
+        _linkage: Linkage,
+        _visibility: Visibility,
+        _symbol_name: &str,
+    ) {
+        bug!("function aliases are not supported by this backend, cannot declare `{instance}`")
+    }
 }
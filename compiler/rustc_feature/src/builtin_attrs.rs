@@ -336,6 +336,10 @@ pub struct BuiltinAttribute {
         must_not_suspend, Normal, template!(Word, NameValueStr: "reason"), WarnFollowing,
         EncodeCrossCrate::Yes, experimental!(must_not_suspend)
     ),
+    gated!(
+        rustc_blocking, Normal, template!(Word), WarnFollowing, EncodeCrossCrate::Yes,
+        experimental!(rustc_blocking)
+    ),
     ungated!(
         deprecated, Normal,
         template!(
@@ -481,7 +485,7 @@ pub struct BuiltinAttribute {
     ),
     // RFC 2412
     gated!(
-        optimize, Normal, template!(List: "size|speed"), ErrorPreceding,
+        optimize, Normal, template!(List: r#"size|speed|level = "0|1|2|3|s|z""#), ErrorPreceding,
         EncodeCrossCrate::No, optimize_attribute, experimental!(optimize)
     ),
 
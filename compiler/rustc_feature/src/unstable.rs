@@ -221,6 +221,9 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (internal, profiler_runtime, "1.18.0", None),
     /// Allows using `rustc_*` attributes (RFC 572).
     (internal, rustc_attrs, "1.0.0", None),
+    /// Allows using `#[rustc_blocking]` to mark functions that block the current thread, for the
+    /// `blocking_in_async` lint.
+    (unstable, rustc_blocking, "CURRENT_RUSTC_VERSION", None),
     /// Allows using the `#[stable]` and `#[unstable]` attributes.
     (internal, staged_api, "1.0.0", None),
     /// Added for testing unstable lints; perma-unstable.
@@ -437,13 +437,25 @@ fn write_out_deps(tcx: TyCtxt<'_>, outputs: &OutputFilenames, out_filenames: &[P
         files.extend(extra_tracked_files);
 
         // We also need to track used PGO profile files
-        if let Some(ref profile_instr) = sess.opts.cg.profile_use {
+        if let Some(raw_profiles) = sess.raw_pgo_use_profiles() {
+            files.extend(raw_profiles.iter().cloned().map(normalize_path));
+        } else if let Some(ref profile_instr) = sess.opts.cg.profile_use {
             files.push(normalize_path(profile_instr.as_path().to_path_buf()));
         }
         if let Some(ref profile_sample) = sess.opts.unstable_opts.profile_sample_use {
             files.push(normalize_path(profile_sample.as_path().to_path_buf()));
         }
 
+        // The lint configuration file
+        if let Some(ref lint_config) = sess.opts.lint_config {
+            files.push(normalize_path(lint_config.path.clone()));
+        }
+
+        // The baseline of the semver check
+        if let Some(ref baseline) = sess.opts.check_semver_against {
+            files.push(normalize_path(baseline.clone()));
+        }
+
         // Debugger visualizer files
         for debugger_visualizer in tcx.debugger_visualizers(LOCAL_CRATE) {
             files.push(normalize_path(debugger_visualizer.path.clone().unwrap()));
@@ -807,6 +819,9 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
                     },
                     {
                         tcx.ensure().clashing_extern_declarations(());
+                    },
+                    {
+                        tcx.ensure().check_semver(());
                     }
                 );
             },
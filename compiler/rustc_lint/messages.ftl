@@ -30,6 +30,10 @@ lint_bad_attribute_argument = bad attribute argument
 
 lint_bad_opt_access = {$msg}
 
+lint_blocking_in_async = call to the blocking function `{$callee}` in an {$coroutine_kind}
+    .note = the executor thread cannot make progress on other tasks until the call returns
+    .help = use an asynchronous alternative, or move the call to a thread where blocking is allowed
+
 lint_builtin_allow_internal_unsafe =
     `allow_internal_unsafe` allows defining macros using unsafe without triggering the `unsafe_code` lint at their call site
 
@@ -165,6 +169,8 @@ lint_check_name_unknown_tool = unknown lint tool: `{$tool_name}`
 
 lint_command_line_source = `forbid` lint level was set on command line
 
+lint_config_file_source = `forbid` lint level was set in the lint configuration file
+
 lint_confusable_identifier_pair = found both `{$existing_sym}` and `{$sym}` as identifiers, which look alike
     .current_use = this identifier can be confused with `{$existing_sym}`
     .other_use = other identifier used here
@@ -212,6 +218,8 @@ lint_enum_intrinsics_mem_variant =
 
 lint_expectation = this lint expectation is unfulfilled
     .note = the `unfulfilled_lint_expectations` lint can't be expected and will always produce this message
+    .remove_attribute_suggestion = remove the attribute
+    .remove_lint_suggestion = remove the lint from the attribute
     .rationale = {$rationale}
 
 lint_for_loops_over_fallibles =
@@ -547,8 +555,74 @@ lint_renamed_lint = lint `{$name}` has been renamed to `{$replace}`
     .suggestion = use the new name
     .help = use the new name `{$replace}`
 
+lint_requested_in_lint_config = requested in the lint configuration file `{$path}`
+
 lint_requested_level = requested on the command line with `{$level} {$lint_name}`
 
+lint_semver_added = {$descr} `{$path}` was added
+
+lint_semver_auto_trait_changed = {$descr} `{$path}` {$implemented ->
+        [true] now implements
+        *[false] no longer implements
+    } `{$auto_trait}`
+
+lint_semver_bounds_loosened = {$descr} `{$path}` has looser bounds
+
+lint_semver_bounds_tightened = {$descr} `{$path}` has stricter bounds
+
+lint_semver_constness_changed = {$descr} `{$path}` is {$is_const ->
+        [true] now
+        *[false] no longer
+    } `const`
+
+lint_semver_generics_changed = the generic parameters of {$descr} `{$path}` changed
+
+lint_semver_generics_defaulted = {$descr} `{$path}` has new generic parameters with defaults
+
+lint_semver_impl_added = implementation of `{$trait_path}` for `{$self_ty}` was added
+
+lint_semver_impl_removed = implementation of `{$trait_path}` for `{$self_ty}` was removed
+
+lint_semver_kind_changed = `{$path}` changed from {$old_descr} to {$descr}
+
+lint_semver_mutability_changed = static `{$path}` is {$mutable ->
+        [true] now
+        *[false] no longer
+    } mutable
+
+lint_semver_non_exhaustive_changed = {$descr} `{$path}` is {$non_exhaustive ->
+        [true] now
+        *[false] no longer
+    } `#[non_exhaustive]`
+
+lint_semver_object_safety_changed = trait `{$path}` is {$object_safe ->
+        [true] now
+        *[false] no longer
+    } object safe
+
+lint_semver_private_field_added = {$descr} `{$path}` has a new private field
+    .note = it can no longer be constructed outside of its crate
+
+lint_semver_reexport_changed = `{$path}` now refers to a different item
+
+lint_semver_removed = {$descr} `{$path}` was removed
+
+lint_semver_return_auto_trait_changed = the return type of {$descr} `{$path}` {$implemented ->
+        [true] now implements
+        *[false] no longer implements
+    } `{$auto_trait}`
+
+lint_semver_signature_changed = the signature of {$descr} `{$path}` changed
+    .note = it was `{$old}` and is now `{$new}`
+
+lint_semver_type_changed = the type of {$descr} `{$path}` changed
+    .note = it was `{$old}` and is now `{$new}`
+
+lint_semver_unsafety_changed = trait `{$path}` is {$is_unsafe ->
+        [true] now
+        *[false] no longer
+    } `unsafe`
+
 lint_span_use_eq_ctxt = use `.eq_ctxt()` instead of `.ctxt() == .ctxt()`
 
 lint_supertrait_as_deref_target = this `Deref` implementation is covered by an implicit supertrait coercion
@@ -606,6 +680,13 @@ lint_untranslatable_diag = diagnostics should be created using translatable mess
 lint_unused_allocation = unnecessary allocation, use `&` instead
 lint_unused_allocation_mut = unnecessary allocation, use `&mut` instead
 
+lint_unused_allow = this `#[allow]` never suppressed a lint
+
+lint_unused_allows_summary = {$count} lint {$count ->
+        [one] level
+        *[other] levels
+    } set to `allow` never suppressed a lint
+
 lint_unused_closure =
     unused {$pre}{$count ->
         [one] closure
@@ -0,0 +1,109 @@
+use rustc_hir::def_id::DefId;
+use rustc_hir::{CoroutineDesugaring, CoroutineKind, Expr, ExprKind};
+use rustc_middle::ty::{self, GenericArgsRef};
+use rustc_span::sym;
+
+use crate::lints::BlockingInAsyncDiag;
+use crate::{LateContext, LateLintPass, LintContext};
+
+declare_lint! {
+    /// The `blocking_in_async` lint detects calls to functions that block the
+    /// current thread, made directly inside an `async` body.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,edition2021,compile_fail
+    /// #![deny(blocking_in_async)]
+    ///
+    /// async fn wait() {
+    ///     std::thread::sleep(std::time::Duration::from_secs(1));
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// An `async` body runs on an executor that usually polls many tasks on a
+    /// few threads. While a call such as `std::thread::sleep`, a `std::fs`
+    /// function or `Mutex::lock` blocks, the executor thread cannot make
+    /// progress on any other task. An asynchronous alternative should be used
+    /// instead, or the call should be moved to a thread where blocking is
+    /// allowed, e.g. with the `spawn_blocking` function of the executor.
+    ///
+    /// The functions that block are marked with the `#[rustc_blocking]`
+    /// attribute, which crates can also put on their own blocking functions
+    /// with `#![feature(rustc_blocking)]`. For calls to trait methods, both
+    /// the trait method and the method of the implementation that is called
+    /// are checked.
+    ///
+    /// Calls inside closures are not linted, even in an `async` body, since
+    /// closures are commonly run on another thread, e.g. by `spawn_blocking`.
+    pub BLOCKING_IN_ASYNC,
+    Allow,
+    "calls to blocking functions inside `async` bodies"
+}
+
+declare_lint_pass!(BlockingInAsync => [BLOCKING_IN_ASYNC]);
+
+impl<'tcx> LateLintPass<'tcx> for BlockingInAsync {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) {
+        let (def_id, args) = match expr.kind {
+            ExprKind::Call(path, _) => {
+                let ExprKind::Path(ref qpath) = path.kind else { return };
+                let Some(def_id) = cx.qpath_res(qpath, path.hir_id).opt_def_id() else { return };
+                (def_id, cx.typeck_results().node_args(path.hir_id))
+            }
+            ExprKind::MethodCall(..) => {
+                let Some(def_id) = cx.typeck_results().type_dependent_def_id(expr.hir_id) else {
+                    return;
+                };
+                (def_id, cx.typeck_results().node_args(expr.hir_id))
+            }
+            _ => return,
+        };
+
+        // Only calls made directly in the `async` body are linted, and not those in closures
+        // nested in it, which have their own body.
+        let Some(body) = cx.enclosing_body else { return };
+        let body_owner = cx.tcx.hir().body_owner_def_id(body);
+        let Some(
+            coroutine_kind @ CoroutineKind::Desugared(
+                CoroutineDesugaring::Async | CoroutineDesugaring::AsyncGen,
+                _,
+            ),
+        ) = cx.tcx.coroutine_kind(body_owner)
+        else {
+            return;
+        };
+
+        let Some(callee) = blocking_callee(cx, def_id, args) else { return };
+        cx.emit_span_lint(
+            BLOCKING_IN_ASYNC,
+            expr.span,
+            BlockingInAsyncDiag {
+                callee: cx.tcx.def_path_str(callee),
+                coroutine_kind: format!("{coroutine_kind:#}"),
+            },
+        );
+    }
+}
+
+/// Returns the function marked with `#[rustc_blocking]` that a call to `def_id` with `args`
+/// invokes, which is either `def_id` itself or, for a trait method, the method of the impl
+/// that the call resolves to.
+fn blocking_callee<'tcx>(
+    cx: &LateContext<'tcx>,
+    def_id: DefId,
+    args: GenericArgsRef<'tcx>,
+) -> Option<DefId> {
+    if cx.tcx.has_attr(def_id, sym::rustc_blocking) {
+        return Some(def_id);
+    }
+    if cx.tcx.trait_of_item(def_id).is_none() {
+        return None;
+    }
+    let instance = ty::Instance::resolve(cx.tcx, cx.param_env, def_id, args).ok()??;
+    let callee = instance.def_id();
+    (callee != def_id && cx.tcx.has_attr(callee, sym::rustc_blocking)).then_some(callee)
+}
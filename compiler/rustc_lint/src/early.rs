@@ -90,11 +90,21 @@ fn visit_param(&mut self, param: &'a ast::Param) {
     }
 
     fn visit_item(&mut self, it: &'a ast::Item) {
+        // The lint configuration file can override the levels of a module, below its attributes.
+        let module = match &it.kind {
+            ast::ItemKind::Mod(_, ast::ModKind::Loaded(_, _, spans)) => {
+                Some(self.context.builder.push_module(it.ident.name, spans.inner_span))
+            }
+            _ => None,
+        };
         self.with_lint_attrs(it.id, &it.attrs, |cx| {
             lint_callback!(cx, check_item, it);
             ast_visit::walk_item(cx, it);
             lint_callback!(cx, check_item_post, it);
-        })
+        });
+        if let Some(push) = module {
+            self.context.builder.pop_module(push);
+        }
     }
 
     fn visit_foreign_item(&mut self, it: &'a ast::ForeignItem) {
@@ -21,6 +21,7 @@ pub enum OverruledAttributeSub {
     DefaultSource { id: String },
     NodeSource { span: Span, reason: Option<Symbol> },
     CommandLineSource,
+    ConfigFileSource,
 }
 
 impl Subdiagnostic for OverruledAttributeSub {
@@ -44,6 +45,9 @@ fn add_to_diag_with<G: EmissionGuarantee, F: SubdiagMessageOp<G>>(
             OverruledAttributeSub::CommandLineSource => {
                 diag.note(fluent::lint_command_line_source);
             }
+            OverruledAttributeSub::ConfigFileSource => {
+                diag.note(fluent::lint_config_file_source);
+            }
         }
     }
 }
@@ -1,8 +1,14 @@
-use crate::lints::{Expectation, ExpectationNote};
+use crate::lints::{
+    Expectation, ExpectationNote, ExpectationRemoval, UnusedAllowDiag, UnusedAllowsSummary,
+};
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
+use rustc_errors::Applicability;
+use rustc_hir::{self as hir, HirId};
+use rustc_middle::lint::LintExpectation;
 use rustc_middle::query::Providers;
 use rustc_middle::ty::TyCtxt;
-use rustc_session::lint::builtin::UNFULFILLED_LINT_EXPECTATIONS;
-use rustc_session::lint::LintExpectationId;
+use rustc_session::lint::builtin::{UNFULFILLED_LINT_EXPECTATIONS, UNUSED_ALLOW};
+use rustc_session::lint::{Level, LintExpectationId, LintId};
 use rustc_span::symbol::sym;
 use rustc_span::Symbol;
 
@@ -11,29 +17,83 @@ pub(crate) fn provide(providers: &mut Providers) {
 }
 
 fn check_expectations(tcx: TyCtxt<'_>, tool_filter: Option<Symbol>) {
-    if !tcx.features().active(sym::lint_reasons) {
+    let check_expects = tcx.features().active(sym::lint_reasons);
+    let report_unused_allows = tcx.sess.opts.unstable_opts.report_unused_allows;
+    if !check_expects && !is_unused_allow_enabled(tcx) {
         return;
     }
 
     let lint_expectations = tcx.lint_expectations(());
-    let fulfilled_expectations = tcx.dcx().steal_fulfilled_expectation_ids();
+    let mut fulfilled_expectations = tcx.dcx().steal_fulfilled_expectation_ids();
 
     tracing::debug!(?lint_expectations, ?fulfilled_expectations);
 
-    for (id, expectation) in lint_expectations {
+    let (allows, expectations): (Vec<_>, Vec<_>) =
+        lint_expectations.iter().partition(|(_, expectation)| expectation.is_allow);
+
+    // The unused allows are reported first, so that the `unused_allow` lint can be expected. Their
+    // removal may be incorrect, as some lints are not even checked when they are allowed.
+    let mut removals = removal_suggestions(
+        tcx,
+        &allows,
+        &fulfilled_expectations,
+        tool_filter,
+        Applicability::MaybeIncorrect,
+    );
+    let mut unused_allows = 0;
+    for (id, allow) in allows {
+        let LintExpectationId::Stable { hir_id, .. } = *id else {
+            unreachable!("at this stage all `LintExpectationId`s are stable");
+        };
+        if !is_unfulfilled(id, allow, &fulfilled_expectations, tool_filter) {
+            continue;
+        }
+        let removal = removals.swap_remove(id);
+        if report_unused_allows && tcx.lint_level_at_node(UNUSED_ALLOW, hir_id).0 != Level::Allow {
+            unused_allows += 1;
+        }
+        tcx.emit_node_span_lint(
+            UNUSED_ALLOW,
+            hir_id,
+            allow.emission_span,
+            UnusedAllowDiag { removal },
+        );
+    }
+    // The summary is a lint too, so that it is silenced along with the reports, e.g. by
+    // `--cap-lints`.
+    if unused_allows > 0 {
+        tcx.emit_node_lint(
+            UNUSED_ALLOW,
+            hir::CRATE_HIR_ID,
+            UnusedAllowsSummary { count: unused_allows },
+        );
+    }
+
+    if !check_expects {
+        return;
+    }
+    fulfilled_expectations.extend(tcx.dcx().steal_fulfilled_expectation_ids());
+
+    let mut removals = removal_suggestions(
+        tcx,
+        &expectations,
+        &fulfilled_expectations,
+        tool_filter,
+        Applicability::MachineApplicable,
+    );
+    for (id, expectation) in expectations {
         // This check will always be true, since `lint_expectations` only
         // holds stable ids
         if let LintExpectationId::Stable { hir_id, .. } = id {
-            if !fulfilled_expectations.contains(id)
-                && tool_filter.map_or(true, |filter| expectation.lint_tool == Some(filter))
-            {
+            if is_unfulfilled(id, expectation, &fulfilled_expectations, tool_filter) {
                 let rationale = expectation.reason.map(|rationale| ExpectationNote { rationale });
                 let note = expectation.is_unfulfilled_lint_expectations.then_some(());
+                let removal = removals.swap_remove(id);
                 tcx.emit_node_span_lint(
                     UNFULFILLED_LINT_EXPECTATIONS,
                     *hir_id,
                     expectation.emission_span,
-                    Expectation { rationale, note },
+                    Expectation { rationale, note, removal },
                 );
             }
         } else {
@@ -41,3 +101,99 @@ fn check_expectations(tcx: TyCtxt<'_>, tool_filter: Option<Symbol>) {
         }
     }
 }
+
+/// Whether the `unused_allow` lint is enabled anywhere in the crate. The allows are only tracked
+/// after it is enabled, so there is nothing to report otherwise.
+fn is_unused_allow_enabled(tcx: TyCtxt<'_>) -> bool {
+    // The levels given on the command line only show up at the crate root.
+    if tcx.lint_level_at_node(UNUSED_ALLOW, hir::CRATE_HIR_ID).0 != Level::Allow {
+        return true;
+    }
+    let unused_allow = LintId::of(UNUSED_ALLOW);
+    tcx.hir_crate_items(()).owners().any(|owner| {
+        tcx.shallow_lint_levels_on(owner)
+            .specs
+            .values()
+            .any(|specs| specs.get(&unused_allow).is_some_and(|&(level, _)| level != Level::Allow))
+    })
+}
+
+fn is_unfulfilled(
+    id: &LintExpectationId,
+    expectation: &LintExpectation,
+    fulfilled_expectations: &FxHashSet<LintExpectationId>,
+    tool_filter: Option<Symbol>,
+) -> bool {
+    !fulfilled_expectations.contains(id)
+        && tool_filter.map_or(true, |filter| expectation.lint_tool == Some(filter))
+}
+
+/// Computes the suggestions to remove the unfulfilled lints of each attribute.
+///
+/// If all the lints of an attribute are unfulfilled, the whole attribute is removed by the
+/// suggestion of the first one. Otherwise each lint is removed along with the comma that follows
+/// it, except for the lints at the end of the list, which are removed along with the comma that
+/// precedes them by the suggestion of the first of them. This way, the suggestions never overlap
+/// and can all be applied together.
+fn removal_suggestions(
+    tcx: TyCtxt<'_>,
+    lint_expectations: &[&(LintExpectationId, LintExpectation)],
+    fulfilled_expectations: &FxHashSet<LintExpectationId>,
+    tool_filter: Option<Symbol>,
+    applicability: Applicability,
+) -> FxIndexMap<LintExpectationId, ExpectationRemoval> {
+    let mut attrs: FxIndexMap<(HirId, u16), Vec<_>> = FxIndexMap::default();
+    for (id, expectation) in lint_expectations {
+        let LintExpectationId::Stable { hir_id, attr_index, lint_index: Some(lint_index), .. } =
+            *id
+        else {
+            continue;
+        };
+        let unfulfilled = is_unfulfilled(id, expectation, fulfilled_expectations, tool_filter);
+        attrs.entry((hir_id, attr_index)).or_default().push((
+            lint_index,
+            id,
+            expectation,
+            unfulfilled,
+        ));
+    }
+
+    let mut removals = FxIndexMap::default();
+    for lints in attrs.values_mut() {
+        lints.sort_by_key(|&(lint_index, ..)| lint_index);
+        let (_, _, first, _) = lints[0];
+        // Only suggest to remove parts of attributes that are written in the source code, and not
+        // expanded by a macro or from a `cfg_attr`, and for which we know all the lints.
+        if usize::from(first.attr_lint_count) != lints.len()
+            || !first.attr_span.can_be_used_for_suggestions()
+            || !tcx
+                .sess
+                .source_map()
+                .span_to_snippet(first.attr_span)
+                .is_ok_and(|snippet| snippet.starts_with('#'))
+        {
+            continue;
+        }
+
+        // The start of the unfulfilled lints at the end of the list.
+        let tail = lints.iter().rposition(|&(.., unfulfilled)| !unfulfilled).map_or(0, |i| i + 1);
+        if tail == 0 {
+            let (_, id, expectation, _) = lints[0];
+            let span = expectation.attr_span;
+            removals.insert(*id, ExpectationRemoval::Attribute { span, applicability });
+            continue;
+        }
+        for (i, &(_, id, expectation, unfulfilled)) in lints.iter().enumerate() {
+            let span = if i < tail && unfulfilled {
+                expectation.emission_span.until(lints[i + 1].2.emission_span)
+            } else if i == tail {
+                let last = lints.last().unwrap().2.emission_span;
+                lints[i - 1].2.emission_span.shrink_to_hi().to(last)
+            } else {
+                continue;
+            };
+            removals.insert(*id, ExpectationRemoval::Lint { span, applicability });
+        }
+    }
+    removals
+}
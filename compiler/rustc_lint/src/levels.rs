@@ -1,7 +1,8 @@
 use crate::errors::{CheckNameUnknownTool, RequestedLevel, UnsupportedGroup};
 use crate::lints::{
-    DeprecatedLintNameFromCommandLine, RemovedLintFromCommandLine, RenamedLintFromCommandLine,
-    UnknownLintFromCommandLine,
+    DeprecatedLintNameFromCommandLine, RemovedLintFromCommandLine, RemovedLintFromLintConfig,
+    RenamedLintFromCommandLine, RenamedLintFromLintConfig, UnknownLintFromCommandLine,
+    UnknownLintFromLintConfig,
 };
 use crate::{
     builtin::MISSING_DOCS,
@@ -19,6 +20,8 @@
 use rustc_errors::{Diag, DiagMessage, LintDiagnostic, MultiSpan};
 use rustc_feature::{Features, GateIssue};
 use rustc_hir as hir;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::LocalModDefId;
 use rustc_hir::intravisit::{self, Visitor};
 use rustc_hir::HirId;
 use rustc_index::IndexVec;
@@ -29,17 +32,18 @@
 };
 use rustc_middle::query::Providers;
 use rustc_middle::ty::{RegisteredTools, TyCtxt};
+use rustc_session::config::lint_config::{LintConfig, LintConfigLevel};
 use rustc_session::lint::{
     builtin::{
         self, FORBIDDEN_LINT_GROUPS, RENAMED_AND_REMOVED_LINTS, SINGLE_USE_LIFETIMES,
-        UNFULFILLED_LINT_EXPECTATIONS, UNKNOWN_LINTS, UNUSED_ATTRIBUTES,
+        UNFULFILLED_LINT_EXPECTATIONS, UNKNOWN_LINTS, UNUSED_ALLOW, UNUSED_ATTRIBUTES,
     },
     Level, Lint, LintExpectationId, LintId,
 };
 use rustc_session::parse::feature_err;
 use rustc_session::Session;
 use rustc_span::symbol::{sym, Symbol};
-use rustc_span::{Span, DUMMY_SP};
+use rustc_span::{FileName, Span, DUMMY_SP};
 
 use crate::errors::{
     MalformedAttribute, MalformedAttributeSub, OverruledAttribute, OverruledAttributeSub,
@@ -175,8 +179,9 @@ fn shallow_lint_levels_on(tcx: TyCtxt<'_>, owner: hir::OwnerId) -> ShallowLintLe
     }
 
     match attrs.map.range(..) {
-        // There is only something to do if there are attributes at all.
-        [] => {}
+        // There is only something to do if there are attributes at all, or if the lint
+        // configuration file overrides the levels of this module.
+        [] => levels.add_lint_config_overrides_for(tcx, owner.into()),
         // Most of the time, there is only one attribute. Avoid fetching HIR in that case.
         &[(local_id, _)] => levels.add_id(HirId { owner, local_id }),
         // Otherwise, we need to visit the attributes in source code order, so we fetch HIR and do
@@ -208,6 +213,8 @@ fn shallow_lint_levels_on(tcx: TyCtxt<'_>, owner: hir::OwnerId) -> ShallowLintLe
 pub struct TopDown {
     sets: LintLevelSets,
     cur: LintStackIndex,
+    /// The path of the current module, for the overrides of the lint configuration file.
+    module_path: Vec<Symbol>,
 }
 
 pub trait LintLevelsProvider {
@@ -297,6 +304,7 @@ fn push_expectation(&mut self, id: LintExpectationId, expectation: LintExpectati
 impl<'tcx> LintLevelsBuilder<'_, LintLevelQueryMap<'tcx>> {
     fn add_id(&mut self, hir_id: HirId) {
         self.provider.cur = hir_id;
+        self.add_lint_config_overrides_for(self.provider.tcx, hir_id);
         self.add(
             self.provider.attrs.get(hir_id.local_id),
             hir_id == hir::CRATE_HIR_ID,
@@ -377,9 +385,14 @@ fn visit_impl_item(&mut self, impl_item: &'tcx hir::ImplItem<'tcx>) {
 
 impl<'tcx> LintLevelsBuilder<'_, QueryMapExpectationsWrapper<'tcx>> {
     fn add_id(&mut self, hir_id: HirId) {
-        // Change both the `HirId` and the associated specs.
+        // Change both the `HirId` and the associated specs. The command-line levels, which are
+        // added beforehand for the crate root, are kept so that the levels match those of the
+        // other builders.
         self.provider.cur = hir_id;
-        self.provider.specs.specs.clear();
+        if hir_id != hir::CRATE_HIR_ID {
+            self.provider.specs.specs.clear();
+        }
+        self.add_lint_config_overrides_for(self.provider.tcx, hir_id);
         self.add(self.provider.tcx.hir().attrs(hir_id), hir_id == hir::CRATE_HIR_ID, Some(hir_id));
     }
 }
@@ -473,7 +486,11 @@ pub(crate) fn new(
         let mut builder = LintLevelsBuilder {
             sess,
             features,
-            provider: TopDown { sets: LintLevelSets::new(), cur: COMMAND_LINE },
+            provider: TopDown {
+                sets: LintLevelSets::new(),
+                cur: COMMAND_LINE,
+                module_path: Vec::new(),
+            },
             lint_added_lints,
             store,
             registered_tools,
@@ -490,6 +507,9 @@ fn process_command_line(&mut self) {
             .list
             .push(LintSet { specs: FxIndexMap::default(), parent: COMMAND_LINE });
         self.add_command_line();
+        if let Some(file) = self.sess.local_crate_source_file() {
+            self.add_lint_config_overrides("", &FileName::Real(file));
+        }
     }
 
     /// Pushes a list of AST lint attributes onto this context.
@@ -531,6 +551,36 @@ pub(crate) fn pop(&mut self, push: BuilderPush) {
         self.provider.cur = push.prev;
         std::mem::forget(push);
     }
+
+    /// Pushes the levels that the overrides of the lint configuration file set for a module.
+    /// This must be called before pushing the attributes of the module, which take precedence.
+    ///
+    /// Don't forget to call `pop_module`!
+    pub(crate) fn push_module(&mut self, name: Symbol, inner_span: Span) -> BuilderPush {
+        self.provider.module_path.push(name);
+        let prev = self.provider.cur;
+        if self.sess.opts.lint_config.as_ref().is_some_and(|config| !config.overrides.is_empty()) {
+            self.provider.cur = self
+                .provider
+                .sets
+                .list
+                .push(LintSet { specs: FxIndexMap::default(), parent: prev });
+            let path = self.provider.module_path.iter().map(Symbol::as_str).collect::<Vec<_>>();
+            let file = self.sess.source_map().span_to_filename(inner_span);
+            self.add_lint_config_overrides(&path.join("::"), &file);
+            if self.provider.current_specs().is_empty() {
+                self.provider.sets.list.pop();
+                self.provider.cur = prev;
+            }
+        }
+        BuilderPush { prev }
+    }
+
+    /// Called after `push_module` when the module is exited.
+    pub(crate) fn pop_module(&mut self, push: BuilderPush) {
+        self.provider.module_path.pop();
+        self.pop(push);
+    }
 }
 
 #[cfg(debug_assertions)]
@@ -558,6 +608,18 @@ fn insert(&mut self, id: LintId, lvl: LevelAndSource) {
     }
 
     fn add_command_line(&mut self) {
+        if let Some(config) = &self.sess.opts.lint_config {
+            self.add_lint_config(config);
+        }
+
+        // `-Zreport-unused-allows` is a shorthand for `-W unused-allow`, which the lint flags below
+        // can still override.
+        if self.sess.opts.unstable_opts.report_unused_allows {
+            let lint_flag_val = Symbol::intern(&UNUSED_ALLOW.name_lower());
+            let src = LintLevelSource::CommandLine(lint_flag_val, Level::Warn);
+            self.insert(LintId::of(UNUSED_ALLOW), (Level::Warn, src));
+        }
+
         for &(ref lint_name, level) in &self.sess.opts.lint_opts {
             // Checks the validity of lint names derived from the command line.
             let (tool_name, lint_name_only) = parse_lint_and_tool_name(lint_name);
@@ -629,6 +691,96 @@ fn add_command_line(&mut self) {
         }
     }
 
+    /// Adds the levels of the `[lints]` table of the lint configuration file, which the
+    /// command-line flags override, and checks the lint names used in the whole file.
+    fn add_lint_config(&mut self, config: &LintConfig) {
+        let path = config.path.display().to_string();
+        let overrides = config.overrides.iter().flat_map(|over| &over.levels);
+        for LintConfigLevel { name, .. } in config.levels.iter().chain(overrides) {
+            let (tool_name, lint_name_only) = parse_lint_and_tool_name(name);
+            match self.store.check_lint_name(lint_name_only, tool_name, self.registered_tools) {
+                CheckLintNameResult::Renamed(ref replace) => {
+                    let suggestion = RenamedLintSuggestion::WithoutSpan { replace };
+                    let lint = RenamedLintFromLintConfig { name, suggestion, path: &path };
+                    self.emit_lint(RENAMED_AND_REMOVED_LINTS, lint);
+                }
+                CheckLintNameResult::Removed(ref reason) => {
+                    let lint = RemovedLintFromLintConfig { name, reason, path: &path };
+                    self.emit_lint(RENAMED_AND_REMOVED_LINTS, lint);
+                }
+                CheckLintNameResult::NoLint(suggestion) => {
+                    let suggestion = suggestion.map(|(replace, from_rustc)| {
+                        UnknownLintSuggestion::WithoutSpan { replace, from_rustc }
+                    });
+                    let lint =
+                        UnknownLintFromLintConfig { name: name.clone(), suggestion, path: &path };
+                    self.emit_lint(UNKNOWN_LINTS, lint);
+                }
+                CheckLintNameResult::NoTool => {
+                    let lint = UnknownLintFromLintConfig {
+                        name: name.clone(),
+                        suggestion: None,
+                        path: &path,
+                    };
+                    self.emit_lint(UNKNOWN_LINTS, lint);
+                }
+                _ => {}
+            }
+        }
+
+        self.insert_lint_config_levels(&config.levels, None);
+    }
+
+    /// Adds the levels that the overrides of the lint configuration file set for a module, given
+    /// the path of the module from the crate root and the source file of its contents.
+    fn add_lint_config_overrides(&mut self, module_path: &str, file: &FileName) {
+        let Some(config) = &self.sess.opts.lint_config else { return };
+        let file = file.prefer_local().to_string();
+        for over in &config.overrides {
+            if let Some(pattern) = over.matching_pattern(module_path, &file) {
+                self.insert_lint_config_levels(&over.levels, Some(Symbol::intern(pattern)));
+            }
+        }
+    }
+
+    /// Like `add_lint_config_overrides`, for a HIR node, which only has overrides if it is a
+    /// module.
+    fn add_lint_config_overrides_for(&mut self, tcx: TyCtxt<'_>, hir_id: HirId) {
+        if self.sess.opts.lint_config.as_ref().map_or(true, |config| config.overrides.is_empty())
+            || !hir_id.is_owner()
+            || tcx.def_kind(hir_id.owner.def_id) != DefKind::Mod
+        {
+            return;
+        }
+        let def_id = hir_id.owner.def_id;
+        let (module, _, _) = tcx.hir().get_module(LocalModDefId::new_unchecked(def_id));
+        let path = tcx.def_path(def_id.to_def_id()).to_string_no_crate_verbose();
+        let file = tcx.sess.source_map().span_to_filename(module.spans.inner_span);
+        self.add_lint_config_overrides(path.trim_start_matches("::"), &file);
+    }
+
+    fn insert_lint_config_levels(&mut self, levels: &[LintConfigLevel], pattern: Option<Symbol>) {
+        for &LintConfigLevel { ref name, level } in levels {
+            let Ok(ids) = self.store.find_lints(name) else {
+                // errors already handled in `add_lint_config`
+                continue;
+            };
+            let src = LintLevelSource::ConfigFile { name: Symbol::intern(name), level, pattern };
+            for id in ids {
+                // ForceWarn and Forbid cannot be overridden
+                if let (Level::ForceWarn(_) | Level::Forbid, _) =
+                    self.provider.get_lint_level(id.lint, self.sess)
+                {
+                    continue;
+                }
+
+                if self.check_gated_lint(id, DUMMY_SP, true) {
+                    self.insert(id, (level, src));
+                }
+            }
+        }
+    }
+
     /// Attempts to insert the `id` to `level_src` map entry. If unsuccessful
     /// (e.g. if a forbid was already inserted on the same scope), then emits a
     /// diagnostic with no change to `specs`.
@@ -657,6 +809,7 @@ fn insert_spec(&mut self, id: LintId, (mut level, src): LevelAndSource) {
                 LintLevelSource::Default => false,
                 LintLevelSource::Node { name, .. } => self.store.is_lint_group(name),
                 LintLevelSource::CommandLine(symbol, _) => self.store.is_lint_group(symbol),
+                LintLevelSource::ConfigFile { name, .. } => self.store.is_lint_group(name),
             };
             debug!(
                 "fcw_warning={:?}, specs.get(&id) = {:?}, old_src={:?}, id_name={:?}",
@@ -673,6 +826,7 @@ fn insert_spec(&mut self, id: LintId, (mut level, src): LevelAndSource) {
                     OverruledAttributeSub::NodeSource { span, reason }
                 }
                 LintLevelSource::CommandLine(_, _) => OverruledAttributeSub::CommandLineSource,
+                LintLevelSource::ConfigFile { .. } => OverruledAttributeSub::ConfigFileSource,
             };
             if !fcw_warning {
                 self.sess.dcx().emit_err(OverruledAttribute {
@@ -768,6 +922,24 @@ fn add(&mut self, attrs: &[ast::Attribute], is_crate_node: bool, source_hir_id:
                 Some(lvl) => lvl,
             };
 
+            // When unused allows are reported, allows are given ids like expectations, which are
+            // fulfilled when the allow suppresses a lint.
+            let allow_id = if let Level::Allow = level
+                && self.tracks_allows()
+            {
+                Some(match source_hir_id {
+                    Some(hir_id) => LintExpectationId::Stable {
+                        hir_id,
+                        attr_index: attr_index.try_into().unwrap(),
+                        lint_index: None,
+                        attr_id: Some(attr.id),
+                    },
+                    None => LintExpectationId::Unstable { attr_id: attr.id, lint_index: None },
+                })
+            } else {
+                None
+            };
+
             let Some(mut metas) = attr.meta_item_list() else { continue };
 
             // Check whether `metas` is empty, and get its last element.
@@ -821,6 +993,7 @@ fn add(&mut self, attrs: &[ast::Attribute], is_crate_node: bool, source_hir_id:
                 }
             }
 
+            let attr_lint_count = metas.len() as u16;
             for (lint_index, li) in metas.iter_mut().enumerate() {
                 let level = match level {
                     Level::Expect(mut id) => {
@@ -829,6 +1002,13 @@ fn add(&mut self, attrs: &[ast::Attribute], is_crate_node: bool, source_hir_id:
                     }
                     level => level,
                 };
+                let allow_id = allow_id.map(|mut id| {
+                    id.set_lint_index(Some(lint_index as u16));
+                    id
+                });
+                // The id stored in the lint level map must be hashable.
+                let src_allow_id =
+                    allow_id.map(|id| if id.is_stable() { id.normalize() } else { id });
 
                 let sp = li.span();
                 let meta_item = match li {
@@ -858,27 +1038,36 @@ fn add(&mut self, attrs: &[ast::Attribute], is_crate_node: bool, source_hir_id:
                     self.store.check_lint_name(&name, tool_name, self.registered_tools);
                 match &lint_result {
                     CheckLintNameResult::Ok(ids) => {
+                        // An `#[allow(unused_allow)]` is not tracked, as it can only suppress
+                        // lints once all the other allows have been checked.
+                        let (allow_id, src_allow_id) = match ids {
+                            [lint] if *lint == LintId::of(UNUSED_ALLOW) => (None, None),
+                            _ => (allow_id, src_allow_id),
+                        };
+
                         // This checks for instances where the user writes `#[expect(unfulfilled_lint_expectations)]`
                         // in that case we want to avoid overriding the lint level but instead add an expectation that
                         // can't be fulfilled. The lint message will include an explanation, that the
                         // `unfulfilled_lint_expectations` lint can't be expected.
-                        if let Level::Expect(expect_id) = level {
-                            // The `unfulfilled_lint_expectations` lint is not part of any lint groups. Therefore. we
-                            // only need to check the slice if it contains a single lint.
-                            let is_unfulfilled_lint_expectations = match ids {
-                                [lint] => *lint == LintId::of(UNFULFILLED_LINT_EXPECTATIONS),
-                                _ => false,
-                            };
-                            self.provider.push_expectation(
-                                expect_id,
-                                LintExpectation::new(
-                                    reason,
-                                    sp,
-                                    is_unfulfilled_lint_expectations,
-                                    tool_name,
-                                ),
-                            );
-                        }
+                        //
+                        // The `unfulfilled_lint_expectations` lint is not part of any lint groups.
+                        // Therefore, we only need to check the slice if it contains a single lint.
+                        let is_unfulfilled_lint_expectations = match ids {
+                            [lint] => *lint == LintId::of(UNFULFILLED_LINT_EXPECTATIONS),
+                            _ => false,
+                        };
+                        self.push_expectation(
+                            level,
+                            allow_id,
+                            LintExpectation::new(
+                                reason,
+                                sp,
+                                is_unfulfilled_lint_expectations,
+                                tool_name,
+                                attr.span,
+                                attr_lint_count,
+                            ),
+                        );
                         let src = LintLevelSource::Node {
                             name: meta_item
                                 .path
@@ -889,6 +1078,7 @@ fn add(&mut self, attrs: &[ast::Attribute], is_crate_node: bool, source_hir_id:
                                 .name,
                             span: sp,
                             reason,
+                            allow_id: src_allow_id,
                         };
                         for &id in *ids {
                             if self.check_gated_lint(id, attr.span, false) {
@@ -906,18 +1096,25 @@ fn add(&mut self, attrs: &[ast::Attribute], is_crate_node: bool, source_hir_id:
                                     name: Symbol::intern(complete_name),
                                     span: sp,
                                     reason,
+                                    allow_id: src_allow_id,
                                 };
                                 for &id in ids {
                                     if self.check_gated_lint(id, attr.span, false) {
                                         self.insert_spec(id, (level, src));
                                     }
                                 }
-                                if let Level::Expect(expect_id) = level {
-                                    self.provider.push_expectation(
-                                        expect_id,
-                                        LintExpectation::new(reason, sp, false, tool_name),
-                                    );
-                                }
+                                self.push_expectation(
+                                    level,
+                                    allow_id,
+                                    LintExpectation::new(
+                                        reason,
+                                        sp,
+                                        false,
+                                        tool_name,
+                                        attr.span,
+                                        attr_lint_count,
+                                    ),
+                                );
                             }
                             Err((Some(ids), ref new_lint_name)) => {
                                 let lint = builtin::RENAMED_AND_REMOVED_LINTS;
@@ -935,16 +1132,23 @@ fn add(&mut self, attrs: &[ast::Attribute], is_crate_node: bool, source_hir_id:
                                     name: Symbol::intern(new_lint_name),
                                     span: sp,
                                     reason,
+                                    allow_id: src_allow_id,
                                 };
                                 for id in ids {
                                     self.insert_spec(*id, (level, src));
                                 }
-                                if let Level::Expect(expect_id) = level {
-                                    self.provider.push_expectation(
-                                        expect_id,
-                                        LintExpectation::new(reason, sp, false, tool_name),
-                                    );
-                                }
+                                self.push_expectation(
+                                    level,
+                                    allow_id,
+                                    LintExpectation::new(
+                                        reason,
+                                        sp,
+                                        false,
+                                        tool_name,
+                                        attr.span,
+                                        attr_lint_count,
+                                    ),
+                                );
                             }
                             Err((None, _)) => {
                                 // If Tool(Err(None, _)) is returned, then either the lint does not
@@ -1002,19 +1206,29 @@ fn add(&mut self, attrs: &[ast::Attribute], is_crate_node: bool, source_hir_id:
                         panic!("renamed lint does not exist: {new_name}");
                     };
 
-                    let src =
-                        LintLevelSource::Node { name: Symbol::intern(&new_name), span: sp, reason };
+                    let src = LintLevelSource::Node {
+                        name: Symbol::intern(&new_name),
+                        span: sp,
+                        reason,
+                        allow_id: src_allow_id,
+                    };
                     for &id in ids {
                         if self.check_gated_lint(id, attr.span, false) {
                             self.insert_spec(id, (level, src));
                         }
                     }
-                    if let Level::Expect(expect_id) = level {
-                        self.provider.push_expectation(
-                            expect_id,
-                            LintExpectation::new(reason, sp, false, tool_name),
-                        );
-                    }
+                    self.push_expectation(
+                        level,
+                        allow_id,
+                        LintExpectation::new(
+                            reason,
+                            sp,
+                            false,
+                            tool_name,
+                            attr.span,
+                            attr_lint_count,
+                        ),
+                    );
                 }
             }
         }
@@ -1041,6 +1255,28 @@ fn add(&mut self, attrs: &[ast::Attribute], is_crate_node: bool, source_hir_id:
         }
     }
 
+    /// Whether the `#[allow]` attributes that are added at this point are tracked, to report those
+    /// that never suppress a lint with the `unused_allow` lint.
+    fn tracks_allows(&self) -> bool {
+        self.provider.get_lint_level(UNUSED_ALLOW, self.sess).0 != Level::Allow
+    }
+
+    /// Records the expectation of an `#[expect]` attribute, or of an `#[allow]` attribute that is
+    /// tracked with `allow_id`.
+    fn push_expectation(
+        &mut self,
+        level: Level,
+        allow_id: Option<LintExpectationId>,
+        mut expectation: LintExpectation,
+    ) {
+        if let Level::Expect(expect_id) = level {
+            self.provider.push_expectation(expect_id, expectation);
+        } else if let Some(allow_id) = allow_id {
+            expectation.is_allow = true;
+            self.provider.push_expectation(allow_id, expectation);
+        }
+    }
+
     /// Checks if the lint is gated on a feature that is not enabled.
     ///
     /// Returns `true` if the lint's feature is enabled.
@@ -49,6 +49,7 @@
 
 mod array_into_iter;
 mod async_fn_in_trait;
+mod blocking_in_async;
 pub mod builtin;
 mod context;
 mod deref_into_dyn_supertrait;
@@ -80,6 +81,7 @@
 mod ptr_nulls;
 mod redundant_semicolon;
 mod reference_casting;
+mod semver;
 mod traits;
 mod types;
 mod unit_bindings;
@@ -93,6 +95,7 @@
 
 use array_into_iter::ArrayIntoIter;
 use async_fn_in_trait::AsyncFnInTrait;
+use blocking_in_async::BlockingInAsync;
 use builtin::*;
 use deref_into_dyn_supertrait::*;
 use drop_forget_useless::*;
@@ -137,6 +140,7 @@ pub fn provide(providers: &mut Providers) {
     levels::provide(providers);
     expect::provide(providers);
     foreign_modules::provide(providers);
+    semver::provide(providers);
     *providers = Providers { lint_mod, ..*providers };
 }
 
@@ -232,6 +236,7 @@ fn lint_mod(tcx: TyCtxt<'_>, module_def_id: LocalModDefId) {
             MissingDebugImplementations: MissingDebugImplementations,
             MissingDoc: MissingDoc,
             AsyncFnInTrait: AsyncFnInTrait,
+            BlockingInAsync: BlockingInAsync,
             NonLocalDefinitions: NonLocalDefinitions::default(),
         ]
     ]
@@ -262,6 +267,7 @@ macro_rules! add_lint_group {
     store.register_lints(&BuiltinCombinedEarlyLintPass::get_lints());
     store.register_lints(&BuiltinCombinedModuleLateLintPass::get_lints());
     store.register_lints(&foreign_modules::get_lints());
+    store.register_lints(&semver::get_lints());
 
     add_lint_group!(
         "nonstandard_style",
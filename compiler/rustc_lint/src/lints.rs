@@ -11,7 +11,8 @@
 use rustc_hir::def_id::DefId;
 use rustc_macros::{LintDiagnostic, Subdiagnostic};
 use rustc_middle::ty::{
-    inhabitedness::InhabitedPredicate, Clause, PolyExistentialTraitRef, Ty, TyCtxt,
+    inhabitedness::InhabitedPredicate, print::TraitRefPrintOnlyTraitPath, Clause,
+    PolyExistentialTraitRef, PolyFnSig, Ty, TyCtxt,
 };
 use rustc_session::Session;
 use rustc_span::{edition::Edition, sym, symbol::Ident, Span, Symbol};
@@ -50,6 +51,16 @@ pub enum ArrayIntoIterDiagSub {
     },
 }
 
+// blocking_in_async.rs
+#[derive(LintDiagnostic)]
+#[diag(lint_blocking_in_async)]
+#[note]
+#[help]
+pub struct BlockingInAsyncDiag {
+    pub callee: String,
+    pub coroutine_kind: String,
+}
+
 // builtin.rs
 #[derive(LintDiagnostic)]
 #[diag(lint_builtin_while_true)]
@@ -580,6 +591,8 @@ pub struct Expectation {
     pub rationale: Option<ExpectationNote>,
     #[note]
     pub note: Option<()>,
+    #[subdiagnostic]
+    pub removal: Option<ExpectationRemoval>,
 }
 
 #[derive(Subdiagnostic)]
@@ -588,6 +601,38 @@ pub struct ExpectationNote {
     pub rationale: Symbol,
 }
 
+// Only shown in the JSON output, so that tools can clean up the attributes.
+#[derive(Clone, Copy, Subdiagnostic)]
+pub enum ExpectationRemoval {
+    #[suggestion(lint_remove_attribute_suggestion, code = "", style = "tool-only")]
+    Attribute {
+        #[primary_span]
+        span: Span,
+        #[applicability]
+        applicability: Applicability,
+    },
+    #[suggestion(lint_remove_lint_suggestion, code = "", style = "tool-only")]
+    Lint {
+        #[primary_span]
+        span: Span,
+        #[applicability]
+        applicability: Applicability,
+    },
+}
+
+#[derive(LintDiagnostic)]
+#[diag(lint_unused_allow)]
+pub struct UnusedAllowDiag {
+    #[subdiagnostic]
+    pub removal: Option<ExpectationRemoval>,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(lint_unused_allows_summary)]
+pub struct UnusedAllowsSummary {
+    pub count: usize,
+}
+
 // ptr_nulls.rs
 #[derive(LintDiagnostic)]
 pub enum PtrNullChecksDiag<'a> {
@@ -1045,6 +1090,16 @@ pub struct RenamedLintFromCommandLine<'a> {
     pub requested_level: RequestedLevel<'a>,
 }
 
+#[derive(LintDiagnostic)]
+#[diag(lint_renamed_lint)]
+#[note(lint_requested_in_lint_config)]
+pub struct RenamedLintFromLintConfig<'a> {
+    pub name: &'a str,
+    #[subdiagnostic]
+    pub suggestion: RenamedLintSuggestion<'a>,
+    pub path: &'a str,
+}
+
 #[derive(LintDiagnostic)]
 #[diag(lint_removed_lint)]
 pub struct RemovedLint<'a> {
@@ -1061,6 +1116,15 @@ pub struct RemovedLintFromCommandLine<'a> {
     pub requested_level: RequestedLevel<'a>,
 }
 
+#[derive(LintDiagnostic)]
+#[diag(lint_removed_lint)]
+#[note(lint_requested_in_lint_config)]
+pub struct RemovedLintFromLintConfig<'a> {
+    pub name: &'a str,
+    pub reason: &'a str,
+    pub path: &'a str,
+}
+
 #[derive(LintDiagnostic)]
 #[diag(lint_unknown_lint)]
 pub struct UnknownLint {
@@ -1092,6 +1156,16 @@ pub struct UnknownLintFromCommandLine<'a> {
     pub requested_level: RequestedLevel<'a>,
 }
 
+#[derive(LintDiagnostic)]
+#[diag(lint_unknown_lint, code = E0602)]
+#[note(lint_requested_in_lint_config)]
+pub struct UnknownLintFromLintConfig<'a> {
+    pub name: String,
+    #[subdiagnostic]
+    pub suggestion: Option<UnknownLintSuggestion>,
+    pub path: &'a str,
+}
+
 #[derive(LintDiagnostic)]
 #[diag(lint_ignored_unless_crate_specified)]
 pub struct IgnoredUnlessCrateSpecified<'a> {
@@ -1389,6 +1463,64 @@ pub struct RedundantSemicolonsDiag {
     pub suggestion: Span,
 }
 
+// semver.rs
+#[derive(LintDiagnostic)]
+pub enum SemverChange<'tcx> {
+    #[diag(lint_semver_added)]
+    Added { descr: &'static str, path: String },
+    #[diag(lint_semver_removed)]
+    Removed { descr: &'static str, path: String },
+    #[diag(lint_semver_kind_changed)]
+    KindChanged { path: String, old_descr: &'static str, descr: &'static str },
+    #[diag(lint_semver_reexport_changed)]
+    ReexportChanged { path: String },
+    #[diag(lint_semver_signature_changed)]
+    #[note]
+    SignatureChanged {
+        descr: &'static str,
+        path: String,
+        old: PolyFnSig<'tcx>,
+        new: PolyFnSig<'tcx>,
+    },
+    #[diag(lint_semver_type_changed)]
+    #[note]
+    TypeChanged { descr: &'static str, path: String, old: Ty<'tcx>, new: Ty<'tcx> },
+    #[diag(lint_semver_constness_changed)]
+    ConstnessChanged { descr: &'static str, path: String, is_const: bool },
+    #[diag(lint_semver_mutability_changed)]
+    MutabilityChanged { path: String, mutable: bool },
+    #[diag(lint_semver_generics_changed)]
+    GenericsChanged { descr: &'static str, path: String },
+    #[diag(lint_semver_generics_defaulted)]
+    GenericsDefaulted { descr: &'static str, path: String },
+    #[diag(lint_semver_bounds_tightened)]
+    BoundsTightened { descr: &'static str, path: String },
+    #[diag(lint_semver_bounds_loosened)]
+    BoundsLoosened { descr: &'static str, path: String },
+    #[diag(lint_semver_non_exhaustive_changed)]
+    NonExhaustiveChanged { descr: &'static str, path: String, non_exhaustive: bool },
+    #[diag(lint_semver_private_field_added)]
+    #[note]
+    PrivateFieldAdded { descr: &'static str, path: String },
+    #[diag(lint_semver_unsafety_changed)]
+    UnsafetyChanged { path: String, is_unsafe: bool },
+    #[diag(lint_semver_object_safety_changed)]
+    ObjectSafetyChanged { path: String, object_safe: bool },
+    #[diag(lint_semver_auto_trait_changed)]
+    AutoTraitChanged { descr: &'static str, path: String, auto_trait: Symbol, implemented: bool },
+    #[diag(lint_semver_return_auto_trait_changed)]
+    ReturnAutoTraitChanged {
+        descr: &'static str,
+        path: String,
+        auto_trait: Symbol,
+        implemented: bool,
+    },
+    #[diag(lint_semver_impl_added)]
+    ImplAdded { trait_path: TraitRefPrintOnlyTraitPath<'tcx>, self_ty: Ty<'tcx> },
+    #[diag(lint_semver_impl_removed)]
+    ImplRemoved { trait_path: TraitRefPrintOnlyTraitPath<'tcx>, self_ty: Ty<'tcx> },
+}
+
 // traits.rs
 pub struct DropTraitConstraintsDiag<'a> {
     pub predicate: Clause<'a>,
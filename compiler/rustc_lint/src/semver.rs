@@ -0,0 +1,1101 @@
+//! Comparison of the public API of the current crate with a previous version of it.
+//!
+//! The previous version is loaded from its metadata by `--check-semver-against` and every
+//! difference in the public API is reported as either a major or a minor change, following the
+//! classification of [RFC 1105](https://rust-lang.github.io/rfcs/1105-api-evolution.html).
+
+use std::cell::{OnceCell, RefCell};
+use std::collections::VecDeque;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Namespace, Res};
+use rustc_hir::def_id::{CrateNum, DefId, CRATE_DEF_ID, LOCAL_CRATE};
+use rustc_hir::definitions::DefPathData;
+use rustc_middle::query::Providers;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{
+    self, GenericArgKind, GenericArgsRef, GenericParamDefKind, Ty, TyCtxt, TypeFoldable,
+    TypeFolder, TypeSuperFoldable, TypeVisitableExt,
+};
+use rustc_session::lint::Lint;
+use rustc_span::symbol::{kw, sym, Symbol};
+use rustc_trait_selection::infer::{InferCtxtExt, TyCtxtInferExt};
+use rustc_trait_selection::traits::query::evaluate_obligation::InferCtxtExt as _;
+use rustc_trait_selection::traits::{elaborate, Obligation, ObligationCause, Reveal};
+
+use crate::lints::SemverChange;
+use crate::LintVec;
+
+pub(crate) fn provide(providers: &mut Providers) {
+    *providers = Providers { check_semver, ..*providers };
+}
+
+pub(crate) fn get_lints() -> LintVec {
+    vec![SEMVER_MAJOR_CHANGES, SEMVER_MINOR_CHANGES]
+}
+
+declare_lint! {
+    /// The `semver_major_changes` lint detects changes to the public API that
+    /// are breaking according to the semver rules of Rust.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs a previous version of the crate)
+    /// // Compiled with `-Zunstable-options --check-semver-against=libfoo-old.rmeta`,
+    /// // where the previous version had a `pub fn bar()`.
+    /// pub fn baz() {}
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: function `bar` was removed
+    ///   |
+    ///   = note: `#[warn(semver_major_changes)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// The lint only runs when `--check-semver-against` is given a previous
+    /// version of the crate. It reports changes that may break downstream
+    /// crates, such as removed items, changed signatures, stricter bounds or
+    /// lost auto trait implementations, which require a new major version.
+    pub SEMVER_MAJOR_CHANGES,
+    Warn,
+    "detects breaking changes to the public API compared to a previous version"
+}
+
+declare_lint! {
+    /// The `semver_minor_changes` lint detects additions to the public API
+    /// that require a new minor version according to the semver rules of
+    /// Rust.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs a previous version of the crate)
+    /// // Compiled with `-Zunstable-options --check-semver-against=libfoo-old.rmeta`,
+    /// // where the previous version did not have `baz`.
+    /// pub fn baz() {}
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: function `baz` was added
+    ///  --> src/lib.rs:3:1
+    ///   |
+    /// 3 | pub fn baz() {}
+    ///   | ^^^^^^^^^^^^
+    ///   |
+    ///   = note: `#[warn(semver_minor_changes)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// The lint only runs when `--check-semver-against` is given a previous
+    /// version of the crate. It reports changes that are compatible for
+    /// downstream crates, such as new items, looser bounds or new trait
+    /// implementations, which still require a new minor version.
+    pub SEMVER_MINOR_CHANGES,
+    Warn,
+    "detects compatible additions to the public API compared to a previous version"
+}
+
+fn check_semver(tcx: TyCtxt<'_>, (): ()) {
+    let Some(baseline) = tcx.semver_baseline(()) else { return };
+    let old_api = public_api(tcx, baseline);
+    let new_api = public_api(tcx, LOCAL_CRATE);
+    let checker = SemverChecker {
+        tcx,
+        baseline,
+        old_paths: old_api.iter().map(|(path, item)| (item.def_id, path.clone())).collect(),
+        old_api,
+        new_api,
+        def_ids: Default::default(),
+        local_def_paths: OnceCell::new(),
+        auto_traits: [
+            tcx.get_diagnostic_item(sym::Send),
+            tcx.lang_items().sync_trait(),
+            tcx.lang_items().unpin_trait(),
+            tcx.get_diagnostic_item(sym::unwind_safe_trait),
+            tcx.get_diagnostic_item(sym::ref_unwind_safe_trait),
+        ]
+        .into_iter()
+        .flatten()
+        .collect(),
+    };
+    checker.check_items();
+    checker.check_impls();
+}
+
+/// An item that can be named from outside of its crate.
+struct ApiItem {
+    def_id: DefId,
+    /// The item or re-export that makes it public, which is where changes are reported.
+    site: DefId,
+}
+
+type ApiPath = (String, Namespace);
+
+/// Collects the items that are reachable through public paths of `krate`, keyed by their
+/// shortest path relative to the crate root.
+fn public_api(tcx: TyCtxt<'_>, krate: CrateNum) -> FxIndexMap<ApiPath, ApiItem> {
+    let mut api = FxIndexMap::default();
+    let mut visited = FxHashSet::default();
+    let mut queue = VecDeque::from([(String::new(), krate.as_def_id())]);
+    while let Some((prefix, module)) = queue.pop_front() {
+        if !visited.insert(module) {
+            continue;
+        }
+        let children = match module.as_local() {
+            Some(module) => tcx.module_children_local(module),
+            None => tcx.module_children(module),
+        };
+        for child in children {
+            let Res::Def(kind, def_id) = child.res else { continue };
+            if !child.vis.is_public()
+                || child.ident.name == kw::Underscore
+                || matches!(kind, DefKind::Ctor(..))
+                || tcx.is_doc_hidden(def_id)
+            {
+                continue;
+            }
+            let Some(ns) = child.res.ns() else { continue };
+            let path = format!("{prefix}{}", child.ident);
+            if kind == DefKind::Mod && def_id.krate == krate {
+                queue.push_back((format!("{path}::"), def_id));
+            }
+            let site = child.reexport_chain.first().and_then(|r| r.id()).unwrap_or(def_id);
+            api.entry((path, ns)).or_insert(ApiItem { def_id, site });
+        }
+    }
+    api
+}
+
+fn has_generics(kind: DefKind) -> bool {
+    matches!(
+        kind,
+        DefKind::Struct
+            | DefKind::Enum
+            | DefKind::Union
+            | DefKind::Trait
+            | DefKind::TraitAlias
+            | DefKind::TyAlias
+            | DefKind::ForeignTy
+            | DefKind::AssocTy
+            | DefKind::AssocFn
+            | DefKind::AssocConst
+            | DefKind::Fn
+            | DefKind::Const
+            | DefKind::AnonConst
+            | DefKind::InlineConst
+            | DefKind::OpaqueTy
+    )
+}
+
+type DefPathKey = Vec<(DefPathData, u32)>;
+
+fn def_path_key(tcx: TyCtxt<'_>, def_id: DefId) -> DefPathKey {
+    tcx.def_path(def_id).data.into_iter().map(|data| (data.data, data.disambiguator)).collect()
+}
+
+struct SemverChecker<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    baseline: CrateNum,
+    old_api: FxIndexMap<ApiPath, ApiItem>,
+    new_api: FxIndexMap<ApiPath, ApiItem>,
+    /// The public paths of the items of the baseline.
+    old_paths: FxHashMap<DefId, ApiPath>,
+    /// The items of the current crate corresponding to the items of the baseline.
+    def_ids: RefCell<FxHashMap<DefId, Option<DefId>>>,
+    local_def_paths: OnceCell<FxHashMap<DefPathKey, DefId>>,
+    auto_traits: Vec<DefId>,
+}
+
+impl<'tcx> SemverChecker<'tcx> {
+    /// Finds the item of the current crate that corresponds to `def_id`, either through its
+    /// public path or, for private items, through its definition path.
+    fn map_def_id(&self, def_id: DefId) -> Option<DefId> {
+        if def_id.krate != self.baseline {
+            return Some(def_id);
+        }
+        if let Some(&new) = self.def_ids.borrow().get(&def_id) {
+            return new;
+        }
+        let tcx = self.tcx;
+        let new = self
+            .old_paths
+            .get(&def_id)
+            .and_then(|path| self.new_api.get(path))
+            .map(|item| item.def_id)
+            .or_else(|| {
+                let local_def_paths = self.local_def_paths.get_or_init(|| {
+                    tcx.iter_local_def_id()
+                        .map(|def_id| (def_path_key(tcx, def_id.to_def_id()), def_id.to_def_id()))
+                        .collect()
+                });
+                local_def_paths.get(&def_path_key(tcx, def_id)).copied()
+            })
+            .filter(|&new| {
+                let kind = tcx.def_kind(def_id);
+                kind == tcx.def_kind(new)
+                    && (!has_generics(kind) || self.generics_compatible(def_id, new))
+            });
+        self.def_ids.borrow_mut().insert(def_id, new);
+        new
+    }
+
+    /// Whether arguments for the generics of `old` can be used for `new`, filling in the
+    /// defaults of the parameters that were added.
+    fn generics_compatible(&self, old: DefId, new: DefId) -> bool {
+        let (old, new) = (self.tcx.generics_of(old), self.tcx.generics_of(new));
+        old.parent_count == new.parent_count
+            && old.params.len() <= new.params.len()
+            && new.params[old.params.len()..].iter().all(|param| match param.kind {
+                GenericParamDefKind::Lifetime => false,
+                GenericParamDefKind::Type { has_default, .. }
+                | GenericParamDefKind::Const { has_default, .. } => has_default,
+            })
+    }
+
+    /// Maps arguments for the generics of an item of the baseline to arguments for the generics
+    /// of `new`, its counterpart in the current crate.
+    fn map_args(&self, new: DefId, args: GenericArgsRef<'tcx>) -> GenericArgsRef<'tcx> {
+        let tcx = self.tcx;
+        if tcx.generics_of(new).count() == args.len() {
+            return args;
+        }
+        ty::GenericArgs::for_item(tcx, new, |param, args_so_far| {
+            args.get(param.index as usize)
+                .copied()
+                .unwrap_or_else(|| param.default_value(tcx).unwrap().instantiate(tcx, args_so_far))
+        })
+    }
+
+    /// Brings types of both crates into a form in which they can be compared.
+    fn normalize<T: TypeFoldable<TyCtxt<'tcx>>>(&self, value: T) -> T {
+        value.fold_with(&mut ApiFolder { checker: self })
+    }
+
+    fn lint(&self, lint: &'static Lint, site: DefId, diag: SemverChange<'tcx>) {
+        let tcx = self.tcx;
+        let def_id = site.as_local().unwrap_or(CRATE_DEF_ID);
+        let span = if def_id == CRATE_DEF_ID {
+            tcx.def_span(def_id).shrink_to_lo()
+        } else {
+            tcx.def_span(def_id)
+        };
+        tcx.emit_node_span_lint(lint, tcx.local_def_id_to_hir_id(def_id), span, diag);
+    }
+
+    /// The site of the module containing `path`, where removed items are reported.
+    fn parent_site(&self, path: &str) -> DefId {
+        path.rsplit_once("::")
+            .and_then(|(parent, _)| self.new_api.get(&(parent.to_owned(), Namespace::TypeNS)))
+            .map_or(CRATE_DEF_ID.to_def_id(), |item| item.site)
+    }
+
+    fn check_items(&self) {
+        let tcx = self.tcx;
+        for ((path, ns), old_item) in &self.old_api {
+            match self.new_api.get(&(path.clone(), *ns)) {
+                Some(new_item) => self.check_item(path, old_item.def_id, new_item),
+                None => self.lint(
+                    SEMVER_MAJOR_CHANGES,
+                    self.parent_site(path),
+                    SemverChange::Removed {
+                        descr: tcx.def_descr(old_item.def_id),
+                        path: path.clone(),
+                    },
+                ),
+            }
+        }
+        for ((path, ns), new_item) in &self.new_api {
+            if !self.old_api.contains_key(&(path.clone(), *ns)) {
+                self.lint(
+                    SEMVER_MINOR_CHANGES,
+                    new_item.site,
+                    SemverChange::Added {
+                        descr: tcx.def_descr(new_item.def_id),
+                        path: path.clone(),
+                    },
+                );
+            }
+        }
+    }
+
+    fn check_item(&self, path: &str, old: DefId, new_item: &ApiItem) {
+        let tcx = self.tcx;
+        let (new, site) = (new_item.def_id, new_item.site);
+        if old.krate != self.baseline || !new.is_local() {
+            // Re-exports of items of other crates only need to refer to the same item.
+            if self.map_def_id(old) != Some(new) {
+                self.lint(
+                    SEMVER_MAJOR_CHANGES,
+                    site,
+                    SemverChange::ReexportChanged { path: path.to_owned() },
+                );
+            }
+            return;
+        }
+        let (old_kind, new_kind) = (tcx.def_kind(old), tcx.def_kind(new));
+        match (old_kind, new_kind) {
+            (
+                DefKind::Static { mutability: old_mutability, .. },
+                DefKind::Static { mutability: new_mutability, .. },
+            ) => {
+                if old_mutability != new_mutability {
+                    let mutable = new_mutability.is_mut();
+                    let diag = SemverChange::MutabilityChanged { path: path.to_owned(), mutable };
+                    self.lint(SEMVER_MAJOR_CHANGES, site, diag);
+                }
+                self.check_type(path, old, new, site);
+            }
+            _ if old_kind != new_kind => self.lint(
+                SEMVER_MAJOR_CHANGES,
+                site,
+                SemverChange::KindChanged {
+                    path: path.to_owned(),
+                    old_descr: tcx.def_descr(old),
+                    descr: tcx.def_descr(new),
+                },
+            ),
+            (DefKind::Fn, _) => self.check_fn(path, old, new, site),
+            (DefKind::Const, _) => self.check_type(path, old, new, site),
+            (DefKind::TyAlias, _) => {
+                if self.check_generics(path, old, new, site) {
+                    self.check_type(path, old, new, site);
+                }
+            }
+            (DefKind::Struct | DefKind::Enum | DefKind::Union, _) => {
+                self.check_adt(path, old, new, site)
+            }
+            (DefKind::Trait, _) => self.check_trait(path, old, new, site),
+            // The items of modules have their own paths, and macros only need to exist.
+            _ => {}
+        }
+    }
+
+    /// Compares the generic parameters of two items, returning whether the rest of their
+    /// definitions can be compared.
+    fn check_generics(&self, path: &str, old: DefId, new: DefId, site: DefId) -> bool {
+        let tcx = self.tcx;
+        let (old_params, new_params) = (&tcx.generics_of(old).params, &tcx.generics_of(new).params);
+        let same_kind = |old: &ty::GenericParamDef, new: &ty::GenericParamDef| {
+            std::mem::discriminant(&old.kind) == std::mem::discriminant(&new.kind)
+        };
+        let descr = tcx.def_descr(new);
+        let path = path.to_owned();
+        if old_params.len() > new_params.len()
+            || !std::iter::zip(old_params, new_params).all(|(old, new)| same_kind(old, new))
+        {
+            self.lint(SEMVER_MAJOR_CHANGES, site, SemverChange::GenericsChanged { descr, path });
+            return false;
+        }
+        if old_params.len() < new_params.len() {
+            if self.generics_compatible(old, new) {
+                let diag = SemverChange::GenericsDefaulted { descr, path };
+                self.lint(SEMVER_MINOR_CHANGES, site, diag);
+            } else {
+                let diag = SemverChange::GenericsChanged { descr, path };
+                self.lint(SEMVER_MAJOR_CHANGES, site, diag);
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Compares two sets of clauses, returning whether clauses were added and whether clauses
+    /// were removed. Only the clauses that don't follow from the other set count, so that e.g.
+    /// replacing `T: Copy` with `T: Clone + Copy` is not a change.
+    fn diff_clauses(
+        &self,
+        old: impl IntoIterator<Item = ty::Clause<'tcx>>,
+        new: impl IntoIterator<Item = ty::Clause<'tcx>>,
+    ) -> (bool, bool) {
+        let old: Vec<_> = old.into_iter().map(|clause| self.normalize(clause)).collect();
+        let new: Vec<_> = new.into_iter().map(|clause| self.normalize(clause)).collect();
+        (!self.implies(&old, &new), !self.implies(&new, &old))
+    }
+
+    /// Whether `clauses` hold wherever `assumptions` hold, as checked by the trait solver.
+    fn implies(&self, assumptions: &[ty::Clause<'tcx>], clauses: &[ty::Clause<'tcx>]) -> bool {
+        let tcx = self.tcx;
+        // Elaborating the assumptions makes the bounds implied by supertraits available.
+        let assumptions: Vec<_> = elaborate(tcx, assumptions.iter().copied()).collect();
+        let param_env = ty::ParamEnv::new(tcx.mk_clauses(&assumptions), Reveal::UserFacing);
+        let infcx = tcx.infer_ctxt().build();
+        clauses.iter().all(|&clause| match clause.kind().skip_binder() {
+            // The trait solver ignores regions, so lifetime bounds are compared as written.
+            ty::ClauseKind::RegionOutlives(..) | ty::ClauseKind::TypeOutlives(..) => {
+                assumptions.contains(&clause)
+            }
+            _ => {
+                let obligation = Obligation::new(tcx, ObligationCause::dummy(), param_env, clause);
+                infcx.predicate_must_hold_modulo_regions(&obligation)
+            }
+        })
+    }
+
+    /// Compares the where clauses of two items. Looser bounds are a minor change, unless
+    /// `loosening_is_major` is set, as it is for traits, whose implementations rely on them.
+    fn check_bounds(
+        &self,
+        descr: &'static str,
+        path: &str,
+        (old, new): (DefId, DefId),
+        site: DefId,
+        loosening_is_major: bool,
+    ) {
+        let predicates = |def_id| self.tcx.predicates_of(def_id).predicates.iter().map(|&(c, _)| c);
+        let (tightened, loosened) = self.diff_clauses(predicates(old), predicates(new));
+        if tightened {
+            let diag = SemverChange::BoundsTightened { descr, path: path.to_owned() };
+            self.lint(SEMVER_MAJOR_CHANGES, site, diag);
+        }
+        if loosened {
+            let lint = if loosening_is_major { SEMVER_MAJOR_CHANGES } else { SEMVER_MINOR_CHANGES };
+            self.lint(lint, site, SemverChange::BoundsLoosened { descr, path: path.to_owned() });
+        }
+    }
+
+    fn check_type(&self, path: &str, old: DefId, new: DefId, site: DefId) {
+        let tcx = self.tcx;
+        let old_ty = tcx.type_of(old).instantiate_identity();
+        let new_ty = tcx.type_of(new).instantiate_identity();
+        if self.normalize(old_ty) != self.normalize(new_ty) {
+            self.lint(
+                SEMVER_MAJOR_CHANGES,
+                site,
+                SemverChange::TypeChanged {
+                    descr: tcx.def_descr(new),
+                    path: path.to_owned(),
+                    old: old_ty,
+                    new: new_ty,
+                },
+            );
+        }
+    }
+
+    fn check_fn(&self, path: &str, old: DefId, new: DefId, site: DefId) {
+        let tcx = self.tcx;
+        let descr = tcx.def_descr(new);
+        let (old_const, new_const) = (tcx.is_const_fn_raw(old), tcx.is_const_fn_raw(new));
+        if old_const != new_const {
+            let lint = if new_const { SEMVER_MINOR_CHANGES } else { SEMVER_MAJOR_CHANGES };
+            let diag = SemverChange::ConstnessChanged {
+                descr,
+                path: path.to_owned(),
+                is_const: new_const,
+            };
+            self.lint(lint, site, diag);
+        }
+        if !self.check_generics(path, old, new, site) {
+            return;
+        }
+        self.check_bounds(descr, path, (old, new), site, false);
+        let old_sig = tcx.fn_sig(old).instantiate_identity();
+        let new_sig = tcx.fn_sig(new).instantiate_identity();
+        if self.normalize(old_sig) != self.normalize(new_sig) {
+            let diag = SemverChange::SignatureChanged {
+                descr,
+                path: path.to_owned(),
+                old: old_sig,
+                new: new_sig,
+            };
+            self.lint(SEMVER_MAJOR_CHANGES, site, diag);
+            return;
+        }
+        // The signatures only refer to opaque return types by name, so compare what callers can
+        // rely on: their bounds and the auto traits they leak.
+        let old_output = tcx.instantiate_bound_regions_with_erased(old_sig.output());
+        let new_output = tcx.instantiate_bound_regions_with_erased(new_sig.output());
+        if !new_output.has_opaque_types() {
+            return;
+        }
+        let opaques = |ty: Ty<'tcx>| {
+            ty.walk()
+                .filter_map(|arg| match arg.unpack() {
+                    GenericArgKind::Type(ty) => match *ty.kind() {
+                        ty::Alias(ty::Opaque, alias) => Some(alias.def_id),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let item_bounds = |def_id| {
+            tcx.explicit_item_bounds(def_id).instantiate_identity_iter_copied().map(|(c, _)| c)
+        };
+        for (old_opaque, new_opaque) in std::iter::zip(opaques(old_output), opaques(new_output)) {
+            let (added, removed) =
+                self.diff_clauses(item_bounds(old_opaque), item_bounds(new_opaque));
+            if added || removed {
+                let lint = if removed { SEMVER_MAJOR_CHANGES } else { SEMVER_MINOR_CHANGES };
+                let diag = SemverChange::SignatureChanged {
+                    descr,
+                    path: path.to_owned(),
+                    old: old_sig,
+                    new: new_sig,
+                };
+                self.lint(lint, site, diag);
+                return;
+            }
+        }
+        self.check_auto_traits(
+            (old_output, tcx.param_env(old)),
+            (new_output, tcx.param_env(new)),
+            site,
+            |auto_trait, implemented| SemverChange::ReturnAutoTraitChanged {
+                descr,
+                path: path.to_owned(),
+                auto_trait,
+                implemented,
+            },
+        );
+    }
+
+    /// Compares the auto traits implemented by two types, each in the `ParamEnv` of its crate.
+    fn check_auto_traits(
+        &self,
+        (old_ty, old_param_env): (Ty<'tcx>, ty::ParamEnv<'tcx>),
+        (new_ty, new_param_env): (Ty<'tcx>, ty::ParamEnv<'tcx>),
+        site: DefId,
+        diag: impl Fn(Symbol, bool) -> SemverChange<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        let implements = |ty, param_env, auto_trait| {
+            tcx.infer_ctxt()
+                .build()
+                .type_implements_trait(auto_trait, [ty], param_env)
+                .must_apply_modulo_regions()
+        };
+        for &auto_trait in &self.auto_traits {
+            let implemented = implements(new_ty, new_param_env, auto_trait);
+            let was_implemented = self
+                .baseline_auto_impl(old_ty, auto_trait)
+                .unwrap_or_else(|| implements(old_ty, old_param_env, auto_trait));
+            if was_implemented != implemented {
+                let lint = if implemented { SEMVER_MINOR_CHANGES } else { SEMVER_MAJOR_CHANGES };
+                self.lint(lint, site, diag(tcx.item_name(auto_trait), implemented));
+            }
+        }
+    }
+
+    /// Whether the baseline explicitly implements `auto_trait` for `ty`, or explicitly doesn't.
+    ///
+    /// The impls of the baseline are left out of `trait_impls_of`, so the trait solver never sees
+    /// them. Only the outermost type is looked up here; explicit impls for baseline types nested
+    /// in `ty` are missed.
+    fn baseline_auto_impl(&self, ty: Ty<'tcx>, auto_trait: DefId) -> Option<bool> {
+        let tcx = self.tcx;
+        let ty::Adt(adt, _) = *ty.kind() else { return None };
+        if adt.did().krate != self.baseline {
+            return None;
+        }
+        tcx.implementations_of_trait((self.baseline, auto_trait)).iter().find_map(
+            |&(impl_def_id, _)| {
+                let header = tcx.impl_trait_header(impl_def_id)?;
+                match *header.trait_ref.skip_binder().self_ty().kind() {
+                    ty::Adt(impl_adt, _) if impl_adt.did() == adt.did() => {
+                        Some(header.polarity == ty::ImplPolarity::Positive)
+                    }
+                    _ => None,
+                }
+            },
+        )
+    }
+
+    fn check_adt(&self, path: &str, old: DefId, new: DefId, site: DefId) {
+        let tcx = self.tcx;
+        let descr = tcx.def_descr(new);
+        let comparable = self.check_generics(path, old, new, site);
+        self.check_bounds(descr, path, (old, new), site, false);
+        let (old_adt, new_adt) = (tcx.adt_def(old), tcx.adt_def(new));
+        if old_adt.is_enum() {
+            let old_non_exhaustive = old_adt.is_variant_list_non_exhaustive();
+            let new_non_exhaustive = new_adt.is_variant_list_non_exhaustive();
+            if old_non_exhaustive != new_non_exhaustive {
+                let lint =
+                    if new_non_exhaustive { SEMVER_MAJOR_CHANGES } else { SEMVER_MINOR_CHANGES };
+                let diag = SemverChange::NonExhaustiveChanged {
+                    descr,
+                    path: path.to_owned(),
+                    non_exhaustive: new_non_exhaustive,
+                };
+                self.lint(lint, site, diag);
+            }
+            let is_visible = |variant: &&ty::VariantDef| !tcx.is_doc_hidden(variant.def_id);
+            for old_variant in old_adt.variants().iter().filter(is_visible) {
+                let variant_path = format!("{path}::{}", old_variant.name);
+                match new_adt.variants().iter().find(|v| v.name == old_variant.name) {
+                    Some(new_variant) => self.check_variant(
+                        &variant_path,
+                        old_variant,
+                        new_variant,
+                        new_variant.def_id,
+                        comparable,
+                    ),
+                    None => self.lint(
+                        SEMVER_MAJOR_CHANGES,
+                        site,
+                        SemverChange::Removed { descr: "variant", path: variant_path },
+                    ),
+                }
+            }
+            for new_variant in new_adt.variants().iter().filter(is_visible) {
+                if !old_adt.variants().iter().any(|v| v.name == new_variant.name) {
+                    let lint = if old_non_exhaustive {
+                        SEMVER_MINOR_CHANGES
+                    } else {
+                        SEMVER_MAJOR_CHANGES
+                    };
+                    let path = format!("{path}::{}", new_variant.name);
+                    let diag = SemverChange::Added { descr: "variant", path };
+                    self.lint(lint, new_variant.def_id, diag);
+                }
+            }
+        } else {
+            let (old_variant, new_variant) =
+                (old_adt.non_enum_variant(), new_adt.non_enum_variant());
+            self.check_variant(path, old_variant, new_variant, site, comparable);
+        }
+        self.check_auto_traits(
+            (tcx.type_of(old).instantiate_identity(), tcx.param_env(old)),
+            (tcx.type_of(new).instantiate_identity(), tcx.param_env(new)),
+            site,
+            |auto_trait, implemented| SemverChange::AutoTraitChanged {
+                descr,
+                path: path.to_owned(),
+                auto_trait,
+                implemented,
+            },
+        );
+        let inherent_items = |def_id| {
+            tcx.inherent_impls(def_id)
+                .into_iter()
+                .flatten()
+                .flat_map(|&impl_def_id| tcx.associated_items(impl_def_id).in_definition_order())
+                .filter(|item| {
+                    tcx.visibility(item.def_id).is_public() && !tcx.is_doc_hidden(item.def_id)
+                })
+                .collect::<Vec<_>>()
+        };
+        self.check_assoc_items(path, &inherent_items(old), &inherent_items(new), site, |_| false);
+    }
+
+    /// Compares the fields of a struct, union or enum variant.
+    fn check_variant(
+        &self,
+        path: &str,
+        old_variant: &ty::VariantDef,
+        new_variant: &ty::VariantDef,
+        site: DefId,
+        comparable: bool,
+    ) {
+        let tcx = self.tcx;
+        let descr = tcx.def_descr(new_variant.def_id);
+        let old_non_exhaustive = old_variant.is_field_list_non_exhaustive();
+        let new_non_exhaustive = new_variant.is_field_list_non_exhaustive();
+        if old_non_exhaustive != new_non_exhaustive {
+            let lint = if new_non_exhaustive { SEMVER_MAJOR_CHANGES } else { SEMVER_MINOR_CHANGES };
+            let diag = SemverChange::NonExhaustiveChanged {
+                descr,
+                path: path.to_owned(),
+                non_exhaustive: new_non_exhaustive,
+            };
+            self.lint(lint, site, diag);
+        }
+        if old_variant.ctor_kind() != new_variant.ctor_kind() {
+            let kind_descr = |variant: &ty::VariantDef| match variant.ctor {
+                Some((_, ctor)) => tcx.def_descr(ctor),
+                None => tcx.def_descr(variant.def_id),
+            };
+            let diag = SemverChange::KindChanged {
+                path: path.to_owned(),
+                old_descr: kind_descr(old_variant),
+                descr: kind_descr(new_variant),
+            };
+            self.lint(SEMVER_MAJOR_CHANGES, site, diag);
+        }
+        let is_public =
+            |field: &ty::FieldDef| field.vis.is_public() && !tcx.is_doc_hidden(field.did);
+        // Struct expressions and patterns outside of the crate need every field to be public.
+        let was_constructible =
+            !old_non_exhaustive && old_variant.fields.iter().all(|field| field.vis.is_public());
+        for old_field in old_variant.fields.iter().filter(|field| is_public(field)) {
+            let field_path = format!("{path}::{}", old_field.name);
+            match new_variant.fields.iter().find(|f| f.name == old_field.name && is_public(f)) {
+                Some(new_field) if comparable => {
+                    self.check_type(&field_path, old_field.did, new_field.did, new_field.did)
+                }
+                Some(_) => {}
+                None => self.lint(
+                    SEMVER_MAJOR_CHANGES,
+                    site,
+                    SemverChange::Removed { descr: "field", path: field_path },
+                ),
+            }
+        }
+        for new_field in new_variant.fields.iter().filter(|field| is_public(field)) {
+            if !old_variant.fields.iter().any(|f| f.name == new_field.name && is_public(f)) {
+                let lint =
+                    if was_constructible { SEMVER_MAJOR_CHANGES } else { SEMVER_MINOR_CHANGES };
+                let path = format!("{path}::{}", new_field.name);
+                self.lint(lint, new_field.did, SemverChange::Added { descr: "field", path });
+            }
+        }
+        if was_constructible && new_variant.fields.iter().any(|field| !field.vis.is_public()) {
+            let diag = SemverChange::PrivateFieldAdded { descr, path: path.to_owned() };
+            self.lint(SEMVER_MAJOR_CHANGES, site, diag);
+        }
+    }
+
+    /// Compares the associated items of two types or traits. Added items are major changes when
+    /// `added_is_major` says so.
+    fn check_assoc_items(
+        &self,
+        path: &str,
+        old_items: &[&ty::AssocItem],
+        new_items: &[&ty::AssocItem],
+        site: DefId,
+        added_is_major: impl Fn(&ty::AssocItem) -> bool,
+    ) {
+        let tcx = self.tcx;
+        let same_item =
+            |old: &ty::AssocItem, new: &ty::AssocItem| old.name == new.name && old.kind == new.kind;
+        for old_item in old_items.iter().filter(|item| !item.is_impl_trait_in_trait()) {
+            let item_path = format!("{path}::{}", old_item.name);
+            match new_items.iter().find(|new_item| same_item(old_item, new_item)) {
+                Some(new_item) => match new_item.kind {
+                    ty::AssocKind::Fn => {
+                        self.check_fn(&item_path, old_item.def_id, new_item.def_id, new_item.def_id)
+                    }
+                    ty::AssocKind::Const => self.check_type(
+                        &item_path,
+                        old_item.def_id,
+                        new_item.def_id,
+                        new_item.def_id,
+                    ),
+                    ty::AssocKind::Type => {}
+                },
+                None => self.lint(
+                    SEMVER_MAJOR_CHANGES,
+                    site,
+                    SemverChange::Removed {
+                        descr: tcx.def_descr(old_item.def_id),
+                        path: item_path,
+                    },
+                ),
+            }
+        }
+        for new_item in new_items.iter().filter(|item| !item.is_impl_trait_in_trait()) {
+            if !old_items.iter().any(|old_item| same_item(old_item, new_item)) {
+                let lint = if added_is_major(new_item) {
+                    SEMVER_MAJOR_CHANGES
+                } else {
+                    SEMVER_MINOR_CHANGES
+                };
+                let diag = SemverChange::Added {
+                    descr: tcx.def_descr(new_item.def_id),
+                    path: format!("{path}::{}", new_item.name),
+                };
+                self.lint(lint, new_item.def_id, diag);
+            }
+        }
+    }
+
+    fn check_trait(&self, path: &str, old: DefId, new: DefId, site: DefId) {
+        let tcx = self.tcx;
+        let (old_unsafety, new_unsafety) =
+            (tcx.trait_def(old).unsafety, tcx.trait_def(new).unsafety);
+        if old_unsafety != new_unsafety {
+            let is_unsafe = new_unsafety == hir::Unsafety::Unsafe;
+            let diag = SemverChange::UnsafetyChanged { path: path.to_owned(), is_unsafe };
+            self.lint(SEMVER_MAJOR_CHANGES, site, diag);
+        }
+        let (old_object_safe, new_object_safe) =
+            (tcx.check_is_object_safe(old), tcx.check_is_object_safe(new));
+        if old_object_safe != new_object_safe {
+            let lint = if new_object_safe { SEMVER_MINOR_CHANGES } else { SEMVER_MAJOR_CHANGES };
+            let diag = SemverChange::ObjectSafetyChanged {
+                path: path.to_owned(),
+                object_safe: new_object_safe,
+            };
+            self.lint(lint, site, diag);
+        }
+        if !self.check_generics(path, old, new, site) {
+            return;
+        }
+        self.check_bounds(tcx.def_descr(new), path, (old, new), site, true);
+        let items = |def_id| {
+            tcx.associated_items(def_id)
+                .in_definition_order()
+                .filter(|item| !tcx.is_doc_hidden(item.def_id))
+                .collect::<Vec<_>>()
+        };
+        // Implementations have to provide the new items that have no default.
+        self.check_assoc_items(path, &items(old), &items(new), site, |item| {
+            !item.defaultness(tcx).has_value()
+        });
+    }
+
+    /// The trait implementations of `krate` that only involve items that can be named from
+    /// outside of it.
+    fn visible_impls(&self, krate: CrateNum) -> Vec<(DefId, ty::TraitRef<'tcx>, ty::ImplPolarity)> {
+        let tcx = self.tcx;
+        let api = if krate == LOCAL_CRATE { &self.new_api } else { &self.old_api };
+        let public: FxHashSet<DefId> = api.values().map(|item| item.def_id).collect();
+        let is_visible = |def_id: DefId| def_id.krate != krate || public.contains(&def_id);
+        tcx.trait_impls_in_crate(krate)
+            .iter()
+            .filter_map(|&impl_def_id| {
+                let header = tcx.impl_trait_header(impl_def_id)?;
+                let trait_ref = header.trait_ref.instantiate_identity();
+                // Auto traits are compared through the types implementing them.
+                if tcx.trait_is_auto(trait_ref.def_id) || !is_visible(trait_ref.def_id) {
+                    return None;
+                }
+                let visible = trait_ref.args.iter().flat_map(|arg| arg.walk()).all(|arg| match arg
+                    .unpack()
+                {
+                    GenericArgKind::Type(ty) => match *ty.kind() {
+                        ty::Adt(adt, _) => is_visible(adt.did()),
+                        ty::Foreign(def_id) => is_visible(def_id),
+                        _ => true,
+                    },
+                    _ => true,
+                });
+                visible.then_some((impl_def_id, trait_ref, header.polarity))
+            })
+            .collect()
+    }
+
+    /// The site where changes to the implementations for `self_ty` are reported.
+    fn impl_site(&self, self_ty: Ty<'tcx>) -> DefId {
+        if let ty::Adt(adt, _) = *self_ty.kind()
+            && let Some(def_id) = self.map_def_id(adt.did())
+            && let Some(item) = self.new_api.values().find(|item| item.def_id == def_id)
+        {
+            item.site
+        } else {
+            CRATE_DEF_ID.to_def_id()
+        }
+    }
+
+    fn check_impls(&self) {
+        let old_impls = self.visible_impls(self.baseline);
+        let new_impls = self.visible_impls(LOCAL_CRATE);
+        let key = |&(_, trait_ref, polarity): &(DefId, ty::TraitRef<'tcx>, ty::ImplPolarity)| {
+            (ApiFolder { checker: self }.fold_trait_ref(self.normalize(trait_ref)), polarity)
+        };
+        let old_keys: Vec<_> = old_impls.iter().map(key).collect();
+        let new_keys: Vec<_> = new_impls.iter().map(key).collect();
+        for (old_impl, old_key) in std::iter::zip(&old_impls, &old_keys) {
+            let (old_def_id, trait_ref, _) = *old_impl;
+            match new_keys.iter().position(|new_key| new_key == old_key) {
+                Some(index) => {
+                    let new_def_id = new_impls[index].0;
+                    let path = with_no_trimmed_paths!(format!(
+                        "{} for {}",
+                        trait_ref.print_only_trait_path(),
+                        trait_ref.self_ty()
+                    ));
+                    self.check_bounds(
+                        "implementation",
+                        &path,
+                        (old_def_id, new_def_id),
+                        new_def_id,
+                        false,
+                    );
+                }
+                None => self.lint(
+                    SEMVER_MAJOR_CHANGES,
+                    self.impl_site(trait_ref.self_ty()),
+                    SemverChange::ImplRemoved {
+                        trait_path: trait_ref.print_only_trait_path(),
+                        self_ty: trait_ref.self_ty(),
+                    },
+                ),
+            }
+        }
+        for (new_impl, new_key) in std::iter::zip(&new_impls, &new_keys) {
+            if !old_keys.contains(new_key) {
+                let (def_id, trait_ref, _) = *new_impl;
+                let diag = SemverChange::ImplAdded {
+                    trait_path: trait_ref.print_only_trait_path(),
+                    self_ty: trait_ref.self_ty(),
+                };
+                self.lint(SEMVER_MINOR_CHANGES, def_id, diag);
+            }
+        }
+    }
+}
+
+/// Maps the items of the baseline in types, signatures and clauses to the items of the current
+/// crate, and erases the names of generic parameters, which are not part of the API.
+struct ApiFolder<'a, 'tcx> {
+    checker: &'a SemverChecker<'tcx>,
+}
+
+impl<'a, 'tcx> TypeFolder<TyCtxt<'tcx>> for ApiFolder<'a, 'tcx> {
+    fn interner(&self) -> TyCtxt<'tcx> {
+        self.checker.tcx
+    }
+
+    fn fold_binder<T: TypeFoldable<TyCtxt<'tcx>>>(
+        &mut self,
+        t: ty::Binder<'tcx, T>,
+    ) -> ty::Binder<'tcx, T> {
+        let t = t.super_fold_with(self);
+        self.checker.tcx.anonymize_bound_vars(t)
+    }
+
+    fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
+        let tcx = self.checker.tcx;
+        let ty = ty.super_fold_with(self);
+        match *ty.kind() {
+            ty::Param(param) => Ty::new_param(tcx, param.index, kw::Empty),
+            ty::Adt(adt, args) => match self.map_def_id(adt.did()) {
+                Some(new) => Ty::new_adt(tcx, tcx.adt_def(new), self.checker.map_args(new, args)),
+                None => ty,
+            },
+            ty::Foreign(def_id) => match self.map_def_id(def_id) {
+                Some(new) => Ty::new_foreign(tcx, new),
+                None => ty,
+            },
+            ty::FnDef(def_id, args) => match self.map_def_id(def_id) {
+                Some(new) => Ty::new_fn_def(tcx, new, self.checker.map_args(new, args)),
+                None => ty,
+            },
+            ty::Alias(kind, alias) => match self.map_def_id(alias.def_id) {
+                Some(new) => {
+                    let args = self.checker.map_args(new, alias.args);
+                    Ty::new_alias(tcx, kind, ty::AliasTy::new(tcx, new, args))
+                }
+                None => ty,
+            },
+            ty::Dynamic(predicates, region, kind) => {
+                let mut predicates: Vec<_> = predicates
+                    .iter()
+                    .map(|predicate| {
+                        predicate.map_bound(|predicate| self.fold_existential(predicate))
+                    })
+                    .collect();
+                predicates.sort_by(|a, b| a.skip_binder().stable_cmp(tcx, &b.skip_binder()));
+                Ty::new_dynamic(tcx, tcx.mk_poly_existential_predicates(&predicates), region, kind)
+            }
+            _ => ty,
+        }
+    }
+
+    fn fold_region(&mut self, r: ty::Region<'tcx>) -> ty::Region<'tcx> {
+        match *r {
+            ty::ReEarlyParam(param) => ty::Region::new_early_param(
+                self.checker.tcx,
+                ty::EarlyParamRegion {
+                    def_id: CRATE_DEF_ID.to_def_id(),
+                    index: param.index,
+                    name: kw::Empty,
+                },
+            ),
+            _ => r,
+        }
+    }
+
+    fn fold_const(&mut self, c: ty::Const<'tcx>) -> ty::Const<'tcx> {
+        let tcx = self.checker.tcx;
+        let c = c.normalize(tcx, ty::ParamEnv::reveal_all()).super_fold_with(self);
+        match c.kind() {
+            ty::ConstKind::Param(param) => {
+                ty::Const::new_param(tcx, ty::ParamConst::new(param.index, kw::Empty), c.ty())
+            }
+            ty::ConstKind::Unevaluated(uv) => match self.map_def_id(uv.def) {
+                Some(def) => {
+                    let args = self.checker.map_args(def, uv.args);
+                    ty::Const::new_unevaluated(tcx, ty::UnevaluatedConst::new(def, args), c.ty())
+                }
+                None => c,
+            },
+            _ => c,
+        }
+    }
+
+    fn fold_predicate(&mut self, p: ty::Predicate<'tcx>) -> ty::Predicate<'tcx> {
+        let tcx = self.checker.tcx;
+        let p = p.super_fold_with(self);
+        let kind = p.kind().map_bound(|kind| match kind {
+            ty::PredicateKind::Clause(ty::ClauseKind::Trait(pred)) => {
+                let trait_ref = self.fold_trait_ref(pred.trait_ref);
+                ty::PredicateKind::Clause(ty::ClauseKind::Trait(ty::TraitPredicate {
+                    trait_ref,
+                    ..pred
+                }))
+            }
+            ty::PredicateKind::Clause(ty::ClauseKind::Projection(pred)) => {
+                let projection_ty = match self.map_def_id(pred.projection_ty.def_id) {
+                    Some(new) => {
+                        let args = self.checker.map_args(new, pred.projection_ty.args);
+                        ty::AliasTy::new(tcx, new, args)
+                    }
+                    None => pred.projection_ty,
+                };
+                ty::PredicateKind::Clause(ty::ClauseKind::Projection(ty::ProjectionPredicate {
+                    projection_ty,
+                    ..pred
+                }))
+            }
+            kind => kind,
+        });
+        tcx.mk_predicate(kind)
+    }
+}
+
+impl<'a, 'tcx> ApiFolder<'a, 'tcx> {
+    /// The item of the current crate that replaces `def_id`, if it is an item of the baseline.
+    fn map_def_id(&self, def_id: DefId) -> Option<DefId> {
+        self.checker.map_def_id(def_id).filter(|&new| new != def_id)
+    }
+
+    fn fold_trait_ref(&mut self, trait_ref: ty::TraitRef<'tcx>) -> ty::TraitRef<'tcx> {
+        match self.map_def_id(trait_ref.def_id) {
+            Some(new) => {
+                let args = self.checker.map_args(new, trait_ref.args);
+                ty::TraitRef::new(self.checker.tcx, new, args)
+            }
+            None => trait_ref,
+        }
+    }
+
+    fn fold_existential(
+        &mut self,
+        predicate: ty::ExistentialPredicate<'tcx>,
+    ) -> ty::ExistentialPredicate<'tcx> {
+        let tcx = self.checker.tcx;
+        // Existential predicates leave out the `Self` type, so only map items whose generics
+        // did not change.
+        let map = |def_id: DefId| {
+            self.map_def_id(def_id)
+                .filter(|&new| tcx.generics_of(new).count() == tcx.generics_of(def_id).count())
+                .unwrap_or(def_id)
+        };
+        match predicate {
+            ty::ExistentialPredicate::Trait(trait_ref) => {
+                ty::ExistentialPredicate::Trait(ty::ExistentialTraitRef {
+                    def_id: map(trait_ref.def_id),
+                    ..trait_ref
+                })
+            }
+            ty::ExistentialPredicate::Projection(projection) => {
+                ty::ExistentialPredicate::Projection(ty::ExistentialProjection {
+                    def_id: map(projection.def_id),
+                    ..projection
+                })
+            }
+            ty::ExistentialPredicate::AutoTrait(def_id) => {
+                ty::ExistentialPredicate::AutoTrait(map(def_id))
+            }
+        }
+    }
+}
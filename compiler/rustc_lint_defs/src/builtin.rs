@@ -57,6 +57,7 @@
         INVALID_TYPE_PARAM_DEFAULT,
         IRREFUTABLE_LET_PATTERNS,
         LARGE_ASSIGNMENTS,
+        LARGE_FUTURES,
         LATE_BOUND_LIFETIME_ARGUMENTS,
         LEGACY_DERIVE_HELPERS,
         LONG_RUNNING_CONST_EVAL,
@@ -114,6 +115,7 @@
         UNSTABLE_NAME_COLLISIONS,
         UNSTABLE_SYNTAX_PRE_EXPANSION,
         UNSUPPORTED_CALLING_CONVENTIONS,
+        UNUSED_ALLOW,
         UNUSED_ASSIGNMENTS,
         UNUSED_ASSOCIATED_TYPE_BOUNDS,
         UNUSED_ATTRIBUTES,
@@ -639,6 +641,44 @@
     @feature_gate = rustc_span::sym::lint_reasons;
 }
 
+declare_lint! {
+    /// The `unused_allow` lint detects `#[allow]` attributes that never
+    /// suppressed a lint.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// #![warn(unused_allow)]
+    ///
+    /// #[allow(unused_variables)]
+    /// fn main() {
+    ///     let x = 10;
+    ///     println!("{x}");
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// An `#[allow]` attribute that no longer suppresses anything, e.g. because
+    /// the code it was written for has changed, hides the lint for any code that
+    /// is added later. Each lint listed in an attribute is checked on its own, and
+    /// the attribute, or the lint in it, should be removed.
+    ///
+    /// This lint is "allow" by default because tracking the allows has a cost,
+    /// and because some lints skip their check entirely when they are allowed, in
+    /// which case their allows are reported even though removing them would make
+    /// the lint fire. The suggestions to remove the attributes are therefore not
+    /// machine-applicable. It can be enabled for the whole crate, or for a module
+    /// or an item, and it only checks the attributes that come after it.
+    /// The allows of tool lints, e.g. `clippy::...`, are only checked when the
+    /// tool runs.
+    pub UNUSED_ALLOW,
+    Allow,
+    "`#[allow]` attributes that never suppressed a lint"
+}
+
 declare_lint! {
     /// The `unused_variables` lint detects variables which are not used in
     /// any way.
@@ -3209,6 +3249,55 @@
     "detects large moves or copies",
 }
 
+declare_lint! {
+    /// The `large_futures` lint detects futures whose state machine is larger
+    /// than the limit set with `-Z future-size-limit`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs -Zfuture-size-limit)
+    /// async fn wait() {}
+    ///
+    /// async fn big() {
+    ///     let buf = [0u8; 16384];
+    ///     wait().await;
+    ///     drop(buf);
+    /// }
+    /// ```
+    ///
+    /// produces:
+    ///
+    /// ```text
+    /// warning: this future is 16386 bytes, exceeding the limit of 4096 bytes
+    ///   --> $DIR/large-future.rs:3:1
+    ///    |
+    ///  3 | async fn big() {
+    ///    | ^^^^^^^^^^^^^^
+    ///    |
+    /// note: `buf` (16384 bytes) is held across this await point
+    ///   --> $DIR/large-future.rs:5:12
+    ///    |
+    ///  5 |     wait().await;
+    ///    |            ^^^^^
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// Every local that is still in use after an `.await` has to be stored in
+    /// the future itself, so large locals, or large futures that are awaited,
+    /// directly increase the size of the enclosing future. Large futures are
+    /// expensive to move around and can overflow the stack when they are
+    /// created. This lint points at the locals that contribute the most bytes,
+    /// along with an await point they are held across, so they can be boxed or
+    /// dropped earlier.
+    ///
+    /// The lint does nothing unless a limit is set, and it is only checked for
+    /// futures that are actually instantiated.
+    pub LARGE_FUTURES,
+    Warn,
+    "detects futures whose size exceeds `-Z future-size-limit`",
+}
+
 declare_lint! {
     /// The `deprecated_cfg_attr_crate_type_name` lint detects uses of the
     /// `#![cfg_attr(..., crate_type = "...")]` and
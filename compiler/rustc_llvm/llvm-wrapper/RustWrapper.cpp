@@ -20,6 +20,8 @@
 #include "llvm/Object/COFFImportFile.h"
 #include "llvm/Object/ObjectFile.h"
 #include "llvm/Pass.h"
+#include "llvm/ProfileData/InstrProfReader.h"
+#include "llvm/ProfileData/InstrProfWriter.h"
 #include "llvm/Bitcode/BitcodeWriter.h"
 #include "llvm/Support/Signals.h"
 
@@ -1327,6 +1329,22 @@ extern "C" void LLVMRustWriteDiagnosticInfoToString(LLVMDiagnosticInfoRef DI,
   unwrap(DI)->print(DP);
 }
 
+// Returns whether `DI` is the warning that the PGO passes emit for a function
+// whose profile no longer matches its code. The passes start that message with
+// the text of the `InstrProfError` they got, so compare against LLVM's own
+// error messages rather than hardcoding their wording.
+extern "C" bool LLVMRustIsStalePGOProfileDiagnostic(LLVMDiagnosticInfoRef DI) {
+  auto *PGO = dyn_cast<DiagnosticInfoPGOProfile>(unwrap(DI));
+  if (!PGO)
+    return false;
+  std::string Msg = PGO->getMsg().str();
+  for (auto Err : {instrprof_error::hash_mismatch, instrprof_error::malformed}) {
+    if (StringRef(Msg).starts_with(InstrProfError(Err).message()))
+      return true;
+  }
+  return false;
+}
+
 enum class LLVMRustDiagnosticKind {
   Other,
   InlineAsm,
@@ -1923,6 +1941,66 @@ extern "C" LLVMRustResult LLVMRustWriteImportLibrary(
   }
 }
 
+// Merges raw `.profraw` profiles into a single indexed profile, like
+// `llvm-profdata merge` does, so that `-C profile-use` can consume raw profiles
+// directly.
+extern "C" LLVMRustResult LLVMRustMergeRawProfiles(
+  const char **Inputs,
+  size_t NumInputs,
+  const char *Output)
+{
+  InstrProfWriter Writer;
+  auto FS = vfs::getRealFileSystem();
+  std::string ErrorString;
+
+  for (size_t i = 0; i < NumInputs; ++i) {
+    auto ReaderOrErr = InstrProfReader::create(Inputs[i], *FS);
+    if (Error E = ReaderOrErr.takeError()) {
+      ErrorString = std::string(Inputs[i]) + ": " + toString(std::move(E));
+      LLVMRustSetLastError(ErrorString.c_str());
+      return LLVMRustResult::Failure;
+    }
+    auto Reader = std::move(ReaderOrErr.get());
+
+    // All profiles have to come from the same kind of instrumentation.
+    if (Error E = Writer.mergeProfileKind(Reader->getProfileKind())) {
+      ErrorString = std::string(Inputs[i]) + ": " + toString(std::move(E));
+      LLVMRustSetLastError(ErrorString.c_str());
+      return LLVMRustResult::Failure;
+    }
+
+    for (auto &Record : *Reader) {
+      Writer.addRecord(std::move(Record), 1, [&](Error E) {
+        if (ErrorString.empty())
+          ErrorString = std::string(Inputs[i]) + ": " + toString(std::move(E));
+        else
+          consumeError(std::move(E));
+      });
+    }
+    if (Reader->hasError()) {
+      ErrorString = std::string(Inputs[i]) + ": " + toString(Reader->getError());
+    }
+    if (!ErrorString.empty()) {
+      LLVMRustSetLastError(ErrorString.c_str());
+      return LLVMRustResult::Failure;
+    }
+  }
+
+  std::error_code EC;
+  raw_fd_ostream OS(Output, EC, sys::fs::OF_None);
+  if (EC) {
+    ErrorString = std::string(Output) + ": " + EC.message();
+    LLVMRustSetLastError(ErrorString.c_str());
+    return LLVMRustResult::Failure;
+  }
+  if (Error E = Writer.write(OS)) {
+    ErrorString = std::string(Output) + ": " + toString(std::move(E));
+    LLVMRustSetLastError(ErrorString.c_str());
+    return LLVMRustResult::Failure;
+  }
+  return LLVMRustResult::Success;
+}
+
 // Transfers ownership of DiagnosticHandler unique_ptr to the caller.
 extern "C" DiagnosticHandler *
 LLVMRustContextGetDiagnosticHandler(LLVMContextRef C) {
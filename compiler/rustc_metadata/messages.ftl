@@ -125,6 +125,10 @@ metadata_invalid_link_modifier =
 metadata_invalid_meta_files =
     found invalid metadata files for crate `{$crate_name}`{$add_info}
 
+metadata_invalid_semver_baseline =
+    `{$path}` is not a previous version of crate `{$crate_name}`
+    .note = the baseline of the semver check must be an rlib or an rmeta file of the same crate
+
 metadata_lib_filename_form =
     file name should be lib*.rlib or {$dll_prefix}*{$dll_suffix}
 
@@ -67,6 +67,11 @@ pub struct CStore {
 
     /// Unused externs of the crate
     unused_externs: Vec<Symbol>,
+
+    /// The previous version of this crate that its public API is compared against, loaded from
+    /// `--check-semver-against`. It is loaded after all the other crates, so it and the crates
+    /// only loaded for it have the last crate numbers.
+    semver_baseline: Option<CrateNum>,
 }
 
 impl std::fmt::Debug for CStore {
@@ -206,9 +211,14 @@ fn set_crate_data(&mut self, cnum: CrateNum, data: CrateMetadata) {
         self.metas[cnum] = Some(Box::new(data));
     }
 
+    /// Iterates over the loaded crates, except for the baseline of the semver check and the
+    /// crates only loaded for it, which only the semver check looks at. Otherwise their items and
+    /// impls would show up in `tcx.crates(())`, e.g. in `trait_impls_of` or in the suggestions.
     pub(crate) fn iter_crate_data(&self) -> impl Iterator<Item = (CrateNum, &CrateMetadata)> {
+        let semver_baseline = self.semver_baseline;
         self.metas
             .iter_enumerated()
+            .take_while(move |&(cnum, _)| Some(cnum) != semver_baseline)
             .filter_map(|(cnum, data)| data.as_deref().map(|data| (cnum, data)))
     }
 
@@ -269,6 +279,10 @@ pub(crate) fn has_alloc_error_handler(&self) -> bool {
         self.has_alloc_error_handler
     }
 
+    pub(crate) fn semver_baseline(&self) -> Option<CrateNum> {
+        self.semver_baseline
+    }
+
     pub fn report_unused_deps(&self, tcx: TyCtxt<'_>) {
         let json_unused_externs = tcx.sess.opts.json_unused_externs;
 
@@ -309,6 +323,7 @@ pub fn new(
             has_alloc_error_handler: false,
             stable_crate_ids,
             unused_externs: Vec::new(),
+            semver_baseline: None,
         }
     }
 }
@@ -915,6 +930,33 @@ fn inject_forced_externs(&mut self) {
         }
     }
 
+    fn load_semver_baseline(&mut self) {
+        let sess = self.tcx.sess;
+        let Some(path) = &sess.opts.check_semver_against else {
+            return;
+        };
+        let name = self.tcx.crate_name(LOCAL_CRATE);
+        let locator = CrateLocator::new(
+            sess,
+            &*self.cstore.metadata_loader,
+            name,
+            true, // only the metadata is needed
+            None,
+            None,
+            false, // is_host
+            PathKind::ExternFlag,
+        );
+        // The baseline is only used to compare the public APIs, so it is never linked, and
+        // neither are the crates that are loaded only for it.
+        let result = locator.load_semver_baseline(path).and_then(|library| {
+            self.register_crate(None, None, library, CrateDepKind::MacrosOnly, name, None)
+        });
+        match result {
+            Ok(cnum) => self.cstore.semver_baseline = Some(cnum),
+            Err(err) => err.report(sess, DUMMY_SP, false),
+        }
+    }
+
     fn inject_dependency_if(
         &mut self,
         krate: CrateNum,
@@ -1035,6 +1077,7 @@ pub fn postprocess(&mut self, krate: &ast::Crate) {
         self.inject_profiler_runtime(krate);
         self.inject_allocator_crate(krate);
         self.inject_panic_runtime(krate);
+        self.load_semver_baseline();
 
         self.report_unused_deps(krate);
         self.report_future_incompatible_deps(krate);
@@ -496,6 +496,16 @@ pub struct ExternLocationNotFile<'a> {
     pub location: &'a Path,
 }
 
+#[derive(Diagnostic)]
+#[diag(metadata_invalid_semver_baseline)]
+#[note]
+pub struct InvalidSemverBaseline<'a> {
+    #[primary_span]
+    pub span: Span,
+    pub crate_name: Symbol,
+    pub path: &'a Path,
+}
+
 pub(crate) struct MultipleCandidates {
     pub span: Span,
     pub flavor: CrateFlavor,
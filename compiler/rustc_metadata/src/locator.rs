@@ -759,6 +759,34 @@ fn find_commandline_library(&mut self) -> Result<Option<Library>, CrateError> {
         Ok(self.extract_lib(rlibs, rmetas, dylibs)?.map(|(_, lib)| lib))
     }
 
+    /// Loads the previous version of the current crate that `--check-semver-against` compares the
+    /// public API against. Unlike the `--extern` paths, the file doesn't have to be named like a
+    /// library, only to be an rlib or an rmeta file.
+    pub(crate) fn load_semver_baseline(mut self, path: &Path) -> Result<Library, CrateError> {
+        let mut rlibs = FxHashMap::default();
+        let mut rmetas = FxHashMap::default();
+        let canonicalized = try_canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("rlib") if path.is_file() => rlibs.insert(canonicalized, PathKind::ExternFlag),
+            Some("rmeta") if path.is_file() => rmetas.insert(canonicalized, PathKind::ExternFlag),
+            _ => return Err(CrateError::InvalidSemverBaseline(self.crate_name, path.to_owned())),
+        };
+
+        if let Some((_, library)) = self.extract_lib(rlibs, rmetas, FxHashMap::default())? {
+            return Ok(library);
+        }
+        let rejections = &self.crate_rejections;
+        if rejections.via_triple.is_empty()
+            && rejections.via_version.is_empty()
+            && rejections.via_invalid.is_empty()
+        {
+            // The file is a library for the right target, but of another crate.
+            Err(CrateError::InvalidSemverBaseline(self.crate_name, path.to_owned()))
+        } else {
+            Err(self.into_error(None))
+        }
+    }
+
     pub(crate) fn into_error(self, root: Option<CratePaths>) -> CrateError {
         CrateError::LocatorCombined(Box::new(CombinedLocatorError {
             crate_name: self.crate_name,
@@ -938,6 +966,7 @@ pub(crate) enum CrateError {
     DlSym(String, String),
     LocatorCombined(Box<CombinedLocatorError>),
     NotFound(Symbol),
+    InvalidSemverBaseline(Symbol, PathBuf),
 }
 
 enum MetadataError<'a> {
@@ -1142,6 +1171,9 @@ pub(crate) fn report(self, sess: &Session, span: Span, missing_core: bool) {
                     dcx.emit_err(error);
                 }
             }
+            CrateError::InvalidSemverBaseline(crate_name, path) => {
+                dcx.emit_err(errors::InvalidSemverBaseline { span, crate_name, path: &path });
+            }
         }
     }
 }
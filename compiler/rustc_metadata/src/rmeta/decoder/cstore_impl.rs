@@ -519,6 +519,7 @@ pub(in crate::rmeta) fn provide(providers: &mut Providers) {
                     .filter_map(|(cnum, data)| data.used().then_some(cnum)),
             )
         },
+        semver_baseline: |tcx, ()| CStore::from_tcx(tcx).semver_baseline(),
         ..providers.queries
     };
     provide_extern(&mut providers.extern_queries);
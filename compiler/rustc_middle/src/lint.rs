@@ -6,7 +6,7 @@
 use rustc_hir::{HirId, ItemLocalId};
 use rustc_session::lint::{
     builtin::{self, FORBIDDEN_LINT_GROUPS},
-    FutureIncompatibilityReason, Level, Lint, LintId,
+    FutureIncompatibilityReason, Level, Lint, LintExpectationId, LintId,
 };
 use rustc_session::Session;
 use rustc_span::hygiene::{ExpnKind, MacroKind};
@@ -26,12 +26,20 @@ pub enum LintLevelSource {
         span: Span,
         /// RFC 2383 reason
         reason: Option<Symbol>,
+        /// For an `allow` attribute whose use is tracked, the id that is marked as fulfilled
+        /// when the attribute suppresses a lint, see [`LintExpectation::is_allow`].
+        allow_id: Option<LintExpectationId>,
     },
 
     /// Lint level was set by a command-line flag.
     /// The provided `Level` is the level specified on the command line.
     /// (The actual level may be lower due to `--cap-lints`.)
     CommandLine(Symbol, Level),
+
+    /// Lint level was set by the lint configuration file given with `--lint-config`.
+    /// The `Symbol` is the name used in the file and `Level` the level it specifies. `pattern` is
+    /// the pattern of the override that set the level, or `None` for the `[lints]` table.
+    ConfigFile { name: Symbol, level: Level, pattern: Option<Symbol> },
 }
 
 impl LintLevelSource {
@@ -40,6 +48,7 @@ pub fn name(&self) -> Symbol {
             LintLevelSource::Default => symbol::kw::Default,
             LintLevelSource::Node { name, .. } => name,
             LintLevelSource::CommandLine(name, _) => name,
+            LintLevelSource::ConfigFile { name, .. } => name,
         }
     }
 
@@ -48,6 +57,7 @@ pub fn span(&self) -> Span {
             LintLevelSource::Default => DUMMY_SP,
             LintLevelSource::Node { span, .. } => span,
             LintLevelSource::CommandLine(_, _) => DUMMY_SP,
+            LintLevelSource::ConfigFile { .. } => DUMMY_SP,
         }
     }
 }
@@ -187,6 +197,15 @@ pub struct LintExpectation {
     /// the lint `clippy::some_lint` the tool would be `clippy`, the same
     /// goes for `rustdoc`. This will be `None` for rustc lints
     pub lint_tool: Option<Symbol>,
+    /// The [`Span`] of the whole attribute, used to suggest its removal.
+    pub attr_span: Span,
+    /// The number of lints listed in the attribute. The removal of a single
+    /// lint is only suggested if each of them has an expectation.
+    pub attr_lint_count: u16,
+    /// Whether this tracks an `#[allow]` attribute rather than an expectation.
+    /// Allows are only tracked with `-Zreport-unused-allows`, and they are
+    /// fulfilled as soon as they suppress a lint.
+    pub is_allow: bool,
 }
 
 impl LintExpectation {
@@ -195,8 +214,18 @@ pub fn new(
         emission_span: Span,
         is_unfulfilled_lint_expectations: bool,
         lint_tool: Option<Symbol>,
+        attr_span: Span,
+        attr_lint_count: u16,
     ) -> Self {
-        Self { reason, emission_span, is_unfulfilled_lint_expectations, lint_tool }
+        Self {
+            reason,
+            emission_span,
+            is_unfulfilled_lint_expectations,
+            lint_tool,
+            attr_span,
+            attr_lint_count,
+            is_allow: false,
+        }
     }
 }
 
@@ -233,6 +262,21 @@ pub fn explain_lint_level_source(
                 ));
             }
         }
+        LintLevelSource::ConfigFile { name: config_name, level: orig_level, pattern } => {
+            let orig_level = orig_level.as_str();
+            match pattern {
+                Some(pattern) => err.note_once(format!(
+                    "`{config_name} = \"{orig_level}\"` set in the lint configuration file, \
+                    for the modules matching `{pattern}`"
+                )),
+                None => err.note_once(format!(
+                    "`{config_name} = \"{orig_level}\"` set in the lint configuration file"
+                )),
+            };
+            if config_name.as_str() != name {
+                err.note_once(format!("`{name}` is implied by `{config_name}`"));
+            }
+        }
         LintLevelSource::Node { name: lint_attr_name, span, reason, .. } => {
             if let Some(rationale) = reason {
                 err.note(rationale.to_string());
@@ -300,6 +344,18 @@ fn lint_level_impl(
         // Convert lint level to error level.
         let err_level = match level {
             Level::Allow => {
+                // A tracked `#[allow]` is used if it suppresses a lint that would otherwise be
+                // emitted. Like for expectations, this goes through the `DiagCtxt` so that it is
+                // replayed for cached queries.
+                // Lints in external macros are not emitted anyway, see below.
+                if let LintLevelSource::Node { allow_id: Some(allow_id), .. } = src
+                    && (lint.report_in_external_macro
+                        || !span.as_ref().is_some_and(|span| {
+                            span.primary_spans().iter().any(|s| in_external_macro(sess, *s))
+                        }))
+                {
+                    Diag::<()>::new(sess.dcx(), rustc_errors::Level::Expect(allow_id), "").emit();
+                }
                 if has_future_breakage {
                     rustc_errors::Level::Allow
                 } else {
@@ -11,6 +11,7 @@
 use crate::error::LimitInvalid;
 use crate::query::Providers;
 use rustc_ast::Attribute;
+use rustc_session::config::lint_config::LintParam;
 use rustc_session::Session;
 use rustc_session::{Limit, Limits};
 use rustc_span::symbol::{sym, Symbol};
@@ -24,7 +25,12 @@ pub fn provide(providers: &mut Providers) {
             tcx.hir().krate_attrs(),
             tcx.sess,
             sym::move_size_limit,
-            tcx.sess.opts.unstable_opts.move_size_limit.unwrap_or(0),
+            tcx.sess
+                .opts
+                .unstable_opts
+                .move_size_limit
+                .or_else(|| lint_config_move_size_limit(tcx.sess))
+                .unwrap_or(0),
         ),
         type_length_limit: get_limit(
             tcx.hir().krate_attrs(),
@@ -35,6 +41,13 @@ pub fn provide(providers: &mut Providers) {
     }
 }
 
+/// The `limit` parameter of `large_assignments` in the lint configuration file.
+fn lint_config_move_size_limit(sess: &Session) -> Option<usize> {
+    match sess.opts.lint_config.as_ref()?.param("large_assignments", "limit")? {
+        &LintParam::Integer(limit) => usize::try_from(limit).ok(),
+    }
+}
+
 pub fn get_recursion_limit(krate_attrs: &[Attribute], sess: &Session) -> Limit {
     get_limit(krate_attrs, sess, sym::recursion_limit, 128)
 }
@@ -248,6 +248,13 @@ pub struct CodegenUnit<'tcx> {
     /// True if this is CGU is used to hold code coverage information for dead code,
     /// false otherwise.
     is_code_coverage_dead_code_cgu: bool,
+    /// The optimization level of this CGU, if it holds functions with an
+    /// `#[optimize(level = "...")]` that differs from the crate's level.
+    opt_level: Option<OptLevel>,
+    /// Function items of this CGU that do not get a body of their own, but are
+    /// emitted as an alias of another function item of this CGU. See
+    /// `-Zdedup-instances`.
+    aliases: FxIndexMap<Instance<'tcx>, Instance<'tcx>>,
 }
 
 /// Auxiliary info about a `MonoItem`.
@@ -298,6 +305,8 @@ pub fn new(name: Symbol) -> CodegenUnit<'tcx> {
             size_estimate: 0,
             primary: false,
             is_code_coverage_dead_code_cgu: false,
+            opt_level: None,
+            aliases: Default::default(),
         }
     }
 
@@ -334,6 +343,27 @@ pub fn make_code_coverage_dead_code_cgu(&mut self) {
         self.is_code_coverage_dead_code_cgu = true;
     }
 
+    /// Returns the level this CGU is optimized at instead of the crate's level, if any.
+    pub fn opt_level(&self) -> Option<OptLevel> {
+        self.opt_level
+    }
+
+    pub fn set_opt_level(&mut self, opt_level: Option<OptLevel>) {
+        self.opt_level = opt_level;
+    }
+
+    /// Returns the function item whose body `instance` is an alias of, if any.
+    pub fn alias_target(&self, instance: Instance<'tcx>) -> Option<Instance<'tcx>> {
+        self.aliases.get(&instance).copied()
+    }
+
+    /// Marks `alias` as sharing the body of `target`. Both have to be items of this CGU.
+    pub fn add_alias(&mut self, alias: Instance<'tcx>, target: Instance<'tcx>) {
+        debug_assert!(self.items.contains_key(&MonoItem::Fn(alias)));
+        debug_assert!(self.items.contains_key(&MonoItem::Fn(target)));
+        self.aliases.insert(alias, target);
+    }
+
     pub fn mangle_name(human_readable_name: &str) -> String {
         // We generate a 80 bit hash from the name. This should be enough to
         // avoid collisions and is still reasonably short for filenames.
@@ -435,10 +465,25 @@ fn hash_stable(&self, hcx: &mut StableHashingContext<'a>, hasher: &mut StableHas
             size_estimate: _,
             primary: _,
             is_code_coverage_dead_code_cgu,
+            opt_level,
+            ref aliases,
         } = *self;
 
         name.hash_stable(hcx, hasher);
         is_code_coverage_dead_code_cgu.hash_stable(hcx, hasher);
+        opt_level.hash_stable(hcx, hasher);
+
+        let mut aliases: Vec<Fingerprint> = aliases
+            .iter()
+            .map(|(alias, target)| {
+                let mut hasher = StableHasher::new();
+                alias.hash_stable(hcx, &mut hasher);
+                target.hash_stable(hcx, &mut hasher);
+                hasher.finish()
+            })
+            .collect();
+        aliases.sort_unstable();
+        aliases.hash_stable(hcx, hasher);
 
         let mut items: Vec<(Fingerprint, _)> = items
             .iter()
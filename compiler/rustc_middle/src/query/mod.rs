@@ -1615,6 +1615,12 @@
         desc { "checking `extern fn` declarations are compatible" }
     }
 
+    /// Lint against the changes of the public API compared to the `--check-semver-against`
+    /// baseline.
+    query check_semver(_: ()) {
+        desc { "checking the public API against the semver baseline" }
+    }
+
     /// Identifies the entry-point (e.g., the `main` function) for a given
     /// crate, returning `None` if there is no entry point (such as for library crates).
     query entry_fn(_: ()) -> Option<(DefId, EntryFnType)> {
@@ -1884,6 +1890,12 @@
         eval_always
         desc { "fetching `CrateNum`s for all crates loaded non-speculatively" }
     }
+    /// The previous version of the local crate that `--check-semver-against` compares the public
+    /// API against. It is loaded along the other crates, but is never linked.
+    query semver_baseline(_: ()) -> Option<CrateNum> {
+        eval_always
+        desc { "fetching the baseline crate of the semver check" }
+    }
 
     /// A list of all traits in a crate, used by rustdoc and error reporting.
     query traits(_: CrateNum) -> &'tcx [DefId] {
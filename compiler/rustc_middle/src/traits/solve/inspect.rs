@@ -24,9 +24,11 @@
 };
 use crate::{infer::canonical::CanonicalVarValues, ty};
 use format::ProofTreeFormatter;
+use html::HtmlProofTreeFormatter;
 use std::fmt::{Debug, Write};
 
 mod format;
+mod html;
 
 /// Some `data` together with information about how they relate to the input
 /// of the canonical query.
@@ -80,6 +82,16 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     }
 }
 
+impl GoalEvaluation<'_> {
+    /// Renders this proof tree as a standalone HTML document, in which nested goals,
+    /// candidates and probes are collapsed and can be searched by their text.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        HtmlProofTreeFormatter::new(&mut html).format_document(self).unwrap();
+        html
+    }
+}
+
 #[derive(Eq, PartialEq)]
 pub struct AddedGoalsEvaluation<'tcx> {
     pub evaluations: Vec<Vec<GoalEvaluation<'tcx>>>,
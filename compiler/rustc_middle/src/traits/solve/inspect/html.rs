@@ -0,0 +1,221 @@
+use super::*;
+
+/// Renders proof trees as HTML, with every goal evaluation, candidate and probe in a collapsed
+/// `<details>` element.
+pub(super) struct HtmlProofTreeFormatter<'a, 'b> {
+    f: &'a mut (dyn Write + 'b),
+}
+
+const STYLE: &str = "\
+body { font-family: monospace; margin: 1em; }
+#search { width: 40em; margin-bottom: 1em; }
+details { margin-left: 1.5em; }
+summary { cursor: pointer; white-space: pre-wrap; }
+.leaf { margin-left: 2.5em; white-space: pre-wrap; }
+.yes { color: #2a7a2a; }
+.maybe { color: #a66c00; }
+.no { color: #b22222; }
+.match { background: #fff3a0; }
+";
+
+/// Opens the ancestors of the summaries and leaves containing the search text, and highlights
+/// them.
+const SCRIPT: &str = "\
+const search = document.getElementById('search');
+search.addEventListener('input', () => {
+    const text = search.value.toLowerCase();
+    for (const node of document.querySelectorAll('summary, .leaf')) {
+        const found = text !== '' && node.textContent.toLowerCase().includes(text);
+        node.classList.toggle('match', found);
+        let details = found ? node.parentElement.closest('details') : null;
+        for (; details; details = details.parentElement.closest('details')) {
+            if (details.firstElementChild !== node) details.open = true;
+        }
+    }
+});
+";
+
+/// Escapes the `Debug` output of `value` for use in HTML text.
+struct Escaped<T>(T);
+
+impl<T: Debug> std::fmt::Display for Escaped<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in format!("{:?}", self.0).chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn certainty_class(result: Result<Certainty, NoSolution>) -> &'static str {
+    match result {
+        Ok(Certainty::Yes) => "yes",
+        Ok(Certainty::Maybe(_)) => "maybe",
+        Err(NoSolution) => "no",
+    }
+}
+
+fn result_class(result: &QueryResult<'_>) -> &'static str {
+    match result {
+        Ok(response) => certainty_class(Ok(response.value.certainty)),
+        Err(NoSolution) => certainty_class(Err(NoSolution)),
+    }
+}
+
+impl<'a, 'b> HtmlProofTreeFormatter<'a, 'b> {
+    pub(super) fn new(f: &'a mut (dyn Write + 'b)) -> Self {
+        HtmlProofTreeFormatter { f }
+    }
+
+    /// Writes a collapsed node whose summary is written by `summary` and whose children are
+    /// written by `children`.
+    fn nested(
+        &mut self,
+        class: &str,
+        summary: std::fmt::Arguments<'_>,
+        children: impl FnOnce(&mut Self) -> std::fmt::Result,
+    ) -> std::fmt::Result {
+        writeln!(self.f, "<details><summary class=\"{class}\">{summary}</summary>")?;
+        children(self)?;
+        writeln!(self.f, "</details>")
+    }
+
+    fn leaf(&mut self, class: &str, text: std::fmt::Arguments<'_>) -> std::fmt::Result {
+        writeln!(self.f, "<div class=\"leaf {class}\">{text}</div>")
+    }
+
+    pub(super) fn format_document(&mut self, eval: &GoalEvaluation<'_>) -> std::fmt::Result {
+        writeln!(self.f, "<!DOCTYPE html>")?;
+        writeln!(self.f, "<html>\n<head>\n<meta charset=\"utf-8\">")?;
+        writeln!(self.f, "<title>{}</title>", Escaped(&eval.uncanonicalized_goal.predicate))?;
+        writeln!(self.f, "<style>\n{STYLE}</style>\n</head>\n<body>")?;
+        writeln!(self.f, "<input id=\"search\" type=\"search\" placeholder=\"Search goals\">")?;
+        self.format_goal_evaluation(eval)?;
+        writeln!(self.f, "<script>\n{SCRIPT}</script>\n</body>\n</html>")
+    }
+
+    fn format_goal_evaluation(&mut self, eval: &GoalEvaluation<'_>) -> std::fmt::Result {
+        let goal_text = match eval.kind {
+            GoalEvaluationKind::Root { orig_values: _ } => "ROOT GOAL",
+            GoalEvaluationKind::Nested => "GOAL",
+        };
+        self.nested(
+            result_class(&eval.evaluation.result),
+            format_args!("{goal_text}: {}", Escaped(&eval.uncanonicalized_goal)),
+            |this| this.format_canonical_goal_evaluation(&eval.evaluation),
+        )
+    }
+
+    fn format_canonical_goal_evaluation(
+        &mut self,
+        eval: &CanonicalGoalEvaluation<'_>,
+    ) -> std::fmt::Result {
+        let class = result_class(&eval.result);
+        self.leaf("", format_args!("GOAL: {}", Escaped(&eval.goal)))?;
+
+        match &eval.kind {
+            CanonicalGoalEvaluationKind::Overflow => {
+                self.leaf(class, format_args!("OVERFLOW: {}", Escaped(&eval.result)))
+            }
+            CanonicalGoalEvaluationKind::CycleInStack => {
+                self.leaf(class, format_args!("CYCLE IN STACK: {}", Escaped(&eval.result)))
+            }
+            CanonicalGoalEvaluationKind::ProvisionalCacheHit => {
+                self.leaf(class, format_args!("PROVISIONAL CACHE HIT: {}", Escaped(&eval.result)))
+            }
+            CanonicalGoalEvaluationKind::Evaluation { revisions } => {
+                for (n, step) in revisions.iter().enumerate() {
+                    self.nested("", format_args!("REVISION {n}"), |this| {
+                        this.format_evaluation_step(step)
+                    })?;
+                }
+                self.leaf(class, format_args!("RESULT: {}", Escaped(&eval.result)))
+            }
+        }
+    }
+
+    fn format_evaluation_step(
+        &mut self,
+        evaluation_step: &GoalEvaluationStep<'_>,
+    ) -> std::fmt::Result {
+        self.leaf(
+            "",
+            format_args!("INSTANTIATED: {}", Escaped(&evaluation_step.instantiated_goal)),
+        )?;
+        self.format_probe(&evaluation_step.evaluation)
+    }
+
+    fn format_probe(&mut self, probe: &Probe<'_>) -> std::fmt::Result {
+        let (class, summary) = match &probe.kind {
+            ProbeKind::Root { result } => {
+                (result_class(result), format!("ROOT RESULT: {}", Escaped(result)))
+            }
+            ProbeKind::NormalizedSelfTyAssembly => {
+                ("", "NORMALIZING SELF TY FOR ASSEMBLY:".to_owned())
+            }
+            ProbeKind::UnsizeAssembly => ("", "ASSEMBLING CANDIDATES FOR UNSIZING:".to_owned()),
+            ProbeKind::UpcastProjectionCompatibility => {
+                ("", "PROBING FOR PROJECTION COMPATIBILITY FOR UPCASTING:".to_owned())
+            }
+            ProbeKind::CommitIfOk => ("", "COMMIT_IF_OK:".to_owned()),
+            ProbeKind::MiscCandidate { name, result } => {
+                (result_class(result), format!("CANDIDATE {name}: {}", Escaped(result)))
+            }
+            ProbeKind::TraitCandidate { source, result } => (
+                result_class(result),
+                format!("CANDIDATE {}: {}", Escaped(source), Escaped(result)),
+            ),
+        };
+
+        self.nested(class, format_args!("{summary}"), |this| {
+            for step in &probe.steps {
+                match step {
+                    ProbeStep::AddGoal(source, goal) => {
+                        let source = match source {
+                            GoalSource::Misc => "misc",
+                            GoalSource::ImplWhereBound => "impl where-bound",
+                        };
+                        this.leaf("", format_args!("ADDED GOAL ({source}): {}", Escaped(goal)))?
+                    }
+                    ProbeStep::EvaluateGoals(eval) => this.format_added_goals_evaluation(eval)?,
+                    ProbeStep::NestedProbe(probe) => this.format_probe(probe)?,
+                    ProbeStep::CommitIfOkStart => {
+                        this.leaf("", format_args!("COMMIT_IF_OK START"))?
+                    }
+                    ProbeStep::CommitIfOkSuccess => {
+                        this.leaf("", format_args!("COMMIT_IF_OK SUCCESS"))?
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn format_added_goals_evaluation(
+        &mut self,
+        added_goals_evaluation: &AddedGoalsEvaluation<'_>,
+    ) -> std::fmt::Result {
+        let result = added_goals_evaluation.result;
+        self.nested(
+            certainty_class(result),
+            format_args!("TRY_EVALUATE_ADDED_GOALS: {}", Escaped(result)),
+            |this| {
+                for (n, iterations) in added_goals_evaluation.evaluations.iter().enumerate() {
+                    this.nested("", format_args!("ITERATION {n}"), |this| {
+                        for goal_evaluation in iterations {
+                            this.format_goal_evaluation(goal_evaluation)?;
+                        }
+                        Ok(())
+                    })?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
@@ -63,18 +63,21 @@
 use rustc_hir as hir;
 use rustc_hir::lang_items::LangItem;
 use rustc_hir::{CoroutineDesugaring, CoroutineKind};
-use rustc_index::bit_set::{BitMatrix, BitSet, GrowableBitSet};
+use rustc_index::bit_set::{BitMatrix, BitSet, ChunkedBitSet, GrowableBitSet};
 use rustc_index::{Idx, IndexVec};
 use rustc_middle::mir::visit::{MutVisitor, PlaceContext, Visitor};
 use rustc_middle::mir::*;
 use rustc_middle::ty::CoroutineArgs;
 use rustc_middle::ty::InstanceDef;
 use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_mir_dataflow::drop_flag_effects::on_all_children_bits;
 use rustc_mir_dataflow::impls::{
-    MaybeBorrowedLocals, MaybeLiveLocals, MaybeRequiresStorage, MaybeStorageLive,
+    borrowed_locals, MaybeBorrowedLocals, MaybeInitializedPlaces, MaybeLiveLocals,
+    MaybeRequiresStorage, MaybeStorageLive,
 };
+use rustc_mir_dataflow::move_paths::{MoveData, MovePathIndex};
 use rustc_mir_dataflow::storage::always_storage_live_locals;
-use rustc_mir_dataflow::Analysis;
+use rustc_mir_dataflow::{Analysis, MaybeReachable, MoveDataParamEnv};
 use rustc_span::def_id::{DefId, LocalDefId};
 use rustc_span::symbol::sym;
 use rustc_span::Span;
@@ -860,6 +863,7 @@ fn locals_live_across_suspend_points<'tcx>(
         &saved_locals,
         always_live_locals.clone(),
         requires_storage_cursor.into_results(),
+        tcx.sess.opts.unstable_opts.coroutine_refined_layout,
     );
 
     LivenessInfo {
@@ -920,21 +924,29 @@ fn deref(&self) -> &Self::Target {
 /// time. Generates a bitset for every local of all the other locals that may be
 /// StorageLive simultaneously with that local. This is used in the layout
 /// computation; see `CoroutineLayout` for more.
+///
+/// With `refined`, always-live locals that are neither arguments nor ever
+/// borrowed are not treated as conflicting with every other saved local. See
+/// `refined_eligible_locals` for why this is sound.
 fn compute_storage_conflicts<'mir, 'tcx>(
     body: &'mir Body<'tcx>,
     saved_locals: &CoroutineSavedLocals,
     always_live_locals: BitSet<Local>,
     mut requires_storage: rustc_mir_dataflow::Results<'tcx, MaybeRequiresStorage<'mir, 'tcx>>,
+    refined: bool,
 ) -> BitMatrix<CoroutineSavedLocal, CoroutineSavedLocal> {
     assert_eq!(body.local_decls.len(), saved_locals.domain_size());
 
-    debug!("compute_storage_conflicts({:?})", body.span);
+    debug!("compute_storage_conflicts({:?}, refined={})", body.span, refined);
     debug!("always_live = {:?}", always_live_locals);
 
     // Locals that are always live or ones that need to be stored across
     // suspension points are not eligible for overlap.
     let mut ineligible_locals = always_live_locals;
     ineligible_locals.intersect(&**saved_locals);
+    if refined {
+        ineligible_locals.subtract(&refined_eligible_locals(body));
+    }
 
     // Compute the storage conflicts for all eligible locals.
     let mut visitor = StorageConflictVisitor {
@@ -972,6 +984,32 @@ fn compute_storage_conflicts<'mir, 'tcx>(
     storage_conflicts
 }
 
+/// Returns the always-live locals whose storage conflicts can be computed from
+/// `MaybeRequiresStorage` like those of locals with storage markers.
+///
+/// Always-live locals have no `StorageLive`/`StorageDead` statements, so
+/// `MaybeRequiresStorage` only tracks them through their uses: they start
+/// requiring storage when they are (partially) assigned to or borrowed, and stop
+/// when they are moved out of as a whole while not borrowed. That is exact for
+/// locals that are never borrowed: their bytes can only be reached through the
+/// local itself, borrowck rejects any use after a move, and drop elaboration has
+/// already removed or guarded the drops of moved-from values. So once such a
+/// local has been moved out of, nothing can observe its slot before the next
+/// assignment, which `MaybeRequiresStorage` reports again.
+///
+/// Borrowed locals are excluded because a pointer derived from them may be used
+/// after the move that kills them, and arguments are excluded because they hold
+/// a value on entry without any assignment in the body.
+fn refined_eligible_locals(body: &Body<'_>) -> BitSet<Local> {
+    let mut eligible = BitSet::new_filled(body.local_decls.len());
+    eligible.subtract(&borrowed_locals(body));
+    eligible.remove(RETURN_PLACE);
+    for arg in body.args_iter() {
+        eligible.remove(arg);
+    }
+    eligible
+}
+
 struct StorageConflictVisitor<'mir, 'tcx, 's> {
     body: &'mir Body<'tcx>,
     saved_locals: &'s CoroutineSavedLocals,
@@ -1577,13 +1615,15 @@ pub(crate) fn mir_coroutine_witnesses<'tcx>(
 
     let always_live_locals = always_storage_live_locals(body);
     let liveness_info = locals_live_across_suspend_points(tcx, body, &always_live_locals, movable);
+    let saved_locals: IndexVec<CoroutineSavedLocal, Local> =
+        liveness_info.saved_locals.iter().collect();
 
     // Extract locals which are live across suspension point into `layout`
     // `remap` gives a mapping from local indices onto coroutine struct indices
     // `storage_liveness` tells us which locals have live storage at suspension points
     let (_, coroutine_layout, _) = compute_layout(liveness_info, body);
 
-    check_suspend_tys(tcx, &coroutine_layout, body);
+    check_suspend_tys(tcx, &coroutine_layout, &saved_locals, body);
 
     Some(coroutine_layout)
 }
@@ -1891,21 +1931,59 @@ fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location
     }
 }
 
-fn check_suspend_tys<'tcx>(tcx: TyCtxt<'tcx>, layout: &CoroutineLayout<'tcx>, body: &Body<'tcx>) {
+fn check_suspend_tys<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    layout: &CoroutineLayout<'tcx>,
+    saved_locals: &IndexVec<CoroutineSavedLocal, Local>,
+    body: &Body<'tcx>,
+) {
+    // `must_not_suspend` is a gated lint, which is always allowed without the feature. Skip
+    // computing the dataflow below in that case.
+    if !tcx.features().must_not_suspend {
+        return;
+    }
+
     let mut linted_tys = FxHashSet::default();
 
     // We want a user-facing param-env.
     let param_env = tcx.param_env(body.source.def_id());
 
-    for (variant, yield_source_info) in
-        layout.variant_fields.iter().zip(&layout.variant_source_info)
+    // The layout conservatively saves the locals that have been borrowed until their storage
+    // dies, even if their value was moved out before the suspension point, e.g. a lock guard
+    // passed to `drop`. Only values that may still be initialized at a suspension point are
+    // actually held across it, so only those are linted.
+    let move_data = MoveData::gather_moves(body, tcx, param_env, |_| true);
+    let mdpe = MoveDataParamEnv { move_data, param_env };
+    let mut maybe_inits = MaybeInitializedPlaces::new(tcx, body, &mdpe)
+        .into_engine(tcx, body)
+        .pass_name("coroutine_must_not_suspend")
+        .iterate_to_fixpoint()
+        .into_results_cursor(body);
+
+    // The suspension points, in the order of the variants of the layout.
+    let suspension_points = body
+        .basic_blocks
+        .iter_enumerated()
+        .filter(|(_, data)| matches!(data.terminator().kind, TerminatorKind::Yield { .. }))
+        .map(|(block, _)| body.terminator_loc(block));
+
+    for ((variant, yield_source_info), location) in layout
+        .variant_fields
+        .iter()
+        .zip(&layout.variant_source_info)
+        .skip(RESERVED_VARIANTS)
+        .zip(suspension_points)
     {
         debug!(?variant);
+        maybe_inits.seek_before_primary_effect(location);
         for &local in variant {
             let decl = &layout.field_tys[local];
             debug!(?decl);
 
-            if !decl.ignore_for_traits && linted_tys.insert(decl.ty) {
+            if !decl.ignore_for_traits
+                && is_maybe_initialized(&mdpe.move_data, maybe_inits.get(), saved_locals[local])
+                && linted_tys.insert(decl.ty)
+            {
                 let Some(hir_id) = decl.source_info.scope.lint_root(&body.source_scopes) else {
                     continue;
                 };
@@ -1927,6 +2005,19 @@ fn check_suspend_tys<'tcx>(tcx: TyCtxt<'tcx>, layout: &CoroutineLayout<'tcx>, bo
     }
 }
 
+/// Returns whether some part of the value of `local` may be initialized in `state`.
+fn is_maybe_initialized(
+    move_data: &MoveData<'_>,
+    state: &MaybeReachable<ChunkedBitSet<MovePathIndex>>,
+    local: Local,
+) -> bool {
+    let MaybeReachable::Reachable(state) = state else { return false };
+    let Some(mpi) = move_data.rev_lookup.find_local(local) else { return true };
+    let mut maybe_init = false;
+    on_all_children_bits(move_data, mpi, |child| maybe_init |= state.contains(child));
+    maybe_init
+}
+
 #[derive(Default)]
 struct SuspendCheckData<'a> {
     source_span: Span,
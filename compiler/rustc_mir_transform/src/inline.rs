@@ -1,6 +1,6 @@
 //! Inlining pass for MIR functions
 use crate::deref_separator::deref_finder;
-use rustc_attr::InlineAttr;
+use rustc_attr::{InlineAttr, OptimizeAttr};
 use rustc_const_eval::transform::validate::validate_types;
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::DefId;
@@ -430,6 +430,10 @@ fn check_codegen_attributes(
             return Err("never inline hint");
         }
 
+        if let OptimizeAttr::Level(OptLevel::No) = callee_attrs.optimize {
+            return Err("#[optimize(level = \"0\")]");
+        }
+
         // Reachability pass defines which functions are eligible for inlining. Generally inlining
         // other functions is incorrect because they could reference symbols that aren't exported.
         let is_generic = callsite
@@ -22,6 +22,7 @@
 
 use hir::ConstContext;
 use required_consts::RequiredConstsVisitor;
+use rustc_attr::OptimizeAttr;
 use rustc_const_eval::util;
 use rustc_data_structures::fx::FxIndexSet;
 use rustc_data_structures::steal::Steal;
@@ -39,6 +40,7 @@
 use rustc_middle::query;
 use rustc_middle::ty::{self, TyCtxt, TypeVisitableExt};
 use rustc_middle::util::Providers;
+use rustc_session::config::OptLevel;
 use rustc_span::{source_map::Spanned, sym, DUMMY_SP};
 use rustc_trait_selection::traits;
 
@@ -557,6 +559,26 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
         WithMinOptLevel(1, x)
     }
 
+    // `#[optimize(level = "0")]` opts the body out of MIR optimizations, just like it opts it out
+    // of LLVM's, so only run the passes that are needed regardless of the optimization level.
+    if let OptimizeAttr::Level(OptLevel::No) = tcx.codegen_fn_attrs(body.source.def_id()).optimize
+    {
+        pm::run_passes(
+            tcx,
+            body,
+            &[
+                &mentioned_items::MentionedItems,
+                &check_alignment::CheckAlignment,
+                &add_call_guards::CriticalCallEdges,
+                &prettify::ReorderBasicBlocks,
+                &prettify::ReorderLocals,
+                &dump_mir::Marker("PreCodegen"),
+            ],
+            Some(MirPhase::Runtime(RuntimePhase::Optimized)),
+        );
+        return;
+    }
+
     // The main optimizations that we do on MIR.
     pm::run_passes(
         tcx,
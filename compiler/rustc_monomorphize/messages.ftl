@@ -12,6 +12,13 @@ monomorphize_large_assignments =
     .label = value moved from here
     .note = The current maximum size is {$limit}, but it can be customized with the move_size_limit attribute: `#![move_size_limit = "..."]`
 
+monomorphize_large_future =
+    this future is {$size} bytes, exceeding the limit of {$limit} bytes
+    .note = the limit can be changed with `-Z future-size-limit=...`
+
+monomorphize_large_future_held_local =
+    {$local} ({$size} bytes) is held across this await point
+
 monomorphize_no_optimized_mir =
     missing optimized MIR for an item in the crate `{$crate_name}`
     .note = missing optimized MIR for this item (was the crate `{$crate_name}` compiled with `--emit=metadata`?)
@@ -226,7 +226,7 @@
 };
 use rustc_middle::ty::{GenericArgKind, GenericArgs};
 use rustc_session::config::EntryFnType;
-use rustc_session::lint::builtin::LARGE_ASSIGNMENTS;
+use rustc_session::lint::builtin::{LARGE_ASSIGNMENTS, LARGE_FUTURES};
 use rustc_session::Limit;
 use rustc_span::source_map::{dummy_spanned, respan, Spanned};
 use rustc_span::symbol::{sym, Ident};
@@ -235,8 +235,8 @@
 use std::path::PathBuf;
 
 use crate::errors::{
-    self, EncounteredErrorWhileInstantiating, LargeAssignmentsLint, NoOptimizedMir, RecursionLimit,
-    TypeLengthLimit,
+    self, EncounteredErrorWhileInstantiating, LargeAssignmentsLint, LargeFutureHeldLocal,
+    LargeFutureLint, NoOptimizedMir, RecursionLimit, TypeLengthLimit,
 };
 
 #[derive(PartialEq)]
@@ -308,6 +308,10 @@ pub fn get_user_items(&self, item: MonoItem<'tcx>) -> &[MonoItem<'tcx>] {
         self.user_map.get(&item).map(|items| items.as_slice()).unwrap_or(&[])
     }
 
+    pub fn get_used_items(&self, item: MonoItem<'tcx>) -> &[MonoItem<'tcx>] {
+        self.used_map.get(&item).map(|items| items.as_slice()).unwrap_or(&[])
+    }
+
     /// Internally iterate over all inlined items used by `item`.
     pub fn for_each_inlined_used_item<F>(&self, tcx: TyCtxt<'tcx>, item: MonoItem<'tcx>, mut f: F)
     where
@@ -440,6 +444,9 @@ fn collect_items_rec<'tcx>(
                 recursion_limit,
             ));
             check_type_length_limit(tcx, instance);
+            if mode == CollectionMode::UsedItems {
+                check_future_size(tcx, instance);
+            }
 
             rustc_data_structures::stack::ensure_sufficient_stack(|| {
                 collect_items_of_instance(
@@ -658,6 +665,94 @@ fn check_type_length_limit<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) {
     }
 }
 
+/// Emits the `large_futures` lint if `instance` is the body of an `async` block or function
+/// whose state machine is larger than `-Z future-size-limit`.
+fn check_future_size<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) {
+    let Some(limit) = tcx.sess.opts.unstable_opts.future_size_limit else {
+        return;
+    };
+    let InstanceDef::Item(def_id) = instance.def else {
+        return;
+    };
+    if !tcx.coroutine_is_async(def_id) {
+        return;
+    }
+    // We need a `HirId` to look up the lint level, so futures that were defined in
+    // another crate and instantiated in this one are not reported.
+    let Some(local_def_id) = def_id.as_local() else {
+        return;
+    };
+
+    let param_env = ty::ParamEnv::reveal_all();
+    let coroutine_ty = Ty::new_coroutine(tcx, def_id, instance.args);
+    let Ok(layout) = tcx.layout_of(param_env.and(coroutine_ty)) else {
+        return;
+    };
+    if layout.size.bytes_usize() <= limit {
+        return;
+    }
+    let Some(coroutine_layout) =
+        tcx.coroutine_layout(def_id, instance.args.as_coroutine().kind_ty())
+    else {
+        return;
+    };
+
+    // Attribute the size to the largest saved locals, each one along with the first
+    // suspension point it is held across. Only a few of them are reported, since
+    // the rest is usually noise.
+    let mut held_locals: Vec<_> = coroutine_layout
+        .field_tys
+        .iter_enumerated()
+        .filter_map(|(local, saved_ty)| {
+            let ty = instance.instantiate_mir_and_normalize_erasing_regions(
+                tcx,
+                param_env,
+                ty::EarlyBinder::bind(saved_ty.ty),
+            );
+            let size = tcx.layout_of(param_env.and(ty)).ok()?.size;
+            if size == Size::ZERO {
+                return None;
+            }
+            let (variant, _) = coroutine_layout
+                .variant_fields
+                .iter_enumerated()
+                .find(|(_, fields)| fields.iter().any(|&field| field == local))?;
+            Some((size, local, ty, variant))
+        })
+        .collect();
+    held_locals.sort_by_key(|&(size, local, ..)| (std::cmp::Reverse(size), local));
+    let held_locals = held_locals
+        .into_iter()
+        .take(3)
+        .map(|(size, local, ty, variant)| {
+            let local = match coroutine_layout.field_names[local] {
+                Some(sym::__awaitee) => format!("the awaited future `{ty}`"),
+                Some(name) => format!("`{name}`"),
+                None => format!("a temporary of type `{ty}`"),
+            };
+            LargeFutureHeldLocal {
+                await_span: coroutine_layout.variant_source_info[variant].span,
+                local,
+                size: size.bytes(),
+            }
+        })
+        .collect();
+
+    // Point at the signature of an `async fn` rather than at its whole body.
+    let span = match tcx.coroutine_kind(def_id) {
+        Some(hir::CoroutineKind::Desugared(_, hir::CoroutineSource::Fn)) => {
+            tcx.def_span(tcx.parent(def_id))
+        }
+        _ => tcx.def_span(def_id),
+    };
+    tcx.emit_node_span_lint(
+        LARGE_FUTURES,
+        tcx.local_def_id_to_hir_id(local_def_id),
+        span,
+        LargeFutureLint { size: layout.size.bytes(), limit: limit as u64, held_locals },
+    );
+}
+
 struct MirUsedCollector<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
     body: &'a mir::Body<'tcx>,
@@ -1787,3 +1882,94 @@ pub fn collect_crate_mono_items(
 
     (state.visited.into_inner(), state.usage_map.into_inner())
 }
+
+/// A function codegened in the local crate, with the functions it may call. This is the call
+/// graph that `--emit=stack-sizes` combines with the stack frame sizes found in object files.
+pub struct CallGraphNode<'tcx> {
+    pub instance: Instance<'tcx>,
+    /// The functions used by `instance` according to the collector, as well as the upstream
+    /// functions it calls directly, which the collector ignores as they aren't codegened here.
+    pub callees: Vec<Instance<'tcx>>,
+    /// Whether `instance` calls functions through pointers, like `dyn Trait` methods, so that its
+    /// callees cannot be known.
+    pub has_indirect_calls: bool,
+}
+
+/// Computes the call graph of the functions in the codegen units of the local crate.
+pub fn call_graph<'tcx>(tcx: TyCtxt<'tcx>) -> Vec<CallGraphNode<'tcx>> {
+    let (_, codegen_units) = tcx.collect_and_partition_mono_items(());
+    // Inlined functions are in every codegen unit using them.
+    let instances: FxHashSet<_> = codegen_units
+        .iter()
+        .flat_map(|cgu| cgu.items().keys())
+        .filter_map(|item| match *item {
+            MonoItem::Fn(instance) => Some(instance),
+            MonoItem::Static(_) | MonoItem::GlobalAsm(_) => None,
+        })
+        .collect();
+
+    instances
+        .into_iter()
+        .map(|instance| {
+            let mut used_items = Vec::new();
+            let mut mentioned_items = Vec::new();
+            collect_items_of_instance(
+                tcx,
+                instance,
+                &mut used_items,
+                &mut mentioned_items,
+                CollectionMode::UsedItems,
+            );
+            let mut callees: FxHashSet<_> = used_items
+                .iter()
+                .filter_map(|item| match item.node {
+                    MonoItem::Fn(callee) => Some(callee.polymorphize(tcx)),
+                    MonoItem::Static(_) | MonoItem::GlobalAsm(_) => None,
+                })
+                .collect();
+
+            let body = tcx.instance_mir(instance.def);
+            let monomorphize = |ty| {
+                instance.instantiate_mir_and_normalize_erasing_regions(
+                    tcx,
+                    ty::ParamEnv::reveal_all(),
+                    ty::EarlyBinder::bind(ty),
+                )
+            };
+            let mut has_indirect_calls = false;
+            for block in body.basic_blocks.iter() {
+                let callee = match block.terminator().kind {
+                    mir::TerminatorKind::Call { ref func, .. } => {
+                        match *monomorphize(func.ty(body, tcx)).kind() {
+                            ty::FnDef(def_id, args) => Instance::expect_resolve(
+                                tcx,
+                                ty::ParamEnv::reveal_all(),
+                                def_id,
+                                args,
+                            ),
+                            _ => {
+                                has_indirect_calls = true;
+                                continue;
+                            }
+                        }
+                    }
+                    mir::TerminatorKind::Drop { ref place, .. } => {
+                        Instance::resolve_drop_in_place(tcx, monomorphize(place.ty(body, tcx).ty))
+                    }
+                    _ => continue,
+                };
+                match callee.def {
+                    InstanceDef::Virtual(..) => has_indirect_calls = true,
+                    // Intrinsics and empty drop glue are not calls.
+                    InstanceDef::Intrinsic(_) | InstanceDef::DropGlue(_, None) => {}
+                    _ if !should_codegen_locally(tcx, callee) => {
+                        callees.insert(callee);
+                    }
+                    _ => {}
+                }
+            }
+
+            CallGraphNode { instance, callees: callees.into_iter().collect(), has_indirect_calls }
+        })
+        .collect()
+}
@@ -0,0 +1,333 @@
+//! Deduplication of generic instances
+//! ==================================
+//!
+//! Instances of a generic function often end up with identical machine code, because the
+//! generic arguments that differ between them are only moved around, but never operated on,
+//! e.g. `Vec<u32>::len` and `Vec<i32>::len`. With `-Zdedup-instances`, such instances are
+//! detected after the codegen units have been merged: one of them keeps its body, and the others
+//! are moved into the same codegen unit and emitted as aliases of it. The aliases keep their own
+//! symbol name, linkage and visibility, so exporting them works as before. Debuginfo is only
+//! emitted for the body, so in a debugger all of the aliases show up as that instance.
+//!
+//! Two instances of the same function can be merged if every generic argument that differs
+//! between them is a type that is only used for its layout in the optimized MIR of the function,
+//! i.e. it is not used to resolve a callee or a constant, to pick drop glue, or in an unsizing
+//! cast. In addition, every type whose layout the body depends on must have the same layout in
+//! both instances, ignoring the signedness of integers, which only matters for arithmetic on
+//! them. These are the types of the locals, of every place and of every prefix of its
+//! projections (so that indexing and field offsets agree), of operands and rvalues, the types
+//! used by `size_of`/`align_of`-like operations, and the pointees of all pointers among them
+//! (so that pointer offsets and copies through pointers agree). Finally, the ABI of both
+//! instances must be the same.
+
+use rustc_data_structures::fx::{FxIndexMap, FxIndexSet};
+use rustc_hir::def_id::DefId;
+use rustc_index::bit_set::BitSet;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::mir::mono::{CodegenUnit, Linkage, MonoItem};
+use rustc_middle::mir::visit::{PlaceContext, TyContext, Visitor};
+use rustc_middle::mir::{self, Location};
+use rustc_middle::ty::adjustment::PointerCoercion;
+use rustc_middle::ty::layout::TyAndLayout;
+use rustc_middle::ty::visit::{TypeSuperVisitable, TypeVisitable, TypeVisitableExt, TypeVisitor};
+use rustc_middle::ty::{self, GenericArgKind, Instance, InstanceDef, Ty, TyCtxt};
+use rustc_target::abi::call::FnAbi;
+use rustc_target::abi::{Abi, Primitive, Scalar};
+
+pub(crate) fn dedup_instances<'tcx>(tcx: TyCtxt<'tcx>, codegen_units: &mut [CodegenUnit<'tcx>]) {
+    // Group the candidates by the function they are an instance of. Codegen units are sorted by
+    // name and their items are visited in a deterministic order, so which instance keeps its
+    // body is deterministic as well.
+    let mut candidates: FxIndexMap<DefId, Vec<(Instance<'tcx>, usize)>> = Default::default();
+    for (cgu_index, cgu) in codegen_units.iter().enumerate() {
+        for (item, data) in cgu.items_in_deterministic_order(tcx) {
+            let MonoItem::Fn(instance) = item else { continue };
+            let InstanceDef::Item(def_id) = instance.def else { continue };
+            if data.inlined || data.linkage != Linkage::External {
+                continue;
+            }
+            if instance.args.is_empty() || instance.args.has_non_region_param() {
+                continue;
+            }
+            let attrs = tcx.codegen_fn_attrs(def_id);
+            if attrs.contains_extern_indicator()
+                || attrs.link_section.is_some()
+                || attrs.flags.contains(CodegenFnAttrFlags::NAKED)
+            {
+                continue;
+            }
+            candidates.entry(def_id).or_default().push((instance, cgu_index));
+        }
+    }
+
+    for (def_id, instances) in candidates {
+        if instances.len() < 2 {
+            continue;
+        }
+
+        let body = tcx.instance_mir(InstanceDef::Item(def_id));
+        let behavioral_params = behavioral_params(tcx, def_id, body);
+        let layout_tys = layout_dependent_tys(tcx, body);
+        debug!(?def_id, ?behavioral_params, ?layout_tys);
+
+        // Each class is made of the instance that keeps its body and its future aliases.
+        let mut classes: Vec<((Instance<'tcx>, usize), Vec<(Instance<'tcx>, usize)>)> = vec![];
+        for (instance, cgu_index) in instances {
+            let class = classes.iter_mut().find(|((target, _), _)| {
+                can_merge(tcx, &behavioral_params, &layout_tys, *target, instance)
+            });
+            match class {
+                Some((_, aliases)) => aliases.push((instance, cgu_index)),
+                None => classes.push(((instance, cgu_index), vec![])),
+            }
+        }
+
+        for ((target, target_cgu), aliases) in classes {
+            for (alias, alias_cgu) in aliases {
+                debug!("emitting `{alias}` as an alias of `{target}`");
+                let item = MonoItem::Fn(alias);
+                let data = codegen_units[alias_cgu].items_mut().swap_remove(&item).unwrap();
+                let cgu = &mut codegen_units[target_cgu];
+                cgu.items_mut().insert(item, data);
+                cgu.add_alias(alias, target);
+            }
+        }
+    }
+
+    for cgu in codegen_units.iter_mut() {
+        cgu.compute_size_estimate();
+    }
+}
+
+/// Returns the generic parameters of `def_id` that are used for more than their layout, so
+/// that instances can only be merged if they agree on them.
+fn behavioral_params<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    body: &mir::Body<'tcx>,
+) -> BitSet<u32> {
+    let mut collector =
+        BehavioralParams { tcx, body, params: BitSet::new_empty(tcx.generics_of(def_id).count()) };
+    collector.visit_body(body);
+    collector.params
+}
+
+struct BehavioralParams<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a mir::Body<'tcx>,
+    params: BitSet<u32>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for BehavioralParams<'a, 'tcx> {
+    fn visit_constant(&mut self, ct: &mir::ConstOperand<'tcx>, _: Location) {
+        // Callees, statics and constants are all resolved using the generic arguments.
+        ct.const_.visit_with(self);
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
+        if let mir::Rvalue::Cast(kind, operand, target_ty) = rvalue {
+            // Unsizing picks a vtable, and function pointers are derived from the source type.
+            if matches!(
+                kind,
+                mir::CastKind::PointerCoercion(
+                    PointerCoercion::Unsize
+                        | PointerCoercion::ReifyFnPointer
+                        | PointerCoercion::ClosureFnPointer(_)
+                ) | mir::CastKind::DynStar
+            ) {
+                operand.ty(self.body, self.tcx).visit_with(self);
+                target_ty.visit_with(self);
+            }
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: Location) {
+        match &terminator.kind {
+            // The drop glue depends on the type being dropped.
+            mir::TerminatorKind::Drop { place, .. } => {
+                place.ty(self.body, self.tcx).ty.visit_with(self);
+            }
+            // The ABI of a call through a function pointer depends on its signature.
+            mir::TerminatorKind::Call { func, .. } if func.constant().is_none() => {
+                func.ty(self.body, self.tcx).visit_with(self);
+            }
+            mir::TerminatorKind::InlineAsm { .. } => self.params.insert_all(),
+            _ => {}
+        }
+        self.super_terminator(terminator, location);
+    }
+
+    fn visit_ty(&mut self, _: Ty<'tcx>, _: TyContext) {
+        // Types of locals and places only matter for their layout, which is checked separately.
+    }
+}
+
+impl<'a, 'tcx> TypeVisitor<TyCtxt<'tcx>> for BehavioralParams<'a, 'tcx> {
+    fn visit_ty(&mut self, ty: Ty<'tcx>) {
+        if !ty.has_non_region_param() {
+            return;
+        }
+        match *ty.kind() {
+            ty::Param(param) => {
+                self.params.insert(param.index);
+            }
+            _ => ty.super_visit_with(self),
+        }
+    }
+
+    fn visit_const(&mut self, c: ty::Const<'tcx>) {
+        if !c.has_non_region_param() {
+            return;
+        }
+        match c.kind() {
+            ty::ConstKind::Param(param) => {
+                self.params.insert(param.index);
+            }
+            _ => c.super_visit_with(self),
+        }
+    }
+}
+
+/// Returns the generic types whose layout the body of `def_id` depends on, so that instances
+/// can only be merged if these types have equivalent layouts in both of them.
+fn layout_dependent_tys<'tcx>(tcx: TyCtxt<'tcx>, body: &mir::Body<'tcx>) -> FxIndexSet<Ty<'tcx>> {
+    let mut collector = LayoutDependentTys { tcx, body, tys: Default::default() };
+    collector.visit_body(body);
+    collector.tys
+}
+
+struct LayoutDependentTys<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a mir::Body<'tcx>,
+    tys: FxIndexSet<Ty<'tcx>>,
+}
+
+impl<'a, 'tcx> LayoutDependentTys<'a, 'tcx> {
+    fn add(&mut self, ty: Ty<'tcx>) {
+        if !ty.has_non_region_param() || !self.tys.insert(ty) {
+            return;
+        }
+        // Offsetting, reading and writing through a pointer all depend on the layout of its
+        // pointee, even if the pointer itself has the same layout in every instance.
+        if let Some(pointee) = ty.builtin_deref(true) {
+            self.add(pointee.ty);
+        }
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for LayoutDependentTys<'a, 'tcx> {
+    fn visit_place(&mut self, place: &mir::Place<'tcx>, context: PlaceContext, location: Location) {
+        // Projections depend on the layout of the place they project from, e.g. indexing a slice
+        // depends on the size of its elements.
+        for (base, _) in place.iter_projections() {
+            self.add(base.ty(self.body, self.tcx).ty);
+        }
+        self.add(place.ty(self.body, self.tcx).ty);
+        self.super_place(place, context, location);
+    }
+
+    fn visit_operand(&mut self, operand: &mir::Operand<'tcx>, location: Location) {
+        self.add(operand.ty(self.body, self.tcx));
+        self.super_operand(operand, location);
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
+        self.add(rvalue.ty(self.body, self.tcx));
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_ty(&mut self, ty: Ty<'tcx>, _: TyContext) {
+        // This covers the types of locals as well as the types in `NullaryOp`s, such as the one
+        // of `size_of`, and the target types of casts.
+        self.add(ty);
+    }
+}
+
+fn can_merge<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    behavioral_params: &BitSet<u32>,
+    layout_tys: &FxIndexSet<Ty<'tcx>>,
+    target: Instance<'tcx>,
+    alias: Instance<'tcx>,
+) -> bool {
+    for (index, (a, b)) in target.args.iter().zip(alias.args).enumerate() {
+        if a == b {
+            continue;
+        }
+        if behavioral_params.contains(index as u32) {
+            return false;
+        }
+        match a.unpack() {
+            GenericArgKind::Type(_) => {}
+            GenericArgKind::Lifetime(_) | GenericArgKind::Const(_) => return false,
+        }
+    }
+
+    let param_env = ty::ParamEnv::reveal_all();
+    let layouts_match = |ty: Ty<'tcx>| {
+        let ty = ty::EarlyBinder::bind(ty);
+        let a = target.instantiate_mir_and_normalize_erasing_regions(tcx, param_env, ty);
+        let b = alias.instantiate_mir_and_normalize_erasing_regions(tcx, param_env, ty);
+        if a == b {
+            return true;
+        }
+        match (tcx.layout_of(param_env.and(a)), tcx.layout_of(param_env.and(b))) {
+            (Ok(a), Ok(b)) => layouts_equivalent(a, b),
+            _ => false,
+        }
+    };
+    if !layout_tys.iter().all(|&ty| layouts_match(ty)) {
+        return false;
+    }
+
+    let no_extra_args = ty::List::empty();
+    match (
+        tcx.fn_abi_of_instance(param_env.and((target, no_extra_args))),
+        tcx.fn_abi_of_instance(param_env.and((alias, no_extra_args))),
+    ) {
+        (Ok(a), Ok(b)) => fn_abis_equivalent(a, b),
+        _ => false,
+    }
+}
+
+fn fn_abis_equivalent<'tcx>(a: &FnAbi<'tcx, Ty<'tcx>>, b: &FnAbi<'tcx, Ty<'tcx>>) -> bool {
+    a.conv == b.conv
+        && a.c_variadic == b.c_variadic
+        && a.fixed_count == b.fixed_count
+        && a.can_unwind == b.can_unwind
+        && a.args.len() == b.args.len()
+        && a.args.iter().chain([&a.ret]).zip(b.args.iter().chain([&b.ret])).all(|(a, b)| {
+            // The pass mode includes the argument attributes, so this also makes sure that
+            // integers are extended the same way.
+            a.mode == b.mode && layouts_equivalent(a.layout, b.layout)
+        })
+}
+
+fn layouts_equivalent<'tcx>(a: TyAndLayout<'tcx>, b: TyAndLayout<'tcx>) -> bool {
+    a.layout == b.layout
+        || (a.size == b.size
+            && a.align == b.align
+            && a.fields == b.fields
+            && a.variants == b.variants
+            && a.largest_niche == b.largest_niche
+            && erase_int_signedness(a.abi) == erase_int_signedness(b.abi))
+}
+
+fn erase_int_signedness(abi: Abi) -> Abi {
+    let erase = |scalar| match scalar {
+        Scalar::Initialized { value: Primitive::Int(int, _), valid_range } => {
+            Scalar::Initialized { value: Primitive::Int(int, false), valid_range }
+        }
+        Scalar::Union { value: Primitive::Int(int, _) } => {
+            Scalar::Union { value: Primitive::Int(int, false) }
+        }
+        _ => scalar,
+    };
+    match abi {
+        Abi::Scalar(scalar) => Abi::Scalar(erase(scalar)),
+        Abi::ScalarPair(a, b) => Abi::ScalarPair(erase(a), erase(b)),
+        Abi::Vector { element, count } => Abi::Vector { element: erase(element), count },
+        Abi::Uninhabited | Abi::Aggregate { .. } => abi,
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::fluent_generated as fluent;
 use rustc_errors::{Diag, DiagCtxt, Diagnostic, EmissionGuarantee, Level};
-use rustc_macros::{Diagnostic, LintDiagnostic};
+use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
 use rustc_span::{Span, Symbol};
 
 #[derive(Diagnostic)]
@@ -72,6 +72,25 @@ pub struct LargeAssignmentsLint {
     pub limit: u64,
 }
 
+#[derive(LintDiagnostic)]
+#[diag(monomorphize_large_future)]
+#[note]
+pub struct LargeFutureLint {
+    pub size: u64,
+    pub limit: u64,
+    #[subdiagnostic]
+    pub held_locals: Vec<LargeFutureHeldLocal>,
+}
+
+#[derive(Subdiagnostic)]
+#[note(monomorphize_large_future_held_local)]
+pub struct LargeFutureHeldLocal {
+    #[primary_span]
+    pub await_span: Span,
+    pub local: String,
+    pub size: u64,
+}
+
 #[derive(Diagnostic)]
 #[diag(monomorphize_symbol_already_defined)]
 pub struct SymbolAlreadyDefined {
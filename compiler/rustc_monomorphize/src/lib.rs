@@ -18,12 +18,14 @@
 use rustc_span::ErrorGuaranteed;
 
 mod collector;
+mod dedup;
 mod errors;
 mod partitioning;
 mod polymorphize;
 mod util;
 
 use collector::should_codegen_locally;
+pub use collector::{call_graph, CallGraphNode};
 
 rustc_fluent_macro::fluent_messages! { "../messages.ftl" }
 
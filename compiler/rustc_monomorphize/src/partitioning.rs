@@ -98,6 +98,7 @@
 use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 
+use rustc_attr::OptimizeAttr;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync;
 use rustc_hir::def::DefKind;
@@ -112,12 +113,13 @@
 use rustc_middle::query::Providers;
 use rustc_middle::ty::print::{characteristic_def_id_of_type, with_no_trimmed_paths};
 use rustc_middle::ty::{self, visit::TypeVisitableExt, InstanceDef, TyCtxt};
-use rustc_session::config::{DumpMonoStatsFormat, SwitchWithOptPath};
+use rustc_session::config::{CguPartitioning, DumpMonoStatsFormat, OptLevel, SwitchWithOptPath};
 use rustc_session::CodegenUnits;
 use rustc_span::symbol::Symbol;
 
 use crate::collector::UsageMap;
 use crate::collector::{self, MonoItemCollectionStrategy};
+use crate::dedup;
 use crate::errors::{CouldntDumpMonoStats, SymbolAlreadyDefined, UnknownCguCollectionMode};
 
 struct PartitioningCx<'a, 'tcx> {
@@ -158,13 +160,35 @@ fn partition<'tcx, I>(
 
     // Merge until we don't exceed the max CGU count.
     // `merge_codegen_units` is responsible for updating the CGU size
-    // estimates.
+    // estimates. CGUs with an optimization level of their own are only
+    // merged with CGUs of the same level, as anything else would change the
+    // level their items are optimized at. They count against the max CGU
+    // count, but there is always room for one CGU with the other items.
     {
         let _prof_timer = tcx.prof.generic_activity("cgu_partitioning_merge_cgus");
-        merge_codegen_units(cx, &mut codegen_units);
+        let (opt_level_cgus, mut regular_cgus): (Vec<_>, Vec<_>) =
+            codegen_units.into_iter().partition(|cgu| cgu.opt_level().is_some());
+        let mut opt_level_cgus = merge_opt_level_codegen_units(cx, opt_level_cgus);
+        let max_codegen_units =
+            tcx.sess.codegen_units().as_usize().saturating_sub(opt_level_cgus.len()).max(1);
+        merge_codegen_units(cx, &mut regular_cgus, max_codegen_units);
+        codegen_units = regular_cgus;
+        if !opt_level_cgus.is_empty() {
+            codegen_units.append(&mut opt_level_cgus);
+            codegen_units.sort_by(|a, b| a.name().as_str().cmp(b.name().as_str()));
+        }
         debug_dump(tcx, "MERGE", &codegen_units);
     }
 
+    // Emit instances that would get the same machine code as aliases of each
+    // other. This moves items between CGUs, so it has to happen before
+    // internalization.
+    if tcx.sess.opts.unstable_opts.dedup_instances && !tcx.sess.instrument_coverage() {
+        let _prof_timer = tcx.prof.generic_activity("cgu_partitioning_dedup_instances");
+        dedup::dedup_instances(tcx, &mut codegen_units);
+        debug_dump(tcx, "DEDUP", &codegen_units);
+    }
+
     // Make as many symbols "internal" as possible, so LLVM has more freedom to
     // optimize.
     if !tcx.sess.link_dead_code() {
@@ -225,6 +249,7 @@ fn place_mono_items<'tcx, I>(cx: &PartitioningCx<'_, 'tcx>, mono_items: I) -> Pl
 
         let characteristic_def_id = characteristic_def_id_of_mono_item(cx.tcx, mono_item);
         let is_volatile = is_incremental_build && mono_item.is_generic_fn(cx.tcx);
+        let opt_level = mono_item_opt_level(cx.tcx, mono_item);
 
         let cgu_name = match characteristic_def_id {
             Some(def_id) => compute_codegen_unit_name(
@@ -232,12 +257,17 @@ fn place_mono_items<'tcx, I>(cx: &PartitioningCx<'_, 'tcx>, mono_items: I) -> Pl
                 cgu_name_builder,
                 def_id,
                 is_volatile,
+                opt_level,
                 cgu_name_cache,
             ),
             None => fallback_cgu_name(cgu_name_builder),
         };
 
-        let cgu = codegen_units.entry(cgu_name).or_insert_with(|| CodegenUnit::new(cgu_name));
+        let cgu = codegen_units.entry(cgu_name).or_insert_with(|| {
+            let mut cgu = CodegenUnit::new(cgu_name);
+            cgu.set_opt_level(opt_level);
+            cgu
+        });
 
         let mut can_be_internalized = true;
         let (linkage, visibility) = mono_item_linkage_and_visibility(
@@ -306,13 +336,51 @@ fn get_reachable_inlined_items<'tcx>(
     }
 }
 
+// Merges the CGUs of each optimization level into a single CGU named after
+// the level, so that `#[optimize(level = "...")]` adds at most one CGU per
+// level. The CGUs are expected to be sorted by name, for deterministic
+// behaviour.
+fn merge_opt_level_codegen_units<'tcx>(
+    cx: &PartitioningCx<'_, 'tcx>,
+    codegen_units: Vec<CodegenUnit<'tcx>>,
+) -> Vec<CodegenUnit<'tcx>> {
+    let cgu_name_builder = &mut CodegenUnitNameBuilder::new(cx.tcx);
+    let mut merged: Vec<(CodegenUnit<'tcx>, usize)> = Vec::new();
+    for mut cgu in codegen_units {
+        match merged.iter_mut().find(|(dst, _)| dst.opt_level() == cgu.opt_level()) {
+            Some((dst, count)) => {
+                dst.items_mut().extend(cgu.items_mut().drain(..));
+                *count += 1;
+            }
+            None => merged.push((cgu, 1)),
+        }
+    }
+    merged
+        .into_iter()
+        .map(|(mut cgu, count)| {
+            if count > 1 {
+                let opt_level = cgu.opt_level().unwrap();
+                let name = cgu_name_builder.build_cgu_name(
+                    LOCAL_CRATE,
+                    [format!("opt-{opt_level:?}")],
+                    None::<&str>,
+                );
+                cgu.set_name(name);
+                cgu.compute_size_estimate();
+            }
+            cgu
+        })
+        .collect()
+}
+
 // This function requires the CGUs to be sorted by name on input, and ensures
 // they are sorted by name on return, for deterministic behaviour.
 fn merge_codegen_units<'tcx>(
     cx: &PartitioningCx<'_, 'tcx>,
     codegen_units: &mut Vec<CodegenUnit<'tcx>>,
+    max_codegen_units: usize,
 ) {
-    assert!(cx.tcx.sess.codegen_units().as_usize() >= 1);
+    assert!(max_codegen_units >= 1);
 
     // A sorted order here ensures merging is deterministic.
     assert!(codegen_units.is_sorted_by(|a, b| a.name().as_str() <= b.name().as_str()));
@@ -335,7 +403,15 @@ fn merge_codegen_units<'tcx>(
     // getting any bigger, if we can avoid it. When we have more than N CGUs
     // then at least one of the biggest N will have to grow. codegen_units[N-1]
     // is the smallest of those, and so has the most room to grow.
-    let max_codegen_units = cx.tcx.sess.codegen_units().as_usize();
+    //
+    // The `-Zcgu-partitioning` strategy decides which CGU gets merged into
+    // codegen_units[N-1]:
+    // - `by-module` picks the one with the greatest overlap of inlined items.
+    // - `call-graph` picks the one with the most items calling, or called by,
+    //   items of codegen_units[N-1], as well as overlapping inlined items.
+    // - `size-balanced` always picks the biggest of codegen_units[N..], which
+    //   is longest-processing-time-first bin packing into N bins.
+    let strategy = cx.tcx.sess.opts.unstable_opts.cgu_partitioning;
     while codegen_units.len() > max_codegen_units {
         // Sort small CGUs to the back.
         codegen_units.sort_by_key(|cgu| cmp::Reverse(cgu.size_estimate()));
@@ -346,17 +422,24 @@ fn merge_codegen_units<'tcx>(
         // tie, favour the earlier (bigger) CGU.
         let mut max_overlap = 0;
         let mut max_overlap_i = max_codegen_units;
-        for (i, cgu_src) in codegen_units.iter().enumerate().skip(max_codegen_units) {
-            if cgu_src.size_estimate() <= max_overlap {
-                // None of the remaining overlaps can exceed `max_overlap`, so
-                // stop looking.
-                break;
-            }
+        if strategy != CguPartitioning::SizeBalanced {
+            for (i, cgu_src) in codegen_units.iter().enumerate().skip(max_codegen_units) {
+                if cgu_src.size_estimate() <= max_overlap {
+                    // None of the remaining overlaps can exceed `max_overlap`, so
+                    // stop looking.
+                    break;
+                }
 
-            let overlap = compute_inlined_overlap(cgu_dst, cgu_src);
-            if overlap > max_overlap {
-                max_overlap = overlap;
-                max_overlap_i = i;
+                let overlap = match strategy {
+                    CguPartitioning::CallGraph => compute_call_graph_affinity(cx, cgu_dst, cgu_src),
+                    CguPartitioning::ByModule | CguPartitioning::SizeBalanced => {
+                        compute_inlined_overlap(cgu_dst, cgu_src)
+                    }
+                };
+                if overlap > max_overlap {
+                    max_overlap = overlap;
+                    max_overlap_i = i;
+                }
             }
         }
 
@@ -506,6 +589,31 @@ fn compute_inlined_overlap<'tcx>(cgu1: &CodegenUnit<'tcx>, cgu2: &CodegenUnit<'t
     overlap
 }
 
+/// Compute the combined size of the items of `cgu_src` that are either inlined
+/// items also present in `cgu_dst`, or root items that call, or are called by,
+/// an item of `cgu_dst`. The result never exceeds the size of `cgu_src`.
+fn compute_call_graph_affinity<'tcx>(
+    cx: &PartitioningCx<'_, 'tcx>,
+    cgu_dst: &CodegenUnit<'tcx>,
+    cgu_src: &CodegenUnit<'tcx>,
+) -> usize {
+    let in_dst = |item: &MonoItem<'tcx>| cgu_dst.items().contains_key(item);
+
+    let mut affinity = 0;
+    for (&item, data) in cgu_src.items().iter() {
+        let is_related = if data.inlined {
+            in_dst(&item)
+        } else {
+            cx.usage_map.get_used_items(item).iter().any(in_dst)
+                || cx.usage_map.get_user_items(item).iter().any(in_dst)
+        };
+        if is_related {
+            affinity += data.size_estimate;
+        }
+    }
+    affinity
+}
+
 fn internalize_symbols<'tcx>(
     cx: &PartitioningCx<'_, 'tcx>,
     codegen_units: &mut [CodegenUnit<'tcx>],
@@ -675,6 +783,7 @@ fn compute_codegen_unit_name(
     name_builder: &mut CodegenUnitNameBuilder<'_>,
     def_id: DefId,
     volatile: bool,
+    opt_level: Option<OptLevel>,
     cache: &mut CguNameCache,
 ) -> Symbol {
     // Find the innermost module that is not nested within a function.
@@ -704,7 +813,7 @@ fn compute_codegen_unit_name(
 
     let cgu_def_id = cgu_def_id.unwrap();
 
-    *cache.entry((cgu_def_id, volatile)).or_insert_with(|| {
+    *cache.entry((cgu_def_id, volatile, opt_level)).or_insert_with(|| {
         let def_path = tcx.def_path(cgu_def_id);
 
         let components = def_path.data.iter().map(|part| match part.data.name() {
@@ -712,12 +821,34 @@ fn compute_codegen_unit_name(
             DefPathDataName::Anon { .. } => unreachable!(),
         });
 
-        let volatile_suffix = volatile.then_some("volatile");
+        let opt_level_suffix = opt_level.map(|opt_level| format!("opt-{opt_level:?}"));
+        let suffix = match (volatile, opt_level_suffix) {
+            (true, Some(opt_level_suffix)) => Some(format!("volatile-{opt_level_suffix}")),
+            (true, None) => Some("volatile".to_string()),
+            (false, opt_level_suffix) => opt_level_suffix,
+        };
 
-        name_builder.build_cgu_name(def_path.krate, components, volatile_suffix)
+        name_builder.build_cgu_name(def_path.krate, components, suffix)
     })
 }
 
+/// Returns the optimization level that an `#[optimize(level = "...")]` on `mono_item` asks for,
+/// if it needs a different LLVM pass pipeline than the rest of the crate. Such items end up in a
+/// codegen unit of their own for each level, which is optimized at that level. Levels `0`, `s`
+/// and `z` are applied with function attributes instead, and inlined items are optimized along
+/// with the codegen unit they are inlined into.
+fn mono_item_opt_level<'tcx>(tcx: TyCtxt<'tcx>, mono_item: MonoItem<'tcx>) -> Option<OptLevel> {
+    let MonoItem::Fn(ty::Instance { def: InstanceDef::Item(def_id), .. }) = mono_item else {
+        return None;
+    };
+    match tcx.codegen_fn_attrs(def_id).optimize {
+        OptimizeAttr::Level(
+            opt_level @ (OptLevel::Less | OptLevel::Default | OptLevel::Aggressive),
+        ) if opt_level != tcx.sess.opts.optimize => Some(opt_level),
+        _ => None,
+    }
+}
+
 // Anything we can't find a proper codegen unit for goes into this.
 fn fallback_cgu_name(name_builder: &mut CodegenUnitNameBuilder<'_>) -> Symbol {
     name_builder.build_cgu_name(LOCAL_CRATE, &["fallback"], Some("cgu"))
@@ -736,7 +867,7 @@ fn mono_item_linkage_and_visibility<'tcx>(
     (Linkage::External, vis)
 }
 
-type CguNameCache = FxHashMap<(DefId, bool), Symbol>;
+type CguNameCache = FxHashMap<(DefId, bool, Option<OptLevel>), Symbol>;
 
 fn static_visibility<'tcx>(
     tcx: TyCtxt<'tcx>,
@@ -1128,6 +1259,10 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
         )
     });
 
+    if tcx.sess.opts.unstable_opts.print_cgu_sizes {
+        print_cgu_sizes(tcx, codegen_units);
+    }
+
     if tcx.prof.enabled() {
         // Record CGU size estimates for self-profiling.
         for cgu in codegen_units {
@@ -1151,7 +1286,7 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
     // Output monomorphization stats per def_id
     if let SwitchWithOptPath::Enabled(ref path) = tcx.sess.opts.unstable_opts.dump_mono_stats {
         if let Err(err) =
-            dump_mono_items_stats(tcx, codegen_units, path, tcx.crate_name(LOCAL_CRATE))
+            dump_mono_items_stats(tcx, codegen_units, &usage_map, path, tcx.crate_name(LOCAL_CRATE))
         {
             tcx.dcx().emit_fatal(CouldntDumpMonoStats { error: err.to_string() });
         }
@@ -1211,11 +1346,39 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
     (tcx.arena.alloc(mono_items), codegen_units)
 }
 
-/// Outputs stats about instantiation counts and estimated size, per `MonoItem`'s
-/// def, to a file in the given output directory.
+/// Outputs the size distribution of the codegen units, for `-Zprint-cgu-sizes`.
+fn print_cgu_sizes(tcx: TyCtxt<'_>, codegen_units: &[CodegenUnit<'_>]) {
+    let strategy = tcx.sess.opts.unstable_opts.cgu_partitioning.name();
+    let sizes: Vec<_> = codegen_units.iter().map(|cgu| cgu.size_estimate()).collect();
+    let total_size: usize = sizes.iter().sum();
+    // Partitioning always produces at least one CGU.
+    let min_size = sizes.iter().min().unwrap();
+    let max_size = sizes.iter().max().unwrap();
+    let mean_size = total_size as f64 / sizes.len() as f64;
+
+    println!(
+        "print-cgu-size strategy: `{strategy}`, codegen units: {}, total size: {total_size}, \
+         min size: {min_size}, max size: {max_size}, mean size: {mean_size:.1}",
+        sizes.len(),
+    );
+    for cgu in codegen_units {
+        let inlined_items = cgu.items().values().filter(|data| data.inlined).count();
+        println!(
+            "print-cgu-size     cgu: `{}`, size: {}, items: {} ({inlined_items} inlined)",
+            cgu.name(),
+            cgu.size_estimate(),
+            cgu.items().len(),
+        );
+    }
+}
+
+/// Outputs stats about instantiation counts, estimated size and codegen unit placement, per
+/// `MonoItem`'s def, to a file in the given output directory. With `-Zdump-mono-stats-users`, the
+/// items that caused each instantiation are listed as well.
 fn dump_mono_items_stats<'tcx>(
     tcx: TyCtxt<'tcx>,
     codegen_units: &[CodegenUnit<'tcx>],
+    usage_map: &UsageMap<'tcx>,
     output_directory: &Option<PathBuf>,
     crate_name: Symbol,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -1233,15 +1396,21 @@ fn dump_mono_items_stats<'tcx>(
     let file = File::create(&output_path)?;
     let mut file = BufWriter::new(file);
 
-    // Gather instantiated mono items grouped by def_id
-    let mut items_per_def_id: FxHashMap<_, Vec<_>> = Default::default();
+    // Gather instantiated mono items grouped by def_id, along with the codegen units they were
+    // placed in. Inlined items can be placed in more than one codegen unit.
+    let mut items_per_def_id: FxHashMap<_, FxHashMap<_, Vec<_>>> = Default::default();
     for cgu in codegen_units {
         cgu.items()
             .keys()
             // Avoid variable-sized compiler-generated shims
             .filter(|mono_item| mono_item.is_user_defined())
             .for_each(|mono_item| {
-                items_per_def_id.entry(mono_item.def_id()).or_default().push(mono_item);
+                items_per_def_id
+                    .entry(mono_item.def_id())
+                    .or_default()
+                    .entry(*mono_item)
+                    .or_default()
+                    .push(cgu.name());
             });
     }
 
@@ -1249,19 +1418,68 @@ fn dump_mono_items_stats<'tcx>(
     struct MonoItem {
         name: String,
         instantiation_count: usize,
+        /// The average size estimate of the instances.
         size_estimate: usize,
         total_estimate: usize,
+        /// The number of MIR statements of all the instances.
+        mir_statements: usize,
+        codegen_units: Vec<String>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        instantiations: Vec<Instantiation>,
     }
 
+    #[derive(serde::Serialize)]
+    struct Instantiation {
+        name: String,
+        used_by: Vec<String>,
+    }
+
+    let dump_users = tcx.sess.opts.unstable_opts.dump_mono_stats_users;
+
     // Output stats sorted by total instantiated size, from heaviest to lightest
     let mut stats: Vec<_> = items_per_def_id
         .into_iter()
         .map(|(def_id, items)| {
             let name = with_no_trimmed_paths!(tcx.def_path_str(def_id));
             let instantiation_count = items.len();
-            let size_estimate = items[0].size_estimate(tcx);
-            let total_estimate = instantiation_count * size_estimate;
-            MonoItem { name, instantiation_count, size_estimate, total_estimate }
+            let total_estimate: usize = items.keys().map(|item| item.size_estimate(tcx)).sum();
+            let size_estimate = total_estimate / instantiation_count;
+            let mir_statements = items.keys().map(|item| mir_statement_count(tcx, item)).sum();
+
+            let mut codegen_units: Vec<_> =
+                items.values().flatten().map(|cgu_name| cgu_name.to_string()).collect();
+            codegen_units.sort_unstable();
+            codegen_units.dedup();
+
+            let mut instantiations: Vec<_> = if dump_users {
+                items
+                    .keys()
+                    .map(|&item| {
+                        let name = with_no_trimmed_paths!(item.to_string());
+                        let mut used_by: Vec<_> = usage_map
+                            .get_user_items(item)
+                            .iter()
+                            .map(|user| with_no_trimmed_paths!(user.to_string()))
+                            .collect();
+                        used_by.sort_unstable();
+                        used_by.dedup();
+                        Instantiation { name, used_by }
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            instantiations.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+            MonoItem {
+                name,
+                instantiation_count,
+                size_estimate,
+                total_estimate,
+                mir_statements,
+                codegen_units,
+                instantiations,
+            }
         })
         .collect();
     stats.sort_unstable_by_key(|item| cmp::Reverse(item.total_estimate));
@@ -1272,16 +1490,40 @@ struct MonoItem {
             DumpMonoStatsFormat::Markdown => {
                 writeln!(
                     file,
-                    "| Item | Instantiation count | Estimated Cost Per Instantiation | Total Estimated Cost |"
+                    "| Item | Instantiation count | Average Estimated Cost Per Instantiation | Total Estimated Cost | Total MIR Statements | Codegen Units |"
                 )?;
-                writeln!(file, "| --- | ---: | ---: | ---: |")?;
-
-                for MonoItem { name, instantiation_count, size_estimate, total_estimate } in stats {
+                writeln!(file, "| --- | ---: | ---: | ---: | ---: | --- |")?;
+
+                for MonoItem {
+                    name,
+                    instantiation_count,
+                    size_estimate,
+                    total_estimate,
+                    mir_statements,
+                    codegen_units,
+                    ..
+                } in &stats
+                {
+                    let codegen_units = codegen_units.join(", ");
                     writeln!(
                         file,
-                        "| `{name}` | {instantiation_count} | {size_estimate} | {total_estimate} |"
+                        "| `{name}` | {instantiation_count} | {size_estimate} | {total_estimate} | {mir_statements} | {codegen_units} |"
                     )?;
                 }
+
+                for MonoItem { name, instantiations, .. } in &stats {
+                    if instantiations.is_empty() {
+                        continue;
+                    }
+
+                    writeln!(file, "\n## `{name}`\n")?;
+                    for Instantiation { name, used_by } in instantiations {
+                        writeln!(file, "- `{name}`")?;
+                        for user in used_by {
+                            writeln!(file, "  - used by `{user}`")?;
+                        }
+                    }
+                }
             }
         }
     }
@@ -1289,6 +1531,16 @@ struct MonoItem {
     Ok(())
 }
 
+/// The number of MIR statements in the body of a mono item, or 0 if it has no MIR body.
+fn mir_statement_count<'tcx>(tcx: TyCtxt<'tcx>, item: &MonoItem<'tcx>) -> usize {
+    match *item {
+        MonoItem::Fn(instance) => {
+            tcx.instance_mir(instance.def).basic_blocks.iter().map(|bb| bb.statements.len()).sum()
+        }
+        MonoItem::Static(_) | MonoItem::GlobalAsm(_) => 0,
+    }
+}
+
 pub fn provide(providers: &mut Providers) {
     providers.collect_and_partition_mono_items = collect_and_partition_mono_items;
 
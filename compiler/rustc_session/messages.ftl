@@ -72,6 +72,8 @@ session_invalid_num_literal_suffix = invalid suffix `{$suffix}` for number liter
     .label = invalid suffix `{$suffix}`
     .help = the suffix must be one of the numeric types (`u32`, `isize`, `f32`, etc.)
 
+session_linker_flavor_requires_elf = linker flavor `{$flavor}` can only be used on targets producing ELF files
+
 session_linker_plugin_lto_windows_not_supported = linker plugin based LTO is not supported together with `-C prefer-dynamic` when targeting Windows-like targets
 
 session_not_circumvent_feature = `-Zunleash-the-miri-inside-of-you` may not be used to circumvent feature gates, except when testing error paths in the CTFE engine
@@ -86,6 +88,13 @@ session_profile_sample_use_file_does_not_exist = file `{$path}` passed to `-C pr
 
 session_profile_use_file_does_not_exist = file `{$path}` passed to `-C profile-use` does not exist.
 
+session_profile_use_no_raw_profiles = directory `{$path}` passed to `-C profile-use` contains no `.profraw` files
+    .help = run a program built with `-C profile-generate={$path}` to collect profiles first
+
+session_profile_use_raw_requires_unstable = passing raw profiles to `-C profile-use` is unstable
+    .note = `{$path}` is a raw `.profraw` profile or a directory of them
+    .help = add `-Z unstable-options` or merge the profiles with `llvm-profdata merge` first
+
 session_sanitizer_cfi_canonical_jump_tables_requires_cfi = `-Zsanitizer-cfi-canonical-jump-tables` requires `-Zsanitizer=cfi`
 
 session_sanitizer_cfi_generalize_pointers_requires_cfi = `-Zsanitizer-cfi-generalize-pointers` requires `-Zsanitizer=cfi` or `-Zsanitizer=kcfi`
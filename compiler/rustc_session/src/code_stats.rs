@@ -59,6 +59,19 @@ pub enum DataTypeKind {
     Coroutine,
 }
 
+/// How much room the saved locals of a coroutine take up once they have been
+/// overlapped with each other in the coroutine layout.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LocalsOverlapInfo {
+    /// Sum of the sizes of all distinct saved locals.
+    pub total_size: u64,
+    /// Number of bytes actually occupied by those locals.
+    pub overlapped_size: u64,
+    /// For each suspension point variant, the number of bytes of the locals
+    /// saved there that are shared with locals not live at that point.
+    pub per_variant: Vec<(Symbol, u64)>,
+}
+
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub struct TypeSizeInfo {
     pub kind: DataTypeKind,
@@ -68,6 +81,7 @@ pub struct TypeSizeInfo {
     pub packed: bool,
     pub opt_discr_size: Option<u64>,
     pub variants: Vec<VariantInfo>,
+    pub opt_locals_overlap: Option<LocalsOverlapInfo>,
 }
 
 pub struct VTableSizeInfo {
@@ -105,6 +119,7 @@ pub fn record_type_size<S: ToString>(
         packed: bool,
         opt_discr_size: Option<Size>,
         mut variants: Vec<VariantInfo>,
+        opt_locals_overlap: Option<LocalsOverlapInfo>,
     ) {
         // Sort variants so the largest ones are shown first. A stable sort is
         // used here so that source code order is preserved for all variants
@@ -122,6 +137,7 @@ pub fn record_type_size<S: ToString>(
             packed,
             opt_discr_size: opt_discr_size.map(|s| s.bytes()),
             variants,
+            opt_locals_overlap,
         };
         self.type_sizes.borrow_mut().insert(info);
     }
@@ -228,6 +244,27 @@ pub fn print_type_sizes(&self) {
 
                     min_offset = offset + size;
                 }
+
+                if let Some(overlap) = &info.opt_locals_overlap {
+                    let shared = overlap.per_variant.iter().find(|(v, _)| Some(*v) == *name);
+                    if let Some(&(_, shared @ 1..)) = shared {
+                        println!(
+                            "print-type-size {indent}overlapped with locals not live here: \
+                                  {shared} bytes"
+                        );
+                    }
+                }
+            }
+
+            if let Some(LocalsOverlapInfo { total_size, overlapped_size, .. }) =
+                info.opt_locals_overlap
+            {
+                if overlapped_size < total_size {
+                    println!(
+                        "print-type-size {indent}coroutine locals: {total_size} bytes, \
+                              overlapped into {overlapped_size} bytes"
+                    );
+                }
             }
 
             match overall_size.checked_sub(max_variant_size) {
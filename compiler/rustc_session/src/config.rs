@@ -5,6 +5,7 @@
 
 pub use crate::options::*;
 
+use crate::config::lint_config::LintConfig;
 use crate::errors::FileWriteFail;
 use crate::search_paths::SearchPath;
 use crate::utils::{CanonicalizedPath, NativeLib, NativeLibKind};
@@ -36,6 +37,7 @@
 use std::str::{self, FromStr};
 use std::sync::LazyLock;
 
+pub mod lint_config;
 pub mod sigpipe;
 
 /// The different settings that the `-C strip` flag can have.
@@ -80,7 +82,7 @@ pub enum CFProtection {
     Full,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Hash, HashStable_Generic)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, HashStable_Generic, Encodable, Decodable)]
 pub enum OptLevel {
     No,         // -O0
     Less,       // -O1
@@ -410,6 +412,10 @@ pub enum OutputType {
     Object,
     Exe,
     DepInfo,
+    /// A report of the symbols that survived linking, based on the linker's map file.
+    LinkMap,
+    /// A report of the stack usage of each function, based on the stack sizes in object files.
+    StackSizes,
 }
 
 // Safety: Trivial C-Style enums have a stable sort order across compilation sessions.
@@ -428,7 +434,11 @@ fn to_stable_hash_key(&self, _: &HCX) -> Self::KeyType {
 impl OutputType {
     fn is_compatible_with_codegen_units_and_single_output_file(&self) -> bool {
         match *self {
-            OutputType::Exe | OutputType::DepInfo | OutputType::Metadata => true,
+            OutputType::Exe
+            | OutputType::DepInfo
+            | OutputType::Metadata
+            | OutputType::LinkMap
+            | OutputType::StackSizes => true,
             OutputType::Bitcode
             | OutputType::Assembly
             | OutputType::LlvmAssembly
@@ -447,6 +457,8 @@ pub fn shorthand(&self) -> &'static str {
             OutputType::Metadata => "metadata",
             OutputType::Exe => "link",
             OutputType::DepInfo => "dep-info",
+            OutputType::LinkMap => "link-map",
+            OutputType::StackSizes => "stack-sizes",
         }
     }
 
@@ -460,13 +472,15 @@ fn from_shorthand(shorthand: &str) -> Option<Self> {
             "metadata" => OutputType::Metadata,
             "link" => OutputType::Exe,
             "dep-info" => OutputType::DepInfo,
+            "link-map" => OutputType::LinkMap,
+            "stack-sizes" => OutputType::StackSizes,
             _ => return None,
         })
     }
 
     fn shorthands_display() -> String {
         format!(
-            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
+            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
             OutputType::Bitcode.shorthand(),
             OutputType::Assembly.shorthand(),
             OutputType::LlvmAssembly.shorthand(),
@@ -475,6 +489,8 @@ fn shorthands_display() -> String {
             OutputType::Metadata.shorthand(),
             OutputType::Exe.shorthand(),
             OutputType::DepInfo.shorthand(),
+            OutputType::LinkMap.shorthand(),
+            OutputType::StackSizes.shorthand(),
         )
     }
 
@@ -487,6 +503,8 @@ pub fn extension(&self) -> &'static str {
             OutputType::Object => "o",
             OutputType::Metadata => "rmeta",
             OutputType::DepInfo => "d",
+            OutputType::LinkMap => "link-map",
+            OutputType::StackSizes => "stack-sizes",
             OutputType::Exe => "",
         }
     }
@@ -496,7 +514,9 @@ pub fn is_text_output(&self) -> bool {
             OutputType::Assembly
             | OutputType::LlvmAssembly
             | OutputType::Mir
-            | OutputType::DepInfo => true,
+            | OutputType::DepInfo
+            | OutputType::LinkMap
+            | OutputType::StackSizes => true,
             OutputType::Bitcode | OutputType::Object | OutputType::Metadata | OutputType::Exe => {
                 false
             }
@@ -586,7 +606,9 @@ pub fn should_codegen(&self) -> bool {
             | OutputType::LlvmAssembly
             | OutputType::Mir
             | OutputType::Object
-            | OutputType::Exe => true,
+            | OutputType::Exe
+            | OutputType::LinkMap
+            | OutputType::StackSizes => true,
             OutputType::Metadata | OutputType::DepInfo => false,
         })
     }
@@ -600,8 +622,9 @@ pub fn should_link(&self) -> bool {
             | OutputType::Mir
             | OutputType::Metadata
             | OutputType::Object
-            | OutputType::DepInfo => false,
-            OutputType::Exe => true,
+            | OutputType::DepInfo
+            | OutputType::StackSizes => false,
+            OutputType::Exe | OutputType::LinkMap => true,
         })
     }
 }
@@ -710,6 +733,7 @@ pub enum PrintKind {
     TlsModels,
     TargetSpec,
     AllTargetSpecs,
+    TargetSpecJsonSchema,
     NativeStaticLibs,
     StackProtectorStrategies,
     LinkArgs,
@@ -1037,6 +1061,8 @@ fn default() -> Options {
             debuginfo_compression: DebugInfoCompression::None,
             lint_opts: Vec::new(),
             lint_cap: None,
+            lint_config: None,
+            check_semver_against: None,
             describe_lints: false,
             output_types: OutputTypes(BTreeMap::new()),
             search_paths: vec![],
@@ -1708,8 +1734,8 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
             "Compiler information to print on stdout",
             "[crate-name|file-names|sysroot|target-libdir|cfg|calling-conventions|\
              target-list|target-cpus|target-features|relocation-models|code-models|\
-             tls-models|target-spec-json|all-target-specs-json|native-static-libs|\
-             stack-protector-strategies|link-args|deployment-target]",
+             tls-models|target-spec-json|all-target-specs-json|target-spec-json-schema|\
+             native-static-libs|stack-protector-strategies|link-args|deployment-target]",
         ),
         opt::flagmulti_s("g", "", "Equivalent to -C debuginfo=2"),
         opt::flagmulti_s("O", "", "Equivalent to -C opt-level=2"),
@@ -1793,6 +1819,13 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "FROM=TO",
         ),
         opt::multi("", "env-set", "Inject an environment variable", "VAR=VALUE"),
+        opt::opt("", "lint-config", "Read lint levels and parameters from a TOML file", "PATH"),
+        opt::opt(
+            "",
+            "check-semver-against",
+            "Compare the public API with a previous version of the crate",
+            "PATH",
+        ),
     ]);
     opts
 }
@@ -2032,10 +2065,23 @@ fn parse_output_types(
                         display = OutputType::shorthands_display(),
                     ))
                 });
+                if matches!(output_type, OutputType::LinkMap | OutputType::StackSizes)
+                    && !unstable_opts.unstable_options
+                {
+                    early_dcx.early_fatal(format!(
+                        "the `{}` emission type is unstable, \
+                        the `-Z unstable-options` flag must also be passed to use it",
+                        output_type.shorthand(),
+                    ));
+                }
                 output_types.insert(output_type, path);
             }
         }
     };
+    // The link map is written while linking, so it implies `--emit=link`.
+    if output_types.contains_key(&OutputType::LinkMap) {
+        output_types.entry(OutputType::Exe).or_insert(None);
+    }
     if output_types.is_empty() {
         output_types.insert(OutputType::Exe, None);
     }
@@ -2131,6 +2177,7 @@ fn collect_print_requests(
         ("target-libdir", PrintKind::TargetLibdir),
         ("target-list", PrintKind::TargetList),
         ("target-spec-json", PrintKind::TargetSpec),
+        ("target-spec-json-schema", PrintKind::TargetSpecJsonSchema),
         ("tls-models", PrintKind::TlsModels),
         // tidy-alphabetical-end
     ];
@@ -2165,6 +2212,16 @@ fn collect_print_requests(
                     );
                 }
             }
+            Some((_, PrintKind::TargetSpecJsonSchema)) => {
+                if unstable_opts.unstable_options {
+                    PrintKind::TargetSpecJsonSchema
+                } else {
+                    early_dcx.early_fatal(
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the target-spec-json-schema print option",
+                    );
+                }
+            }
             Some(&(_, print_kind)) => print_kind,
             None => {
                 let prints =
@@ -2580,6 +2637,20 @@ fn parse_logical_env(
     vars
 }
 
+fn parse_lint_config(early_dcx: &EarlyDiagCtxt, matches: &getopts::Matches) -> Option<LintConfig> {
+    let path = PathBuf::from(matches.opt_str("lint-config")?);
+    let contents = fs::read_to_string(&path).unwrap_or_else(|err| {
+        early_dcx.early_fatal(format!(
+            "failed to read the lint configuration file `{}`: {err}",
+            path.display()
+        ))
+    });
+    let display = path.display().to_string();
+    Some(LintConfig::parse(path, &contents).unwrap_or_else(|err| {
+        early_dcx.early_fatal(format!("invalid lint configuration file `{display}`: {err}"))
+    }))
+}
+
 // JUSTIFICATION: before wrapper fn is available
 #[allow(rustc::bad_opt_access)]
 pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::Matches) -> Options {
@@ -2608,6 +2679,8 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
 
     let mut unstable_opts = UnstableOptions::build(early_dcx, matches);
     let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(early_dcx, matches);
+    let lint_config = parse_lint_config(early_dcx, matches);
+    let check_semver_against = matches.opt_str("check-semver-against").map(PathBuf::from);
 
     check_error_format_stability(early_dcx, &unstable_opts, error_format);
 
@@ -2857,6 +2930,8 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
         debuginfo_compression,
         lint_opts,
         lint_cap,
+        lint_config,
+        check_semver_against,
         describe_lints,
         output_types,
         search_paths,
@@ -3155,9 +3230,10 @@ pub enum WasiExecModel {
 /// we have an opt-in scheme here, so one is hopefully forced to think about
 /// how the hash should be calculated when adding a new command-line argument.
 pub(crate) mod dep_tracking {
+    use super::lint_config::LintConfig;
     use super::{
-        BranchProtection, CFGuard, CFProtection, CollapseMacroDebuginfo, CoverageOptions,
-        CrateType, DebugInfo, DebugInfoCompression, ErrorOutputType, FunctionReturn,
+        BranchProtection, CFGuard, CFProtection, CguPartitioning, CollapseMacroDebuginfo,
+        CoverageOptions, CrateType, DebugInfo, DebugInfoCompression, ErrorOutputType, FunctionReturn,
         InliningThreshold, InstrumentCoverage, InstrumentXRay, LinkerPluginLto, LocationDetail,
         LtoCli, NextSolverConfig, OomStrategy, OptLevel, OutFileName, OutputType, OutputTypes,
         Polonius, RemapPathScopeComponents, ResolveDocLinks, SourceFileHashAlgorithm,
@@ -3225,6 +3301,7 @@ fn hash(
         String,
         PathBuf,
         lint::Level,
+        LintConfig,
         WasiExecModel,
         u32,
         RelocModel,
@@ -3269,6 +3346,7 @@ fn hash(
         Polonius,
         InliningThreshold,
         FunctionReturn,
+        CguPartitioning,
     );
 
     impl<T1, T2> DepTrackingHash for (T1, T2)
@@ -3442,6 +3520,33 @@ pub fn extension(self) -> &'static str {
     }
 }
 
+/// The strategy used by `-Zcgu-partitioning` to merge codegen units.
+#[derive(Clone, Copy, PartialEq, Hash, Debug, Default)]
+pub enum CguPartitioning {
+    /// Place items into one codegen unit per source-level module, then merge the units with the
+    /// most inlined items in common.
+    #[default]
+    ByModule,
+
+    /// Place items like `ByModule`, then bin-pack the units so that the merged units have
+    /// similar sizes.
+    SizeBalanced,
+
+    /// Place items like `ByModule`, then merge the units with the most calls between them, so
+    /// callers and callees end up in the same unit and can be inlined without LTO.
+    CallGraph,
+}
+
+impl CguPartitioning {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ByModule => "by-module",
+            Self::SizeBalanced => "size-balanced",
+            Self::CallGraph => "call-graph",
+        }
+    }
+}
+
 /// `-Zpolonius` values, enabling the borrow checker polonius analysis, and which version: legacy,
 /// or future prototype.
 #[derive(Clone, Copy, PartialEq, Hash, Debug, Default)]
@@ -0,0 +1,229 @@
+//! The lint configuration file given with `--lint-config`.
+//!
+//! The file is written in TOML. Its `[lints]` table sets the level of lints for the whole crate,
+//! and optionally their parameters. Each `[[overrides]]` entry sets the level of lints for the
+//! modules whose path, or whose source file, matches one of its glob patterns:
+//!
+//! ```toml
+//! [lints]
+//! unsafe_code = "deny"
+//! large_assignments = { level = "warn", limit = 4096 }
+//!
+//! [[overrides]]
+//! modules = ["bindings", "bindings::**"]
+//! files = ["src/generated/**"]
+//! lints = { missing_docs = "allow", non_camel_case_types = "allow" }
+//! ```
+//!
+//! The command-line flags take precedence over the `[lints]` table. The overrides take
+//! precedence over both, and lint attributes in the source code over everything.
+
+use rustc_lint_defs::Level;
+use std::path::PathBuf;
+
+/// The parameters that lints accept, with the type of their values.
+const LINT_PARAMS: &[(&str, &str, LintParamKind)] =
+    &[("large_assignments", "limit", LintParamKind::Integer)];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LintParamKind {
+    Integer,
+}
+
+/// The value of a lint parameter.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum LintParam {
+    Integer(u64),
+}
+
+/// The level of a lint, with its name as written in the configuration file.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LintConfigLevel {
+    pub name: String,
+    pub level: Level,
+}
+
+/// A set of lint levels that only applies to some modules.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LintConfigOverride {
+    /// Glob patterns for the paths of modules, relative to the crate root.
+    pub modules: Vec<String>,
+    /// Glob patterns for the paths of source files.
+    pub files: Vec<String>,
+    pub levels: Vec<LintConfigLevel>,
+}
+
+impl LintConfigOverride {
+    /// Returns the first pattern that matches a module, given the path of the module and of the
+    /// source file of its contents.
+    pub fn matching_pattern(&self, module_path: &str, file: &str) -> Option<&str> {
+        let module = self.modules.iter().find(|pattern| glob_matches(pattern, module_path, "::"));
+        let file_pattern = || self.files.iter().find(|pattern| glob_matches(pattern, file, "/"));
+        module.or_else(file_pattern).map(|pattern| pattern.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LintConfig {
+    pub path: PathBuf,
+    /// The levels of the `[lints]` table.
+    pub levels: Vec<LintConfigLevel>,
+    /// The parameters of the `[lints]` table, as `(lint, parameter, value)`.
+    pub params: Vec<(String, String, LintParam)>,
+    pub overrides: Vec<LintConfigOverride>,
+}
+
+impl LintConfig {
+    pub fn parse(path: PathBuf, contents: &str) -> Result<LintConfig, String> {
+        let table = match contents.parse::<toml::Value>() {
+            Ok(toml::Value::Table(table)) => table,
+            Ok(_) => return Err("expected a table".to_owned()),
+            Err(err) => return Err(err.to_string()),
+        };
+        let mut config =
+            LintConfig { path, levels: Vec::new(), params: Vec::new(), overrides: Vec::new() };
+        for (key, value) in table {
+            match key.as_str() {
+                "lints" => {
+                    for (name, value) in as_table(&value, "lints")? {
+                        let (level, params) = match value {
+                            toml::Value::Table(table) => {
+                                let mut level = None;
+                                let mut params = Vec::new();
+                                for (key, value) in table {
+                                    if key == "level" {
+                                        level = Some(parse_level(value, name)?);
+                                    } else {
+                                        params.push((key.clone(), parse_param(name, key, value)?));
+                                    }
+                                }
+                                (level, params)
+                            }
+                            _ => (Some(parse_level(value, name)?), Vec::new()),
+                        };
+                        if let Some(level) = level {
+                            config.levels.push(LintConfigLevel { name: name.clone(), level });
+                        }
+                        config.params.extend(
+                            params.into_iter().map(|(key, param)| (name.clone(), key, param)),
+                        );
+                    }
+                }
+                "overrides" => {
+                    let toml::Value::Array(overrides) = value else {
+                        return Err("`overrides` must be an array of tables".to_owned());
+                    };
+                    for value in &overrides {
+                        config.overrides.push(parse_override(value)?);
+                    }
+                }
+                _ => return Err(format!("unknown key `{key}`")),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Returns the value of a lint parameter, if the configuration file sets it.
+    pub fn param(&self, lint: &str, param: &str) -> Option<&LintParam> {
+        self.params.iter().find(|(l, p, _)| l == lint && p == param).map(|(_, _, value)| value)
+    }
+}
+
+fn as_table<'a>(value: &'a toml::Value, key: &str) -> Result<&'a toml::value::Table, String> {
+    value.as_table().ok_or_else(|| format!("`{key}` must be a table"))
+}
+
+fn parse_level(value: &toml::Value, lint: &str) -> Result<Level, String> {
+    value.as_str().and_then(Level::from_str).ok_or_else(|| {
+        format!(
+            "the level of `{lint}` must be one of \
+            \"allow\", \"warn\", \"deny\" or \"forbid\""
+        )
+    })
+}
+
+fn parse_param(lint: &str, param: &str, value: &toml::Value) -> Result<LintParam, String> {
+    let Some(&(_, _, kind)) = LINT_PARAMS.iter().find(|&&(l, p, _)| l == lint && p == param) else {
+        return Err(format!("unknown parameter `{param}` for the lint `{lint}`"));
+    };
+    match (kind, value) {
+        (LintParamKind::Integer, toml::Value::Integer(value)) if *value >= 0 => {
+            Ok(LintParam::Integer(*value as u64))
+        }
+        (LintParamKind::Integer, _) => {
+            Err(format!("the parameter `{param}` of `{lint}` must be a non-negative integer"))
+        }
+    }
+}
+
+fn parse_override(value: &toml::Value) -> Result<LintConfigOverride, String> {
+    let mut over =
+        LintConfigOverride { modules: Vec::new(), files: Vec::new(), levels: Vec::new() };
+    for (key, value) in as_table(value, "overrides")? {
+        match key.as_str() {
+            "modules" | "files" => {
+                let patterns = value
+                    .as_array()
+                    .and_then(|patterns| {
+                        patterns.iter().map(|p| p.as_str().map(str::to_owned)).collect()
+                    })
+                    .ok_or_else(|| format!("`{key}` must be an array of strings"))?;
+                if key == "modules" {
+                    over.modules = patterns;
+                } else {
+                    over.files = patterns;
+                }
+            }
+            "lints" => {
+                for (name, value) in as_table(value, "lints")? {
+                    let level = parse_level(value, name)?;
+                    over.levels.push(LintConfigLevel { name: name.clone(), level });
+                }
+            }
+            _ => return Err(format!("unknown key `{key}` in `overrides`")),
+        }
+    }
+    if over.modules.is_empty() && over.files.is_empty() {
+        return Err("overrides must have `modules` or `files` patterns".to_owned());
+    }
+    Ok(over)
+}
+
+/// Matches a path against a glob pattern, both made of segments separated by `separator`.
+/// In a segment, `*` matches any sequence of characters and `?` any character. The `**` segment
+/// matches any number of segments.
+pub fn glob_matches(pattern: &str, path: &str, separator: &str) -> bool {
+    fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => path.is_empty(),
+            Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+            Some((segment, rest)) => match path.split_first() {
+                Some((first, path_rest)) => {
+                    let segment: Vec<_> = segment.chars().collect();
+                    let first: Vec<_> = first.chars().collect();
+                    segment_matches(&segment, &first) && segments_match(rest, path_rest)
+                }
+                None => false,
+            },
+        }
+    }
+
+    fn segment_matches(pattern: &[char], segment: &[char]) -> bool {
+        match pattern.split_first() {
+            None => segment.is_empty(),
+            Some(('*', rest)) => {
+                (0..=segment.len()).any(|skip| segment_matches(rest, &segment[skip..]))
+            }
+            Some((&c, rest)) => match segment.split_first() {
+                Some((&first, segment_rest)) => {
+                    (c == '?' || c == first) && segment_matches(rest, segment_rest)
+                }
+                None => false,
+            },
+        }
+    }
+
+    let pattern: Vec<_> = pattern.split(separator).collect();
+    let path: Vec<_> = if path.is_empty() { Vec::new() } else { path.split(separator).collect() };
+    segments_match(&pattern, &path)
+}
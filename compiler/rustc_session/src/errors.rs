@@ -86,6 +86,21 @@ pub(crate) struct ProfileUseFileDoesNotExist<'a> {
     pub(crate) path: &'a std::path::Path,
 }
 
+#[derive(Diagnostic)]
+#[diag(session_profile_use_raw_requires_unstable)]
+#[note]
+#[help]
+pub(crate) struct ProfileUseRawRequiresUnstable<'a> {
+    pub(crate) path: &'a std::path::Path,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_profile_use_no_raw_profiles)]
+#[help]
+pub(crate) struct ProfileUseNoRawProfiles<'a> {
+    pub(crate) path: &'a std::path::Path,
+}
+
 #[derive(Diagnostic)]
 #[diag(session_profile_sample_use_file_does_not_exist)]
 pub(crate) struct ProfileSampleUseFileDoesNotExist<'a> {
@@ -459,6 +474,12 @@ pub(crate) struct IncompatibleLinkerFlavor {
     pub(crate) compatible_list: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(session_linker_flavor_requires_elf)]
+pub(crate) struct LinkerFlavorRequiresElf {
+    pub(crate) flavor: &'static str,
+}
+
 #[derive(Diagnostic)]
 #[diag(session_function_return_requires_x86_or_x86_64)]
 pub(crate) struct FunctionReturnRequiresX86OrX8664;
@@ -1,3 +1,4 @@
+use crate::config::lint_config::LintConfig;
 use crate::config::*;
 
 use crate::search_paths::SearchPath;
@@ -143,6 +144,8 @@ pub struct Options {
         debuginfo_compression: DebugInfoCompression [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED_NO_CRATE_HASH],
         lint_cap: Option<lint::Level> [TRACKED_NO_CRATE_HASH],
+        lint_config: Option<LintConfig> [TRACKED_NO_CRATE_HASH],
+        check_semver_against: Option<PathBuf> [TRACKED_NO_CRATE_HASH],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         search_paths: Vec<SearchPath> [UNTRACKED],
@@ -439,6 +442,8 @@ mod desc {
         "either a boolean (`yes`, `no`, `on`, `off`, etc), or a non-negative number";
     pub const parse_llvm_module_flag: &str = "<key>:<type>:<value>:<behavior>. Type must currently be `u32`. Behavior should be one of (`error`, `warning`, `require`, `override`, `append`, `appendunique`, `max`, `min`)";
     pub const parse_function_return: &str = "`keep` or `thunk-extern`";
+    pub const parse_cgu_partitioning: &str =
+        "`by-module` (default), `size-balanced` or `call-graph`";
 }
 
 mod parse {
@@ -1415,6 +1420,16 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         }
         true
     }
+
+    pub(crate) fn parse_cgu_partitioning(slot: &mut CguPartitioning, v: Option<&str>) -> bool {
+        match v {
+            Some("by-module") => *slot = CguPartitioning::ByModule,
+            Some("size-balanced") => *slot = CguPartitioning::SizeBalanced,
+            Some("call-graph") => *slot = CguPartitioning::CallGraph,
+            _ => return false,
+        }
+        true
+    }
 }
 
 options! {
@@ -1573,6 +1588,9 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "set options for branch target identification and pointer authentication on AArch64"),
     cf_protection: CFProtection = (CFProtection::None, parse_cfprotection, [TRACKED],
         "instrument control-flow architecture protection"),
+    cgu_partitioning: CguPartitioning = (CguPartitioning::default(), parse_cgu_partitioning, [TRACKED],
+        "strategy used to merge codegen units: `by-module` (default), `size-balanced` or \
+        `call-graph`"),
     check_cfg_all_expected: bool = (false, parse_bool, [UNTRACKED],
         "show all expected values in check-cfg diagnostics (default: no)"),
     codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED],
@@ -1582,6 +1600,10 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "set option to collapse debuginfo for macros"),
     combine_cgu: bool = (false, parse_bool, [TRACKED],
         "combine CGUs into a single one"),
+    coroutine_refined_layout: bool = (false, parse_bool, [TRACKED],
+        "let always-live coroutine locals that are never borrowed overlap with other saved \
+        locals when their storage is not required at the same time, e.g. after being moved \
+        out of (default: no)"),
     coverage_options: CoverageOptions = (CoverageOptions::default(), parse_coverage_options, [TRACKED],
         "control details of coverage instrumentation"),
     crate_attr: Vec<String> = (Vec::new(), parse_string_push, [TRACKED],
@@ -1594,6 +1616,9 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "emit line numbers debug info inside macros (default: no)"),
     debuginfo_compression: DebugInfoCompression = (DebugInfoCompression::None, parse_debuginfo_compression, [TRACKED],
         "compress debug info sections (none, zlib, zstd, default: none)"),
+    dedup_instances: bool = (false, parse_bool, [TRACKED],
+        "emit instances of a generic function that only differ in the layout of their type \
+        arguments as aliases of a single body (default: no)"),
     deduplicate_diagnostics: bool = (true, parse_bool, [UNTRACKED],
         "deduplicate identical diagnostics (default: yes)"),
     default_hidden_visibility: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1629,6 +1654,11 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "output statistics about monomorphization collection"),
     dump_mono_stats_format: DumpMonoStatsFormat = (DumpMonoStatsFormat::Markdown, parse_dump_mono_stats, [UNTRACKED],
         "the format to use for -Z dump-mono-stats (`markdown` (default) or `json`)"),
+    dump_mono_stats_users: bool = (false, parse_bool, [UNTRACKED],
+        "include the items that caused each instantiation in -Z dump-mono-stats (default: no)"),
+    dump_solver_proof_trees: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the proof tree of every goal of the next trait solver as an HTML document \
+        in this directory"),
     dwarf_version: Option<u32> = (None, parse_opt_number, [TRACKED],
         "version of DWARF debug information to emit (default: 2 or 4, depending on platform)"),
     dylib_lto: bool = (false, parse_bool, [UNTRACKED],
@@ -1663,6 +1693,8 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "replace returns with jumps to `__x86_return_thunk` (default: `keep`)"),
     function_sections: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "whether each function should go in its own section"),
+    future_size_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "the size at which the `large_futures` lint starts to be emitted"),
     future_incompat_test: bool = (false, parse_bool, [UNTRACKED],
         "forces all lints to be future incompatible, used for internal testing (default: no)"),
     graphviz_dark_mode: bool = (false, parse_bool, [UNTRACKED],
@@ -1721,6 +1753,9 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "link native libraries in the linker invocation (default: yes)"),
     link_only: bool = (false, parse_bool, [TRACKED],
         "link the `.rlink` file generated by `-Z no-link` (default: no)"),
+    linker_threads: Option<usize> = (None, parse_opt_number, [UNTRACKED],
+        "number of threads used by the linker, if it is mold or LLD for ELF targets \
+        (default: the linker's own default)"),
     lint_mir: bool = (false, parse_bool, [UNTRACKED],
         "lint MIR before and after each transformation"),
     llvm_module_flag: Vec<(String, u32, String)> = (Vec::new(), parse_llvm_module_flag, [TRACKED],
@@ -1824,6 +1859,8 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "use a more precise version of drop elaboration for matches on enums (default: yes). \
         This results in better codegen, but has caused miscompilations on some tier 2 platforms. \
         See #77382 and #74551."),
+    print_cgu_sizes: bool = (false, parse_bool, [UNTRACKED],
+        "print the size distribution of the codegen units (default: no)"),
     #[rustc_lint_opt_deny_field_access("use `Session::print_codegen_stats` instead of this field")]
     print_codegen_stats: bool = (false, parse_bool, [UNTRACKED],
         "print codegen statistics (default: no)"),
@@ -1870,6 +1907,9 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
     remark_dir: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
         "directory into which to write optimization remarks (if not specified, they will be \
 written to standard error output)"),
+    report_unused_allows: bool = (false, parse_bool, [TRACKED],
+        "warn about the `#[allow]` attributes that never suppressed a lint, like `-W unused-allow`, \
+and print their number (default: no)"),
     sanitizer: SanitizerSet = (SanitizerSet::empty(), parse_sanitizers, [TRACKED],
         "use a sanitizer"),
     sanitizer_cfi_canonical_jump_tables: Option<bool> = (Some(true), parse_opt_bool, [TRACKED],
@@ -1,5 +1,7 @@
 use crate::code_stats::CodeStats;
-pub use crate::code_stats::{DataTypeKind, FieldInfo, FieldKind, SizeKind, VariantInfo};
+pub use crate::code_stats::{
+    DataTypeKind, FieldInfo, FieldKind, LocalsOverlapInfo, SizeKind, VariantInfo,
+};
 use crate::config::{
     self, CrateType, FunctionReturn, InstrumentCoverage, OptLevel, OutFileName, OutputType,
     RemapPathScopeComponents, SwitchWithOptPath,
@@ -15,7 +17,7 @@
 use rustc_data_structures::jobserver::{self, Client};
 use rustc_data_structures::profiling::{SelfProfiler, SelfProfilerRef};
 use rustc_data_structures::sync::{
-    AtomicU64, DynSend, DynSync, Lock, Lrc, MappedReadGuard, ReadGuard, RwLock,
+    AtomicU64, DynSend, DynSync, Lock, Lrc, MappedReadGuard, OnceLock, ReadGuard, RwLock,
 };
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitter;
 use rustc_errors::emitter::{stderr_destination, DynEmitter, HumanEmitter, HumanReadableErrorType};
@@ -34,7 +36,8 @@
 use rustc_target::asm::InlineAsmArch;
 use rustc_target::spec::{CodeModel, PanicStrategy, RelocModel, RelroLevel};
 use rustc_target::spec::{
-    DebuginfoKind, SanitizerSet, SplitDebuginfo, StackProtector, Target, TargetTriple, TlsModel,
+    DebuginfoKind, LinkerFlavorCli, SanitizerSet, SplitDebuginfo, StackProtector, Target,
+    TargetTriple, TlsModel,
 };
 
 use std::any::Any;
@@ -216,6 +219,10 @@ pub struct Session {
     /// This is mainly useful for other tools that reads that debuginfo to figure out
     /// how to call the compiler with the same arguments.
     pub expanded_args: Vec<String>,
+
+    /// The raw profiles passed to `-C profile-use`, computed on first use by
+    /// `raw_pgo_use_profiles`.
+    raw_pgo_use_profiles: OnceLock<Option<Vec<PathBuf>>>,
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord)]
@@ -344,6 +351,33 @@ pub fn enable_internal_lints(&self) -> bool {
         self.unstable_options() && !self.opts.actually_rustdoc
     }
 
+    /// Returns the raw `.profraw` profiles to merge when `-C profile-use` is given a raw profile or
+    /// a directory of them, like the one written by `-C profile-generate`, instead of an indexed
+    /// `.profdata` file. The directory is only read once, so that all users agree on the profiles.
+    pub fn raw_pgo_use_profiles(&self) -> Option<&[PathBuf]> {
+        self.raw_pgo_use_profiles
+            .get_or_init(|| {
+                let path = self.opts.cg.profile_use.as_ref()?;
+                let is_raw_profile =
+                    |path: &Path| path.extension().is_some_and(|ext| ext == "profraw");
+                if path.is_dir() {
+                    let mut profiles: Vec<_> = std::fs::read_dir(path)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|entry| Some(entry.ok()?.path()))
+                        .filter(|path| is_raw_profile(path))
+                        .collect();
+                    profiles.sort();
+                    Some(profiles)
+                } else if is_raw_profile(path) {
+                    Some(vec![path.clone()])
+                } else {
+                    None
+                }
+            })
+            .as_deref()
+    }
+
     pub fn instrument_coverage(&self) -> bool {
         self.opts.cg.instrument_coverage() != InstrumentCoverage::No
     }
@@ -1122,6 +1156,7 @@ pub fn build_session(
         cfg_version,
         using_internal_features,
         expanded_args,
+        raw_pgo_use_profiles: OnceLock::new(),
     };
 
     validate_commandline_args_with_session_available(&sess);
@@ -1155,6 +1190,12 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
     if let Some(ref path) = sess.opts.cg.profile_use {
         if !path.exists() {
             sess.dcx().emit_err(errors::ProfileUseFileDoesNotExist { path });
+        } else if let Some(raw_profiles) = sess.raw_pgo_use_profiles() {
+            if !sess.unstable_options() {
+                sess.dcx().emit_err(errors::ProfileUseRawRequiresUnstable { path });
+            } else if raw_profiles.is_empty() {
+                sess.dcx().emit_err(errors::ProfileUseNoRawProfiles { path });
+            }
         }
     }
 
@@ -1303,6 +1344,16 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
         if let Some(compatible_list) = sess.target.linker_flavor.check_compatibility(flavor) {
             let flavor = flavor.desc();
             sess.dcx().emit_err(errors::IncompatibleLinkerFlavor { flavor, compatible_list });
+        } else if let LinkerFlavorCli::Mold(..) = flavor {
+            // Same as the object file format chosen for the target by `rustc_codegen_ssa`.
+            let target = &sess.target;
+            let is_like_elf = !(target.is_like_osx
+                || target.is_like_windows
+                || target.is_like_wasm
+                || target.is_like_aix);
+            if !is_like_elf {
+                sess.dcx().emit_err(errors::LinkerFlavorRequiresElf { flavor: flavor.desc() });
+            }
         }
     }
 
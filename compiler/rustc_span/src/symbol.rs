@@ -1044,6 +1044,7 @@
         len,
         let_chains,
         let_else,
+        level,
         lhs,
         lib,
         libc,
@@ -1532,6 +1533,7 @@
         rustc_allow_incoherent_impl,
         rustc_allowed_through_unstable_modules,
         rustc_attrs,
+        rustc_blocking,
         rustc_box,
         rustc_builtin_macro,
         rustc_capture_analysis,
@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 
+use rustc_span::edit_distance::edit_distance;
 pub use serde_json::Value as Json;
 use serde_json::{json, Map, Number};
 
@@ -133,3 +134,92 @@ fn to_json(&self) -> Json {
         })
     }
 }
+
+/// Checks that `value` has the types required by `schema`, using the subset of JSON schema
+/// written by [`crate::spec::Target::json_schema`]: the `type`, `minimum`, `maximum`, `items`,
+/// `properties` and `additionalProperties` keywords. Fields that the schema doesn't allow are not
+/// type errors, they are found by [`unknown_fields`] instead.
+pub(crate) fn matches_schema(value: &Json, schema: &Json) -> bool {
+    let has_type = |ty: &Json| match ty.as_str() {
+        Some("null") => value.is_null(),
+        Some("boolean") => value.is_boolean(),
+        Some("integer") => value.is_i64() || value.is_u64(),
+        Some("string") => value.is_string(),
+        Some("array") => value.is_array(),
+        Some("object") => value.is_object(),
+        _ => false,
+    };
+    let type_matches = match &schema["type"] {
+        Json::Null => true,
+        Json::Array(types) => types.iter().any(has_type),
+        ty => has_type(ty),
+    };
+    if !type_matches {
+        return false;
+    }
+
+    match value {
+        Json::Number(n) => {
+            schema["minimum"].as_f64().map_or(true, |min| n.as_f64().is_some_and(|n| n >= min))
+                && schema["maximum"]
+                    .as_f64()
+                    .map_or(true, |max| n.as_f64().is_some_and(|n| n <= max))
+        }
+        Json::Array(items) => items.iter().all(|item| matches_schema(item, &schema["items"])),
+        Json::Object(fields) => fields.iter().all(|(name, field)| {
+            match schema["properties"].get(name).or_else(|| schema.get("additionalProperties")) {
+                Some(Json::Bool(_)) | None => true,
+                Some(field_schema) => matches_schema(field, field_schema),
+            }
+        }),
+        _ => true,
+    }
+}
+
+/// Returns the fields of the objects in `value` that `schema` doesn't allow, as paths like
+/// `metadata.tier`, along with the allowed field with the closest name, if any.
+pub(crate) fn unknown_fields(value: &Json, schema: &Json) -> Vec<(String, Option<String>)> {
+    fn walk(value: &Json, schema: &Json, path: &str, unknown: &mut Vec<(String, Option<String>)>) {
+        match value {
+            Json::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    walk(item, &schema["items"], &format!("{path}[{i}]"), unknown);
+                }
+            }
+            Json::Object(fields) => {
+                let properties = schema["properties"].as_object();
+                for (name, field) in fields {
+                    let field_path =
+                        if path.is_empty() { name.clone() } else { format!("{path}.{name}") };
+                    match properties.and_then(|properties| properties.get(name)) {
+                        Some(field_schema) => walk(field, field_schema, &field_path, unknown),
+                        None => match &schema["additionalProperties"] {
+                            Json::Bool(false) => {
+                                let suggestion = properties.and_then(|properties| {
+                                    closest_name(name, properties.keys().map(String::as_str))
+                                });
+                                unknown.push((field_path, suggestion));
+                            }
+                            field_schema => walk(field, field_schema, &field_path, unknown),
+                        },
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut unknown = Vec::new();
+    walk(value, schema, "", &mut unknown);
+    unknown
+}
+
+/// Returns the name in `names` closest to `name`, if it's close enough to be a likely typo.
+fn closest_name<'a>(name: &str, names: impl Iterator<Item = &'a str>) -> Option<String> {
+    // The same limit as `rustc_span::edit_distance::find_best_match_for_name`.
+    let limit = name.len().max(3) / 3;
+    names
+        .filter_map(|candidate| Some((edit_distance(name, candidate, limit)?, candidate)))
+        .min()
+        .map(|(_, candidate)| candidate.to_owned())
+}
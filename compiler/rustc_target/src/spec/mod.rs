@@ -36,13 +36,13 @@
 
 use crate::abi::call::Conv;
 use crate::abi::{Endian, Integer, Size, TargetDataLayout, TargetDataLayoutErrors};
-use crate::json::{Json, ToJson};
+use crate::json::{matches_schema, unknown_fields, Json, ToJson};
 use crate::spec::abi::{lookup as lookup_abi, Abi};
 use crate::spec::crt_objects::CrtObjects;
 use rustc_fs_util::try_canonicalize;
 use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
 use rustc_span::symbol::{kw, sym, Symbol};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
@@ -113,6 +113,10 @@ pub enum LinkerFlavor {
     /// possibly with non-GNU extensions (both naked and compiler-wrapped forms).
     /// LLD doesn't support any of these.
     Unix(Cc),
+    /// The mold linker for ELF targets whose principal flavor is `Gnu` (both naked and
+    /// compiler-wrapped forms). It accepts the same command line as GNU ld, plus its own options
+    /// for e.g. the thread count.
+    Mold(Cc),
     /// MSVC-style linker for Windows and UEFI, LLD supports it.
     Msvc(Lld),
     /// Emscripten Compiler Frontend, a wrapper around `WasmLld(Cc::Yes)` that has a different
@@ -138,6 +142,7 @@ pub enum LinkerFlavorCli {
     Darwin(Cc, Lld),
     WasmLld(Cc),
     Unix(Cc),
+    Mold(Cc),
     // Note: `Msvc(Lld::No)` is also a stable value.
     Msvc(Lld),
     EmCc,
@@ -160,6 +165,7 @@ pub fn is_unstable(&self) -> bool {
             | LinkerFlavorCli::Darwin(..)
             | LinkerFlavorCli::WasmLld(..)
             | LinkerFlavorCli::Unix(..)
+            | LinkerFlavorCli::Mold(..)
             | LinkerFlavorCli::Msvc(Lld::Yes)
             | LinkerFlavorCli::EmCc
             | LinkerFlavorCli::Bpf
@@ -220,6 +226,7 @@ fn from_cli_json(cli: LinkerFlavorCli, lld_flavor: LldFlavor, is_gnu: bool) -> L
             LinkerFlavorCli::Darwin(cc, lld) => LinkerFlavor::Darwin(cc, lld),
             LinkerFlavorCli::WasmLld(cc) => LinkerFlavor::WasmLld(cc),
             LinkerFlavorCli::Unix(cc) => LinkerFlavor::Unix(cc),
+            LinkerFlavorCli::Mold(cc) => LinkerFlavor::Mold(cc),
             LinkerFlavorCli::Msvc(lld) => LinkerFlavor::Msvc(lld),
             LinkerFlavorCli::EmCc => LinkerFlavor::EmCc,
             LinkerFlavorCli::Bpf => LinkerFlavor::Bpf,
@@ -252,13 +259,15 @@ fn to_cli(self) -> LinkerFlavorCli {
             LinkerFlavor::Gnu(Cc::Yes, _)
             | LinkerFlavor::Darwin(Cc::Yes, _)
             | LinkerFlavor::WasmLld(Cc::Yes)
-            | LinkerFlavor::Unix(Cc::Yes) => LinkerFlavorCli::Gcc,
+            | LinkerFlavor::Unix(Cc::Yes)
+            | LinkerFlavor::Mold(Cc::Yes) => LinkerFlavorCli::Gcc,
             LinkerFlavor::Gnu(_, Lld::Yes) => LinkerFlavorCli::Lld(LldFlavor::Ld),
             LinkerFlavor::Darwin(_, Lld::Yes) => LinkerFlavorCli::Lld(LldFlavor::Ld64),
             LinkerFlavor::WasmLld(..) => LinkerFlavorCli::Lld(LldFlavor::Wasm),
-            LinkerFlavor::Gnu(..) | LinkerFlavor::Darwin(..) | LinkerFlavor::Unix(..) => {
-                LinkerFlavorCli::Ld
-            }
+            LinkerFlavor::Gnu(..)
+            | LinkerFlavor::Darwin(..)
+            | LinkerFlavor::Unix(..)
+            | LinkerFlavor::Mold(..) => LinkerFlavorCli::Ld,
             LinkerFlavor::Msvc(Lld::Yes) => LinkerFlavorCli::Lld(LldFlavor::Link),
             LinkerFlavor::Msvc(..) => LinkerFlavorCli::Msvc(Lld::No),
             LinkerFlavor::EmCc => LinkerFlavorCli::Em,
@@ -275,6 +284,7 @@ fn to_cli_counterpart(self) -> LinkerFlavorCli {
             LinkerFlavor::Darwin(cc, lld) => LinkerFlavorCli::Darwin(cc, lld),
             LinkerFlavor::WasmLld(cc) => LinkerFlavorCli::WasmLld(cc),
             LinkerFlavor::Unix(cc) => LinkerFlavorCli::Unix(cc),
+            LinkerFlavor::Mold(cc) => LinkerFlavorCli::Mold(cc),
             LinkerFlavor::Msvc(lld) => LinkerFlavorCli::Msvc(lld),
             LinkerFlavor::EmCc => LinkerFlavorCli::EmCc,
             LinkerFlavor::Bpf => LinkerFlavorCli::Bpf,
@@ -290,6 +300,7 @@ fn infer_cli_hints(cli: LinkerFlavorCli) -> (Option<Cc>, Option<Lld>) {
             }
             LinkerFlavorCli::WasmLld(cc) => (Some(cc), Some(Lld::Yes)),
             LinkerFlavorCli::Unix(cc) => (Some(cc), None),
+            LinkerFlavorCli::Mold(cc) => (Some(cc), Some(Lld::No)),
             LinkerFlavorCli::Msvc(lld) => (Some(Cc::No), Some(lld)),
             LinkerFlavorCli::EmCc => (Some(Cc::Yes), Some(Lld::Yes)),
             LinkerFlavorCli::Bpf | LinkerFlavorCli::Ptx => (None, None),
@@ -347,6 +358,7 @@ fn with_hints(self, (cc_hint, lld_hint): (Option<Cc>, Option<Lld>)) -> LinkerFla
             }
             LinkerFlavor::WasmLld(cc) => LinkerFlavor::WasmLld(cc_hint.unwrap_or(cc)),
             LinkerFlavor::Unix(cc) => LinkerFlavor::Unix(cc_hint.unwrap_or(cc)),
+            LinkerFlavor::Mold(cc) => LinkerFlavor::Mold(cc_hint.unwrap_or(cc)),
             LinkerFlavor::Msvc(lld) => LinkerFlavor::Msvc(lld_hint.unwrap_or(lld)),
             LinkerFlavor::EmCc | LinkerFlavor::Bpf | LinkerFlavor::Llbc | LinkerFlavor::Ptx => self,
         }
@@ -369,6 +381,7 @@ pub fn check_compatibility(self, cli: LinkerFlavorCli) -> Option<String> {
                 | (LinkerFlavor::Darwin(..), LinkerFlavorCli::Darwin(..))
                 | (LinkerFlavor::WasmLld(..), LinkerFlavorCli::WasmLld(..))
                 | (LinkerFlavor::Unix(..), LinkerFlavorCli::Unix(..))
+                | (LinkerFlavor::Mold(..), LinkerFlavorCli::Mold(..))
                 | (LinkerFlavor::Msvc(..), LinkerFlavorCli::Msvc(..))
                 | (LinkerFlavor::EmCc, LinkerFlavorCli::EmCc)
                 | (LinkerFlavor::Bpf, LinkerFlavorCli::Bpf)
@@ -376,10 +389,12 @@ pub fn check_compatibility(self, cli: LinkerFlavorCli) -> Option<String> {
                 | (LinkerFlavor::Ptx, LinkerFlavorCli::Ptx) => return true,
                 // 2. The linker flavor is independent of target and compatible
                 (LinkerFlavor::Ptx, LinkerFlavorCli::Llbc) => return true,
+                // 3. the target uses a GNU linker, which mold can replace.
+                (LinkerFlavor::Gnu(..), LinkerFlavorCli::Mold(..)) => return true,
                 _ => {}
             }
 
-            // 3. or, the flavor is legacy and survives this roundtrip.
+            // 4. or, the flavor is legacy and survives this roundtrip.
             cli == self.with_cli_hints(cli).to_cli()
         };
         (!compatible(cli)).then(|| {
@@ -396,6 +411,7 @@ pub fn lld_flavor(self) -> LldFlavor {
         match self {
             LinkerFlavor::Gnu(..)
             | LinkerFlavor::Unix(..)
+            | LinkerFlavor::Mold(..)
             | LinkerFlavor::EmCc
             | LinkerFlavor::Bpf
             | LinkerFlavor::Llbc
@@ -407,7 +423,16 @@ pub fn lld_flavor(self) -> LldFlavor {
     }
 
     pub fn is_gnu(self) -> bool {
-        matches!(self, LinkerFlavor::Gnu(..))
+        matches!(self, LinkerFlavor::Gnu(..) | LinkerFlavor::Mold(..))
+    }
+
+    /// Returns the flavor whose link arguments from the target spec apply to this flavor: mold
+    /// takes the arguments meant for the GNU linker it replaces.
+    pub fn link_args_flavor(self) -> LinkerFlavor {
+        match self {
+            LinkerFlavor::Mold(cc) => LinkerFlavor::Gnu(cc, Lld::No),
+            _ => self,
+        }
     }
 
     /// Returns whether the flavor uses the `lld` linker.
@@ -423,6 +448,7 @@ pub fn uses_lld(self) -> bool {
             | LinkerFlavor::Darwin(..)
             | LinkerFlavor::Msvc(_)
             | LinkerFlavor::Unix(_)
+            | LinkerFlavor::Mold(_)
             | LinkerFlavor::Bpf
             | LinkerFlavor::Llbc
             | LinkerFlavor::Ptx => false,
@@ -437,12 +463,14 @@ pub fn uses_cc(self) -> bool {
             | LinkerFlavor::Darwin(Cc::Yes, _)
             | LinkerFlavor::WasmLld(Cc::Yes)
             | LinkerFlavor::Unix(Cc::Yes)
+            | LinkerFlavor::Mold(Cc::Yes)
             | LinkerFlavor::EmCc => true,
             LinkerFlavor::Gnu(..)
             | LinkerFlavor::Darwin(..)
             | LinkerFlavor::WasmLld(_)
             | LinkerFlavor::Msvc(_)
             | LinkerFlavor::Unix(_)
+            | LinkerFlavor::Mold(_)
             | LinkerFlavor::Bpf
             | LinkerFlavor::Llbc
             | LinkerFlavor::Ptx => false,
@@ -490,6 +518,8 @@ pub fn desc(self) -> &'static str {
     (LinkerFlavorCli::WasmLld(Cc::Yes)) "wasm-lld-cc"
     (LinkerFlavorCli::Unix(Cc::No)) "unix"
     (LinkerFlavorCli::Unix(Cc::Yes)) "unix-cc"
+    (LinkerFlavorCli::Mold(Cc::No)) "gnu-mold"
+    (LinkerFlavorCli::Mold(Cc::Yes)) "gnu-mold-cc"
     (LinkerFlavorCli::Msvc(Lld::Yes)) "msvc-lld"
     (LinkerFlavorCli::Msvc(Lld::No)) "msvc"
     (LinkerFlavorCli::EmCc) "em-cc"
@@ -1725,21 +1755,22 @@ macro_rules! cvs {
 /// Includes fields that weren't recognized and fields that don't have the expected type.
 #[derive(Debug, PartialEq)]
 pub struct TargetWarnings {
-    unused_fields: Vec<String>,
+    unknown_fields: Vec<String>,
     incorrect_type: Vec<String>,
 }
 
 impl TargetWarnings {
     pub fn empty() -> Self {
-        Self { unused_fields: Vec::new(), incorrect_type: Vec::new() }
+        Self { unknown_fields: Vec::new(), incorrect_type: Vec::new() }
     }
 
     pub fn warning_messages(&self) -> Vec<String> {
         let mut warnings = vec![];
-        if !self.unused_fields.is_empty() {
+        if !self.unknown_fields.is_empty() {
             warnings.push(format!(
-                "target json file contains unused fields: {}",
-                self.unused_fields.join(", ")
+                "target json file contains unknown fields: {}; \
+                 this will become an error in a future release",
+                self.unknown_fields.join(", ")
             ));
         }
         if !self.incorrect_type.is_empty() {
@@ -2245,6 +2276,7 @@ fn add_link_args_iter(
         }
         LinkerFlavor::WasmLld(..)
         | LinkerFlavor::Unix(..)
+        | LinkerFlavor::Mold(..)
         | LinkerFlavor::EmCc
         | LinkerFlavor::Bpf
         | LinkerFlavor::Llbc
@@ -2573,6 +2605,36 @@ pub fn max_atomic_width(&self) -> u64 {
 
     /// Loads a target descriptor from a JSON object.
     pub fn from_json(obj: Json) -> Result<(Target, TargetWarnings), String> {
+        Target::from_json_with_schema(obj).map(|(target, warnings, _)| (target, warnings))
+    }
+
+    /// Returns the JSON schema of target specifications, printed by
+    /// `--print target-spec-json-schema`. It is derived from the fields read by
+    /// [`Target::from_json`], so that it can't get out of sync with it.
+    pub fn json_schema() -> Json {
+        let minimal = json!({
+            "arch": "",
+            "data-layout": "",
+            "llvm-target": "",
+            "target-pointer-width": "64",
+        });
+        let (_, _, properties) =
+            Target::from_json_with_schema(minimal).expect("minimal target specification");
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "rustc target specification",
+            "type": "object",
+            "required": ["arch", "data-layout", "llvm-target", "target-pointer-width"],
+            "properties": properties,
+            "additionalProperties": false,
+        })
+    }
+
+    /// Loads a target descriptor from a JSON object, and returns the JSON schema of the fields
+    /// that were looked for.
+    fn from_json_with_schema(
+        obj: Json,
+    ) -> Result<(Target, TargetWarnings, serde_json::Map<String, Json>), String> {
         // While ugly, this code must remain this way to retain
         // compatibility with existing JSON fields and the internal
         // expected naming of the Target and TargetOptions structs.
@@ -2584,6 +2646,68 @@ pub fn from_json(obj: Json) -> Result<(Target, TargetWarnings), String> {
             Value::Object(obj) => obj,
             _ => return Err("Expected JSON object for target")?,
         };
+        let original = Json::Object(obj.clone());
+
+        // The JSON schema of every field, recorded as they are read. Values that don't match the
+        // schema of their field are reported and ignored, and fields without a schema are unknown.
+        let mut properties = serde_json::Map::new();
+        let mut incorrect_type = vec![];
+
+        macro_rules! key_schema {
+            (bool) => (json!({ "type": "boolean" }));
+            (Option<bool>) => (key_schema!(bool));
+            (u32) => (json!({ "type": "integer", "minimum": 0 }));
+            (Option<u64>) => (key_schema!(u32));
+            (list) => (json!({ "type": "array", "items": { "type": "string" } }));
+            (opt_list) => (key_schema!(list));
+            (fallible_list) => (key_schema!(list));
+            (env) => (key_schema!(list));
+            (SanitizerSet) => (key_schema!(list));
+            (TargetFamilies) => (json!({
+                "type": ["string", "array"],
+                "items": { "type": "string" },
+            }));
+            (link_args) => (json!({ "type": "object", "additionalProperties": key_schema!(list) }));
+            (link_objects) => (key_schema!(link_args));
+            (link_self_contained_components) => (json!({
+                "type": "object",
+                "properties": { "components": key_schema!(list) },
+                "additionalProperties": false,
+            }));
+            (StackProbeType) => (json!({ "type": "object" }));
+            (metadata) => (json!({
+                "type": "object",
+                "properties": {
+                    "description": { "type": ["string", "null"] },
+                    "tier": { "type": ["integer", "null"], "minimum": 1, "maximum": 3 },
+                    "host_tools": { "type": ["boolean", "null"] },
+                    "std": { "type": ["boolean", "null"] },
+                },
+                "additionalProperties": false,
+            }));
+            // Strings, including the ones that are parsed into enums.
+            ($($kind:tt)*) => (json!({ "type": "string" }));
+        }
+
+        macro_rules! check_key {
+            ($name:expr, $($kind:tt)*) => ( {
+                let name = String::from($name);
+                let schema = key_schema!($($kind)*);
+                if obj.get(&name).is_some_and(|value| !matches_schema(value, &schema)) {
+                    obj.remove(&name);
+                    incorrect_type.push(name.clone());
+                }
+                properties.insert(name, schema);
+            } );
+        }
+
+        check_key!("llvm-target", string);
+        check_key!("target-pointer-width", string);
+        check_key!("data-layout", string);
+        check_key!("arch", string);
+        check_key!("metadata", metadata);
+        check_key!("target-endian", string);
+        check_key!("frame-pointer", string);
 
         let mut get_req_field = |name: &str| {
             obj.remove(name)
@@ -2602,9 +2726,7 @@ pub fn from_json(obj: Json) -> Result<(Target, TargetWarnings), String> {
             options: Default::default(),
         };
 
-        // FIXME: This doesn't properly validate anything and just ignores the data if it's invalid.
-        // That's okay for now, the only use of this is when generating docs, which we don't do for
-        // custom targets.
+        // The types of these fields were checked by `check_key!`.
         if let Some(Json::Object(mut metadata)) = obj.remove("metadata") {
             base.metadata.description = metadata
                 .remove("description")
@@ -2618,9 +2740,27 @@ pub fn from_json(obj: Json) -> Result<(Target, TargetWarnings), String> {
             base.metadata.std = metadata.remove("std").and_then(|host| host.as_bool());
         }
 
-        let mut incorrect_type = vec![];
-
+        // Reads a field of `TargetOptions`, after checking its JSON type with `check_key!`.
         macro_rules! key {
+            ($key_name:ident) => ( {
+                check_key!((stringify!($key_name)).replace("_", "-"), string);
+                parse_key!($key_name)
+            } );
+            ($key_name:ident = $json_name:expr) => ( {
+                check_key!($json_name, string);
+                parse_key!($key_name = $json_name)
+            } );
+            ($key_name:ident, $($kind:tt)+) => ( {
+                check_key!((stringify!($key_name)).replace("_", "-"), $($kind)+);
+                parse_key!($key_name, $($kind)+)
+            } );
+            ($key_name:ident = $json_name:expr, $($kind:tt)+) => ( {
+                check_key!($json_name, $($kind)+);
+                parse_key!($key_name = $json_name, $($kind)+)
+            } );
+        }
+
+        macro_rules! parse_key {
             ($key_name:ident) => ( {
                 let name = (stringify!($key_name)).replace("_", "-");
                 if let Some(s) = obj.remove(&name).and_then(|s| s.as_str().map(str::to_string).map(Cow::from)) {
@@ -2772,8 +2912,6 @@ macro_rules! key {
                         base.$key_name = v.iter()
                             .map(|a| a.as_str().unwrap().to_string().into())
                             .collect();
-                    } else {
-                        incorrect_type.push(name)
                     }
                 }
             } );
@@ -2784,8 +2922,6 @@ macro_rules! key {
                         base.$key_name = Some(v.iter()
                             .map(|a| a.as_str().unwrap().to_string().into())
                             .collect());
-                    } else {
-                        incorrect_type.push(name)
                     }
                 }
             } );
@@ -2802,8 +2938,6 @@ macro_rules! key {
                                 format!("`{:?}` is not a valid value for `{}`", j, name)
                             )),
                         }
-                    } else {
-                        incorrect_type.push(name)
                     }
                     Some(Ok(()))
                 }).unwrap_or(Ok(()))
@@ -2875,8 +3009,6 @@ macro_rules! key {
                                 _ => return Err(format!("not a string: {:?}", s)),
                             };
                         }
-                    } else {
-                        incorrect_type.push(name)
                     }
                 }
                 Ok::<(), String>(())
@@ -2911,8 +3043,6 @@ macro_rules! key {
                             };
                         }
                         base.$key_name = LinkSelfContainedDefault::WithComponents(components);
-                    } else {
-                        incorrect_type.push(name)
                     }
                 }
                 Ok::<(), String>(())
@@ -2997,8 +3127,6 @@ macro_rules! key {
                                 }
                             }
                         }
-                    } else {
-                        incorrect_type.push(name)
                     }
                 }
             } );
@@ -3012,8 +3140,8 @@ macro_rules! key {
                     Some(Ok(()))
                 })).unwrap_or(Ok(()))
             } );
-            ($key_name:ident, TargetFamilies) => ( {
-                if let Some(value) = obj.remove("target-family") {
+            ($key_name:ident = $json_name:expr, TargetFamilies) => ( {
+                if let Some(value) = obj.remove($json_name) {
                     if let Some(v) = value.as_array() {
                         base.$key_name = v.iter()
                             .map(|a| a.as_str().unwrap().to_string().into())
@@ -3037,22 +3165,13 @@ macro_rules! key {
             } );
         }
 
-        if let Some(j) = obj.remove("target-endian") {
-            if let Some(s) = j.as_str() {
-                base.endian = s.parse()?;
-            } else {
-                incorrect_type.push("target-endian".into())
-            }
+        if let Some(s) = obj.remove("target-endian").as_ref().and_then(Json::as_str) {
+            base.endian = s.parse()?;
         }
 
-        if let Some(fp) = obj.remove("frame-pointer") {
-            if let Some(s) = fp.as_str() {
-                base.frame_pointer = s
-                    .parse()
-                    .map_err(|()| format!("'{s}' is not a valid value for frame-pointer"))?;
-            } else {
-                incorrect_type.push("frame-pointer".into())
-            }
+        if let Some(s) = obj.remove("frame-pointer").as_ref().and_then(Json::as_str) {
+            base.frame_pointer =
+                s.parse().map_err(|()| format!("'{s}' is not a valid value for frame-pointer"))?;
         }
 
         key!(is_builtin, bool);
@@ -3103,7 +3222,7 @@ macro_rules! key {
         key!(exe_suffix);
         key!(staticlib_prefix);
         key!(staticlib_suffix);
-        key!(families, TargetFamilies);
+        key!(families = "target-family", TargetFamilies);
         key!(abi_return_struct_as_int, bool);
         key!(is_like_aix, bool);
         key!(is_like_osx, bool);
@@ -3168,18 +3287,26 @@ macro_rules! key {
         key!(entry_abi, Conv)?;
         key!(supports_xray, bool);
 
+        // Every known field has a schema by now, so any other field is unknown. These used to be
+        // ignored, so they are only warned about for now.
+        let unknown_fields = unknown_fields(
+            &original,
+            &json!({ "properties": properties, "additionalProperties": false }),
+        )
+        .into_iter()
+        .map(|(field, suggestion)| match suggestion {
+            Some(suggestion) => format!("`{field}` (did you mean `{suggestion}`?)"),
+            None => format!("`{field}`"),
+        })
+        .collect();
+
         if base.is_builtin {
             // This can cause unfortunate ICEs later down the line.
             return Err("may not set is_builtin for targets not built-in".into());
         }
         base.update_from_cli();
 
-        // Each field should have been read using `Json::remove` so any keys remaining are unused.
-        let remaining_keys = obj.keys();
-        Ok((
-            base,
-            TargetWarnings { unused_fields: remaining_keys.cloned().collect(), incorrect_type },
-        ))
+        Ok((base, TargetWarnings { unknown_fields, incorrect_type }, properties))
     }
 
     /// Load a built-in target
@@ -1,7 +1,7 @@
 use crate::spec::Target;
 
 #[test]
-fn report_unused_fields() {
+fn report_unknown_fields() {
     let json = serde_json::from_str(
         r#"
     {
@@ -9,14 +9,20 @@ fn report_unused_fields() {
         "data-layout": "e-m:e-i64:64-n32:64",
         "llvm-target": "powerpc64le-elf",
         "target-pointer-width": "64",
-        "code-mode": "foo"
+        "code-mode": "foo",
+        "metadata": { "teir": 3 },
+        "definitely-not-a-field": true
     }
     "#,
     )
     .unwrap();
-    let warnings = Target::from_json(json).unwrap().1;
-    assert_eq!(warnings.warning_messages().len(), 1);
-    assert!(warnings.warning_messages().join("\n").contains("code-mode"));
+    let warnings = Target::from_json(json).unwrap().1.warning_messages();
+    assert_eq!(warnings.len(), 1);
+    let warning = &warnings[0];
+    assert!(warning.contains("`code-mode` (did you mean `code-model`?)"), "{warning}");
+    assert!(warning.contains("`metadata.teir` (did you mean `tier`?)"), "{warning}");
+    assert!(warning.contains("`definitely-not-a-field`"), "{warning}");
+    assert!(warning.contains("will become an error in a future release"), "{warning}");
 }
 
 #[test]
@@ -38,6 +44,38 @@ fn report_incorrect_json_type() {
     assert!(warnings.warning_messages().join("\n").contains("link-env-remove"));
 }
 
+#[test]
+fn report_incorrect_json_type_of_list_items_and_bools() {
+    let json = serde_json::from_str(
+        r#"
+    {
+        "arch": "powerpc64",
+        "data-layout": "e-m:e-i64:64-n32:64",
+        "llvm-target": "powerpc64le-elf",
+        "target-pointer-width": "64",
+        "asm-args": ["-a", 1],
+        "dynamic-linking": "yes"
+    }
+    "#,
+    )
+    .unwrap();
+    let (target, warnings) = Target::from_json(json).unwrap();
+    let warnings = warnings.warning_messages().join("\n");
+    assert!(warnings.contains("asm-args") && warnings.contains("dynamic-linking"), "{warnings}");
+    assert!(target.asm_args.is_empty());
+}
+
+#[test]
+fn json_schema_describes_target_options() {
+    let schema = Target::json_schema();
+    assert_eq!(schema["additionalProperties"], false);
+    assert_eq!(schema["properties"]["code-model"]["type"], "string");
+    assert_eq!(schema["properties"]["dynamic-linking"]["type"], "boolean");
+    assert_eq!(schema["properties"]["link-env-remove"]["items"]["type"], "string");
+    assert_eq!(schema["properties"]["pre-link-args"]["type"], "object");
+    assert_eq!(schema["properties"]["metadata"]["properties"]["tier"]["maximum"], 3);
+}
+
 #[test]
 fn no_warnings_for_valid_target() {
     let json = serde_json::from_str(
@@ -29,7 +29,7 @@
 
 use crate::traits::vtable::{count_own_vtable_entries, prepare_vtable_segments, VtblSegment};
 
-use super::inspect::ProofTreeBuilder;
+use super::inspect::{dump_proof_tree_html, ProofTreeBuilder};
 use super::{search_graph, GoalEvaluationKind, FIXPOINT_STEP_LIMIT};
 use super::{search_graph::SearchGraph, Goal};
 use super::{GoalSource, SolverMode};
@@ -204,6 +204,11 @@ fn enter_root<R>(
             let _ = lock.write_fmt(format_args!("{tree:?}\n"));
             let _ = lock.flush();
         }
+        if let (Some(tree), Some(dir)) =
+            (&tree, &infcx.tcx.sess.opts.unstable_opts.dump_solver_proof_trees)
+        {
+            dump_proof_tree_html(infcx.tcx, dir, tree);
+        }
 
         assert!(
             ecx.nested_goals.is_empty(),
@@ -264,6 +264,11 @@ pub fn new_maybe_root(
     ) -> ProofTreeBuilder<'tcx> {
         match generate_proof_tree {
             GenerateProofTree::Never => ProofTreeBuilder::new_noop(),
+            GenerateProofTree::IfEnabled
+                if tcx.sess.opts.unstable_opts.dump_solver_proof_trees.is_some() =>
+            {
+                ProofTreeBuilder::new_root()
+            }
             GenerateProofTree::IfEnabled => {
                 let opts = &tcx.sess.opts.unstable_opts;
                 match opts.next_solver.map(|c| c.dump_tree).unwrap_or_default() {
@@ -1,7 +1,28 @@
 pub use rustc_middle::traits::solve::inspect::*;
 
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::ty::TyCtxt;
+
 mod build;
 pub(in crate::solve) use build::*;
 
 mod analyse;
 pub use analyse::*;
+
+/// Writes `tree` as a new HTML document in `dir`, for `-Zdump-solver-proof-trees`.
+///
+/// Like the MIR dumps, failing to write the document is silently ignored.
+pub(in crate::solve) fn dump_proof_tree_html(
+    tcx: TyCtxt<'_>,
+    dir: &Path,
+    tree: &GoalEvaluation<'_>,
+) {
+    static DUMPED_TREES: AtomicUsize = AtomicUsize::new(0);
+    let n = DUMPED_TREES.fetch_add(1, Ordering::Relaxed);
+    let path = dir.join(format!("{}.{n:06}.html", tcx.crate_name(LOCAL_CRATE)));
+    let _ = fs::create_dir_all(dir).and_then(|()| fs::write(path, tree.to_html()));
+}
@@ -9,7 +9,7 @@
 };
 use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, AdtDef, EarlyBinder, GenericArgsRef, Ty, TyCtxt, TypeVisitableExt};
-use rustc_session::{DataTypeKind, FieldInfo, FieldKind, SizeKind, VariantInfo};
+use rustc_session::{DataTypeKind, FieldInfo, FieldKind, LocalsOverlapInfo, SizeKind, VariantInfo};
 use rustc_span::sym;
 use rustc_span::symbol::Symbol;
 use rustc_target::abi::*;
@@ -947,7 +947,7 @@ fn record_layout_for_printing<'tcx>(cx: &LayoutCx<'tcx, TyCtxt<'tcx>>, layout: T
     }
 
     // (delay format until we actually need it)
-    let record = |kind, packed, opt_discr_size, variants| {
+    let record = |kind, packed, opt_discr_size, variants, opt_locals_overlap| {
         let type_desc = with_no_trimmed_paths!(format!("{}", layout.ty));
         cx.tcx.sess.code_stats.record_type_size(
             kind,
@@ -957,6 +957,7 @@ fn record_layout_for_printing<'tcx>(cx: &LayoutCx<'tcx, TyCtxt<'tcx>>, layout: T
             packed,
             opt_discr_size,
             variants,
+            opt_locals_overlap,
         );
     };
 
@@ -966,20 +967,26 @@ fn record_layout_for_printing<'tcx>(cx: &LayoutCx<'tcx, TyCtxt<'tcx>>, layout: T
             let adt_kind = adt_def.adt_kind();
             let adt_packed = adt_def.repr().pack.is_some();
             let (variant_infos, opt_discr_size) = variant_info_for_adt(cx, layout, adt_def);
-            record(adt_kind.into(), adt_packed, opt_discr_size, variant_infos);
+            record(adt_kind.into(), adt_packed, opt_discr_size, variant_infos, None);
         }
 
         ty::Coroutine(def_id, args) => {
             debug!("print-type-size t: `{:?}` record coroutine", layout.ty);
             // Coroutines always have a begin/poisoned/end state with additional suspend points
-            let (variant_infos, opt_discr_size) =
+            let (variant_infos, opt_discr_size, opt_locals_overlap) =
                 variant_info_for_coroutine(cx, layout, def_id, args);
-            record(DataTypeKind::Coroutine, false, opt_discr_size, variant_infos);
+            record(
+                DataTypeKind::Coroutine,
+                false,
+                opt_discr_size,
+                variant_infos,
+                opt_locals_overlap,
+            );
         }
 
         ty::Closure(..) => {
             debug!("print-type-size t: `{:?}` record closure", layout.ty);
-            record(DataTypeKind::Closure, false, None, vec![]);
+            record(DataTypeKind::Closure, false, None, vec![], None);
         }
 
         _ => {
@@ -1065,11 +1072,11 @@ fn variant_info_for_coroutine<'tcx>(
     layout: TyAndLayout<'tcx>,
     def_id: DefId,
     args: ty::GenericArgsRef<'tcx>,
-) -> (Vec<VariantInfo>, Option<Size>) {
+) -> (Vec<VariantInfo>, Option<Size>, Option<LocalsOverlapInfo>) {
     use itertools::Itertools;
 
     let Variants::Multiple { tag, ref tag_encoding, tag_field, .. } = layout.variants else {
-        return (vec![], None);
+        return (vec![], None, None);
     };
 
     let coroutine = cx.tcx.coroutine_layout(def_id, args.as_coroutine().kind_ty()).unwrap();
@@ -1097,6 +1104,10 @@ fn variant_info_for_coroutine<'tcx>(
         })
         .collect();
 
+    // Where each saved local ended up. Locals that are live in several variants
+    // are placed in the prefix, so they only have a single position.
+    let mut local_positions = IndexVec::from_elem(None, &coroutine.field_tys);
+
     let mut variant_infos: Vec<_> = coroutine
         .variant_fields
         .iter_enumerated()
@@ -1110,6 +1121,7 @@ fn variant_info_for_coroutine<'tcx>(
                     let field_name = coroutine.field_names[*local];
                     let field_layout = variant_layout.field(cx, field_idx);
                     let offset = variant_layout.fields.offset(field_idx);
+                    local_positions[*local] = Some((offset, field_layout.size));
                     // The struct is as large as the last field's end
                     variant_size = variant_size.max(offset + field_layout.size);
                     FieldInfo {
@@ -1175,11 +1187,71 @@ fn variant_info_for_coroutine<'tcx>(
     let end_states: Vec<_> = end_states.collect();
     variant_infos.extend(end_states);
 
+    // Compare the space all saved locals would need if each had its own slot
+    // with the space they occupy once overlapped across suspension points.
+    let local_intervals: IndexVec<CoroutineSavedLocal, Option<(Size, Size)>> = local_positions
+        .into_iter()
+        .map(|position| {
+            position
+                .filter(|(_, size)| *size != Size::ZERO)
+                .map(|(offset, size)| (offset, offset + size))
+        })
+        .collect();
+    let total_size = local_intervals
+        .iter()
+        .flatten()
+        .fold(Size::ZERO, |total, (start, end)| total + (*end - *start));
+    let overlapped_size = covered_size(local_intervals.iter().flatten().copied().collect());
+
+    // For every suspension point, how much of the space used by the locals
+    // saved there is shared with locals that are not live at that point.
+    let per_variant = coroutine
+        .variant_fields
+        .iter_enumerated()
+        .map(|(variant_idx, variant_def)| {
+            let mut shared = vec![];
+            for (other, other_interval) in local_intervals.iter_enumerated() {
+                let Some((other_start, other_end)) = *other_interval else { continue };
+                if variant_def.iter().any(|local| *local == other) {
+                    continue;
+                }
+                for (start, end) in variant_def.iter().filter_map(|local| local_intervals[*local]) {
+                    let (start, end) = (start.max(other_start), end.min(other_end));
+                    if start < end {
+                        shared.push((start, end));
+                    }
+                }
+            }
+            let name = Symbol::intern(&ty::CoroutineArgs::variant_name(variant_idx));
+            (name, covered_size(shared).bytes())
+        })
+        .collect();
+
     (
         variant_infos,
         match tag_encoding {
             TagEncoding::Direct => Some(tag.size(cx)),
             _ => None,
         },
+        Some(LocalsOverlapInfo {
+            total_size: total_size.bytes(),
+            overlapped_size: overlapped_size.bytes(),
+            per_variant,
+        }),
     )
 }
+
+/// The number of bytes covered by at least one of the given `[start, end)` intervals.
+fn covered_size(mut intervals: Vec<(Size, Size)>) -> Size {
+    intervals.sort_unstable();
+    let mut covered = Size::ZERO;
+    let mut covered_until = Size::ZERO;
+    for (start, end) in intervals {
+        let start = start.max(covered_until);
+        if end > start {
+            covered += end - start;
+            covered_until = end;
+        }
+    }
+    covered
+}
@@ -268,6 +268,7 @@
     all(any(target_arch = "x86_64", target_arch = "x86"), target_os = "uefi"),
     feature(stdarch_x86_has_cpuid)
 )]
+#![cfg_attr(not(bootstrap), feature(rustc_blocking))]
 //
 // Language features:
 // tidy-alphabetical-start
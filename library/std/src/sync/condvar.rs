@@ -183,6 +183,7 @@ pub const fn new() -> Condvar {
     /// }
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(bootstrap), rustc_blocking)]
     pub fn wait<'a, T>(&self, guard: MutexGuard<'a, T>) -> LockResult<MutexGuard<'a, T>> {
         let poisoned = unsafe {
             let lock = mutex::guard_lock(&guard);
@@ -235,6 +236,7 @@ pub fn wait<'a, T>(&self, guard: MutexGuard<'a, T>) -> LockResult<MutexGuard<'a,
     /// let _guard = cvar.wait_while(lock.lock().unwrap(), |pending| { *pending }).unwrap();
     /// ```
     #[stable(feature = "wait_until", since = "1.42.0")]
+    #[cfg_attr(not(bootstrap), rustc_blocking)]
     pub fn wait_while<'a, T, F>(
         &self,
         mut guard: MutexGuard<'a, T>,
@@ -304,6 +306,7 @@ pub fn wait_while<'a, T, F>(
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     #[deprecated(since = "1.6.0", note = "replaced by `std::sync::Condvar::wait_timeout`")]
+    #[cfg_attr(not(bootstrap), rustc_blocking)]
     pub fn wait_timeout_ms<'a, T>(
         &self,
         guard: MutexGuard<'a, T>,
@@ -375,6 +378,7 @@ pub fn wait_timeout_ms<'a, T>(
     /// }
     /// ```
     #[stable(feature = "wait_timeout", since = "1.5.0")]
+    #[cfg_attr(not(bootstrap), rustc_blocking)]
     pub fn wait_timeout<'a, T>(
         &self,
         guard: MutexGuard<'a, T>,
@@ -441,6 +445,7 @@ pub fn wait_timeout<'a, T>(
     /// // access the locked mutex via result.0
     /// ```
     #[stable(feature = "wait_timeout_until", since = "1.42.0")]
+    #[cfg_attr(not(bootstrap), rustc_blocking)]
     pub fn wait_timeout_while<'a, T, F>(
         &self,
         mut guard: MutexGuard<'a, T>,
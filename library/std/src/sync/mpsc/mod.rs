@@ -845,6 +845,7 @@ pub fn try_recv(&self) -> Result<T, TryRecvError> {
     /// assert_eq!(Err(RecvError), recv.recv());
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(bootstrap), rustc_blocking)]
     pub fn recv(&self) -> Result<T, RecvError> {
         self.inner.recv()
     }
@@ -905,6 +906,7 @@ pub fn recv(&self) -> Result<T, RecvError> {
     /// );
     /// ```
     #[stable(feature = "mpsc_recv_timeout", since = "1.12.0")]
+    #[cfg_attr(not(bootstrap), rustc_blocking)]
     pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
         self.inner.recv_timeout(timeout)
     }
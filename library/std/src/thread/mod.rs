@@ -865,6 +865,7 @@ pub fn sleep_ms(ms: u32) {
 /// assert!(now.elapsed() >= ten_millis);
 /// ```
 #[stable(feature = "thread_sleep", since = "1.4.0")]
+#[cfg_attr(not(bootstrap), rustc_blocking)]
 pub fn sleep(dur: Duration) {
     imp::Thread::sleep(dur)
 }
@@ -1058,6 +1059,7 @@ fn drop(&mut self) {
 /// [`thread::park_timeout`]: park_timeout
 /// [release sequence]: https://en.cppreference.com/w/cpp/atomic/memory_order#Release_sequence
 #[stable(feature = "rust1", since = "1.0.0")]
+#[cfg_attr(not(bootstrap), rustc_blocking)]
 pub fn park() {
     let guard = PanicGuard;
     // SAFETY: park_timeout is called on the parker owned by this thread.
@@ -1125,6 +1127,7 @@ pub fn park_timeout_ms(ms: u32) {
 /// }
 /// ```
 #[stable(feature = "park_timeout", since = "1.4.0")]
+#[cfg_attr(not(bootstrap), rustc_blocking)]
 pub fn park_timeout(dur: Duration) {
     let guard = PanicGuard;
     // SAFETY: park_timeout is called on the parker owned by this thread.
@@ -1654,6 +1657,7 @@ pub fn thread(&self) -> &Thread {
     /// join_handle.join().expect("Couldn't join on the associated thread");
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(bootstrap), rustc_blocking)]
     pub fn join(self) -> Result<T> {
         self.0.join()
     }
@@ -0,0 +1,26 @@
+//! Tests that the `unused_allow` lint tracks the allows of Clippy's lints as well.
+#![warn(unused_allow)]
+
+#[allow(clippy::needless_return)]
+fn used() -> u32 {
+    return 1;
+}
+
+#[allow(clippy::needless_return)]
+//~^ ERROR: this `#[allow]` never suppressed a lint
+//~| NOTE: `-D unused-allow` implied by `-D warnings`
+fn unused() -> u32 {
+    1
+}
+
+#[allow(clippy::needless_return, clippy::needless_bool)]
+//~^ ERROR: this `#[allow]` never suppressed a lint
+fn partially_used() -> u32 {
+    return 1;
+}
+
+fn main() {
+    used();
+    unused();
+    partially_used();
+}
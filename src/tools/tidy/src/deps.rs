@@ -359,6 +359,7 @@
     "tinystr",
     "tinyvec",
     "tinyvec_macros",
+    "toml",
     "tracing",
     "tracing-attributes",
     "tracing-core",
@@ -44,6 +44,11 @@
     "tests/ui/macros/syntax-extension-source-utils-files/includeme.fragment", // more include
     "tests/ui/proc-macro/auxiliary/included-file.txt", // more include
     "tests/ui/invalid/foo.natvis.xml", // sample debugger visualizer
+    "tests/ui/lint/lint-config/lint-config.toml", // lint configuration file
+    "tests/ui/lint/lint-config/lint-config-invalid.toml", // lint configuration file
+    "tests/ui/lint/lint-config/lint-config-unknown.toml", // lint configuration file
+    "tests/ui/lint/lint-config/lint-config-files.toml", // lint configuration file
+    "tests/ui/lint/lint-config/lint-config-large-assignments.toml", // lint configuration file
     "tests/ui/sanitizer/dataflow-abilist.txt", // dataflow sanitizer ABI list file
     "tests/ui/shell-argfiles/shell-argfiles.args", // passing args via a file
     "tests/ui/shell-argfiles/shell-argfiles-badquotes.args", // passing args via a file
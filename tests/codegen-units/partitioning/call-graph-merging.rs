@@ -0,0 +1,59 @@
+// We specify incremental here because we want to test the partitioning for
+//@ incremental compilation
+//@ incremental
+//@ compile-flags:-Zprint-mono-items=lazy
+//@ compile-flags:-Ccodegen-units=3 -Zcgu-partitioning=call-graph
+
+#![crate_type = "rlib"]
+
+// This test makes sure that the `call-graph` partitioning strategy merges CGUs
+// containing callers and callees.
+//
+// The functions get bigger from `aaa` to `eee`, one overflow-checked addition
+// at a time, so the five per-module CGUs are sorted as `eee`, `ddd`, `ccc`,
+// `bbb`, `aaa`, and two merges are needed to get down to three CGUs.
+//
+// First merge: `ccc` is the smallest of the three biggest CGUs, so one of the
+// other two gets merged into it. The `by-module` strategy would pick `bbb`,
+// the bigger one, as neither has inlined items in common with `ccc`. But `aaa`
+// calls into `ccc`, so it gets merged with `ccc` instead.
+//
+// Second merge: `aaa--ccc` is now the biggest CGU, which makes `ddd` the
+// smallest of the three biggest, and `bbb`, the only one left, is merged into
+// it. With `by-module`, the result would have been `bbb--ccc`, `aaa--ddd` and
+// `eee`.
+
+pub mod aaa {
+    //~ MONO_ITEM fn aaa::foo @@ call_graph_merging-aaa--call_graph_merging-ccc[External]
+    pub fn foo(a: u64) -> u64 {
+        crate::ccc::foo(a + 1 + 2 + 3 + 4 + 5 + 6)
+    }
+}
+
+pub mod bbb {
+    //~ MONO_ITEM fn bbb::foo @@ call_graph_merging-bbb--call_graph_merging-ddd[External]
+    pub fn foo(a: u64) -> u64 {
+        a + 1 + 2 + 3 + 4 + 5 + 6 + 7
+    }
+}
+
+pub mod ccc {
+    //~ MONO_ITEM fn ccc::foo @@ call_graph_merging-aaa--call_graph_merging-ccc[External]
+    pub fn foo(a: u64) -> u64 {
+        a + 1 + 2 + 3 + 4 + 5 + 6 + 7 + 8
+    }
+}
+
+pub mod ddd {
+    //~ MONO_ITEM fn ddd::foo @@ call_graph_merging-bbb--call_graph_merging-ddd[External]
+    pub fn foo(a: u64) -> u64 {
+        a + 1 + 2 + 3 + 4 + 5 + 6 + 7 + 8 + 9
+    }
+}
+
+pub mod eee {
+    //~ MONO_ITEM fn eee::foo @@ call_graph_merging-eee[External]
+    pub fn foo(a: u64) -> u64 {
+        a + 1 + 2 + 3 + 4 + 5 + 6 + 7 + 8 + 9 + 10
+    }
+}
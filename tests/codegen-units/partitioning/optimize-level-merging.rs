@@ -0,0 +1,51 @@
+// We specify incremental here because we want to test the partitioning for
+//@ incremental compilation
+//@ incremental
+//@ compile-flags:-Zprint-mono-items=lazy
+//@ compile-flags:-Ccodegen-units=1 -Copt-level=0
+
+#![feature(optimize_attribute)]
+#![crate_type = "rlib"]
+
+// This test makes sure that items with an `#[optimize(level = "...")]` of
+// their own get one CGU per level, whatever module they are in, and that
+// these CGUs count against `-Ccodegen-units`. Here, one CGU is left for the
+// items of `aaa` and `bbb` without a level of their own.
+
+pub mod aaa {
+    //~ MONO_ITEM fn aaa::foo @@ optimize_level_merging-aaa--optimize_level_merging-bbb[External]
+    pub fn foo(a: u64) -> u64 {
+        a + 1
+    }
+
+    //~ MONO_ITEM fn aaa::less @@ optimize_level_merging-opt-Less[External]
+    #[optimize(level = "1")]
+    pub fn less(a: u64) -> u64 {
+        a + 2
+    }
+
+    //~ MONO_ITEM fn aaa::aggressive @@ optimize_level_merging-opt-Aggressive[External]
+    #[optimize(level = "3")]
+    pub fn aggressive(a: u64) -> u64 {
+        a + 3
+    }
+}
+
+pub mod bbb {
+    //~ MONO_ITEM fn bbb::foo @@ optimize_level_merging-aaa--optimize_level_merging-bbb[External]
+    pub fn foo(a: u64) -> u64 {
+        a + 4
+    }
+
+    //~ MONO_ITEM fn bbb::less @@ optimize_level_merging-opt-Less[External]
+    #[optimize(level = "1")]
+    pub fn less(a: u64) -> u64 {
+        a + 5
+    }
+
+    //~ MONO_ITEM fn bbb::aggressive @@ optimize_level_merging-opt-Aggressive[External]
+    #[optimize(level = "3")]
+    pub fn aggressive(a: u64) -> u64 {
+        a + 6
+    }
+}
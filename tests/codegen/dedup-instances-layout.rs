@@ -0,0 +1,48 @@
+// Checks that instances of a generic function are not merged when the body depends on the layout
+// of a type argument that differs between them, even though the locals and the ABI of the
+// instances have the same layouts.
+//
+//@ compile-flags: -Copt-level=0 -Zdedup-instances -Ccodegen-units=1
+
+#![crate_type = "lib"]
+#![feature(core_intrinsics)]
+#![allow(internal_features)]
+
+use std::intrinsics;
+
+// CHECK-NOT: alias
+
+// Indexing depends on the size of the elements.
+// CHECK-COUNT-2: define {{.*}}4elem
+#[inline(never)]
+fn elem<T>(s: &[T], i: usize) -> &T {
+    &s[i]
+}
+
+// Pointer offsets depend on the size of the pointee.
+// CHECK-COUNT-2: define {{.*}}9offset_by
+#[inline(never)]
+fn offset_by<T>(p: *const T, i: usize) -> *const T {
+    unsafe { intrinsics::offset(p, i) }
+}
+
+// `size_of` depends on the size of its type argument.
+// CHECK-COUNT-2: define {{.*}}7size_of
+#[inline(never)]
+fn size_of<T>(_: &T) -> usize {
+    intrinsics::size_of::<T>()
+}
+
+#[no_mangle]
+pub fn a(s: &[u8], p: *const u8) -> usize {
+    elem(s, 1);
+    offset_by(p, 1);
+    size_of(&s[0])
+}
+
+#[no_mangle]
+pub fn b(s: &[u16], p: *const u16) -> usize {
+    elem(s, 1);
+    offset_by(p, 1);
+    size_of(&s[0])
+}
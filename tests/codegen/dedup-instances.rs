@@ -0,0 +1,29 @@
+// Checks that instances of a generic function that only differ in the signedness of an integer
+// type argument are emitted as an alias of a single body.
+//
+//@ compile-flags: -Copt-level=0 -Zdedup-instances -Ccodegen-units=1
+
+#![crate_type = "lib"]
+
+pub struct Wrapper<T> {
+    data: *const T,
+    len: usize,
+}
+
+// CHECK: @[[ALIAS:_ZN[A-Za-z0-9_]+3len[A-Za-z0-9_]+]] = {{.*}}alias {{.*}}@[[TARGET:_ZN[A-Za-z0-9_]+3len[A-Za-z0-9_]+]]
+// CHECK: define {{.*}}@[[TARGET]](
+// CHECK-NOT: define {{.*}}3len
+#[inline(never)]
+fn len<T>(w: &Wrapper<T>) -> usize {
+    w.len
+}
+
+#[no_mangle]
+pub fn a(w: &Wrapper<u32>) -> usize {
+    len(w)
+}
+
+#[no_mangle]
+pub fn b(w: &Wrapper<i32>) -> usize {
+    len(w)
+}
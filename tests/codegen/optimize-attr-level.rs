@@ -0,0 +1,41 @@
+//@ compile-flags: -Copt-level=3 -Ccodegen-units=1
+
+// Checks that `#[optimize(level = "...")]` with levels `0`, `s` and `z` is applied with function
+// attributes, and that `level = "0"` disables optimizations of the function.
+
+#![feature(optimize_attribute)]
+#![crate_type = "rlib"]
+
+#[inline(never)]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+// CHECK-LABEL: define{{.*}}i32 @unoptimized
+// CHECK-SAME: [[UNOPTIMIZED_ATTRS:#[0-9]+]]
+// CHECK: call{{.*}}add
+#[optimize(level = "0")]
+#[no_mangle]
+pub fn unoptimized() -> i32 {
+    add(4, 4)
+}
+
+// CHECK-LABEL: define{{.*}}i32 @small
+// CHECK-SAME: [[SMALL_ATTRS:#[0-9]+]]
+#[optimize(level = "s")]
+#[no_mangle]
+pub fn small() -> i32 {
+    add(5, 5)
+}
+
+// CHECK-LABEL: define{{.*}}i32 @tiny
+// CHECK-SAME: [[TINY_ATTRS:#[0-9]+]]
+#[optimize(level = "z")]
+#[no_mangle]
+pub fn tiny() -> i32 {
+    add(6, 6)
+}
+
+// CHECK-DAG: attributes [[UNOPTIMIZED_ATTRS]] = {{.*}}noinline{{.*}}optnone
+// CHECK-DAG: attributes [[SMALL_ATTRS]] = {{.*}}optsize
+// CHECK-DAG: attributes [[TINY_ATTRS]] = {{.*}}minsize{{.*}}optsize
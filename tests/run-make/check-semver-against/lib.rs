@@ -0,0 +1,89 @@
+#![crate_name = "semver"]
+#![crate_type = "lib"]
+
+#[cfg(old)]
+pub fn removed() {}
+
+#[cfg(not(old))]
+pub fn added() {}
+
+#[cfg(old)]
+pub fn signature(_: u8) {}
+#[cfg(not(old))]
+pub fn signature(_: u16) {}
+
+#[cfg(old)]
+pub fn bounds<T: Clone>(_: T) {}
+#[cfg(not(old))]
+pub fn bounds<T: Clone + Send>(_: T) {}
+
+#[cfg(old)]
+pub fn loosened<T: Clone + Send>(_: T) {}
+#[cfg(not(old))]
+pub fn loosened<T: Clone>(_: T) {}
+
+#[cfg(old)]
+pub fn renamed<'a, T: Clone>(x: &'a T) -> &'a T {
+    x
+}
+#[cfg(not(old))]
+pub fn renamed<'b, U: Clone>(y: &'b U) -> &'b U {
+    y
+}
+
+#[cfg(old)]
+pub fn implied<T: Copy>(_: T) {}
+#[cfg(not(old))]
+pub fn implied<T: Clone + Copy>(_: T) {}
+
+pub trait Base {}
+pub trait Sub: Base {}
+
+#[cfg(old)]
+pub fn moved<T: Sub + Base>(_: T) {}
+#[cfg(not(old))]
+pub fn moved<T: Sub>(_: T) {}
+
+pub struct ExplicitlySend(*const u8);
+unsafe impl Send for ExplicitlySend {}
+
+pub struct Fields {
+    pub a: u8,
+    #[cfg(not(old))]
+    pub b: u8,
+}
+
+#[cfg(old)]
+pub struct Sendable(u8);
+#[cfg(not(old))]
+pub struct Sendable(*const u8);
+
+pub fn opaque() -> impl Sized {
+    #[cfg(old)]
+    let value = 0u8;
+    #[cfg(not(old))]
+    let value = std::rc::Rc::new(0u8);
+    value
+}
+
+pub enum Open {
+    A,
+    #[cfg(not(old))]
+    B,
+}
+
+#[non_exhaustive]
+#[cfg_attr(not(old), derive(Clone))]
+pub enum Closed {
+    A,
+    #[cfg(not(old))]
+    B,
+}
+
+pub trait Trait {
+    fn provided() {}
+    #[cfg(not(old))]
+    fn provided_later() {}
+    #[cfg(not(old))]
+    fn required();
+}
@@ -0,0 +1,56 @@
+// Checks that `--check-semver-against` reports the differences between two versions of a crate,
+// and nothing when its public API did not change.
+
+extern crate run_make_support;
+
+use run_make_support::{rustc, tmp_dir};
+
+fn check_against_baseline(old: bool) -> String {
+    let baseline = tmp_dir().join("libsemver-old.rmeta");
+    let mut cmd = rustc();
+    if old {
+        cmd.cfg("old");
+    }
+    let output = cmd
+        .input("lib.rs")
+        .arg("--emit=metadata")
+        .arg("-Zunstable-options")
+        .arg(&format!("--check-semver-against={}", baseline.display()))
+        .run();
+    String::from_utf8(output.stderr).unwrap()
+}
+
+fn main() {
+    rustc().input("lib.rs").cfg("old").arg("--emit=metadata").arg("-Cmetadata=old").run();
+    std::fs::rename(tmp_dir().join("libsemver.rmeta"), tmp_dir().join("libsemver-old.rmeta"))
+        .unwrap();
+
+    let stderr = check_against_baseline(true);
+    assert!(!stderr.contains("warning"), "{stderr}");
+
+    let stderr = check_against_baseline(false);
+    for expected in [
+        "function `removed` was removed",
+        "function `added` was added",
+        "the signature of function `signature` changed",
+        "function `bounds` has stricter bounds",
+        "function `loosened` has looser bounds",
+        "field `Fields::b` was added",
+        "struct `Sendable` no longer implements `Send`",
+        "the return type of function `opaque` no longer implements `Send`",
+        "variant `Open::B` was added",
+        "variant `Closed::B` was added",
+        "associated function `Trait::required` was added",
+        "associated function `Trait::provided_later` was added",
+        "implementation of `Clone` for `Closed` was added",
+        "`#[warn(semver_major_changes)]` on by default",
+        "`#[warn(semver_minor_changes)]` on by default",
+    ] {
+        assert!(stderr.contains(expected), "missing `{expected}` in:\n{stderr}");
+    }
+    // Renaming generic parameters does not change the API, and neither do equivalent bounds or
+    // explicit impls of auto traits.
+    for unexpected in ["renamed", "implied", "moved", "ExplicitlySend"] {
+        assert!(!stderr.contains(unexpected), "unexpected `{unexpected}` in:\n{stderr}");
+    }
+}
@@ -1 +1,8 @@
 pub fn bar() {}
+
+fn generic<T>(_: T) {}
+
+pub fn baz() {
+    generic(0u8);
+    generic(0u16);
+}
@@ -0,0 +1,13 @@
+#![crate_type = "lib"]
+
+pub trait Speak {}
+
+pub struct Loud;
+
+impl Speak for Loud {}
+
+pub fn require<T: Speak>() {}
+
+pub fn speak() {
+    require::<Loud>();
+}
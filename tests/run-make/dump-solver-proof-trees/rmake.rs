@@ -0,0 +1,34 @@
+// Checks that `-Zdump-solver-proof-trees` writes the proof trees of the next trait solver as
+// collapsible and searchable HTML documents.
+
+extern crate run_make_support;
+
+use run_make_support::{rustc, tmp_dir};
+
+fn main() {
+    let dir = tmp_dir().join("proof-trees");
+    rustc()
+        .input("lib.rs")
+        .arg("-Znext-solver")
+        .arg(&format!("-Zdump-solver-proof-trees={}", dir.display()))
+        .run();
+
+    let documents: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    assert!(!documents.is_empty());
+    for document in &documents {
+        assert!(document.starts_with("<!DOCTYPE html>"));
+        assert!(document.contains("<details><summary"));
+        assert!(document.contains("id=\"search\""));
+        // Nothing is expanded by default.
+        assert!(!document.contains("<details open"));
+    }
+    assert!(
+        documents.iter().any(|document| document.contains("Loud as Speak")
+            && document.contains("ROOT GOAL")
+            && document.contains("class=\"yes\"")),
+        "no proof tree for `Loud: Speak`"
+    );
+}
@@ -0,0 +1,10 @@
+#[inline(never)]
+fn count_ones<T: Copy + Into<u64>>(values: &[T]) -> u32 {
+    values.iter().map(|&value| value.into().count_ones()).sum()
+}
+
+fn main() {
+    let bytes = std::hint::black_box([1u8, 2, 3]);
+    let words = std::hint::black_box([1u32, 2, 3]);
+    println!("{}", count_ones(&bytes) + count_ones(&words));
+}
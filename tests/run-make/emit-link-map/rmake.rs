@@ -0,0 +1,28 @@
+// Checks the link map written by `--emit=link-map`, and that the emission type is unstable.
+
+//@ only-linux
+
+extern crate run_make_support;
+
+use run_make_support::{run, rustc, tmp_dir};
+
+fn main() {
+    rustc().input("main.rs").arg("-Zunstable-options").emit("link-map").run();
+    run("main");
+
+    let link_map = std::fs::read_to_string(tmp_dir().join("main.link-map")).unwrap();
+    for expected in ["Link map of", "Size by crate:", "Size by generic definition:"] {
+        assert!(link_map.contains(expected), "missing `{expected}` in:\n{link_map}");
+    }
+    let has_line_ending_with = |suffix: &str| link_map.lines().any(|line| line.ends_with(suffix));
+    assert!(has_line_ending_with("  std"), "{link_map}");
+    assert!(has_line_ending_with("  core"), "{link_map}");
+    // Both instances of `count_ones` are grouped under their generic definition.
+    assert!(has_line_ending_with(" 2  main::count_ones"), "{link_map}");
+    assert!(link_map.contains("main::count_ones (main, "), "{link_map}");
+
+    let output = rustc().input("main.rs").emit("link-map").output();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("the `-Z unstable-options` flag must also be passed"), "{stderr}");
+}
@@ -0,0 +1,25 @@
+#[inline(never)]
+fn leaf(x: u64) -> u64 {
+    let buffer = std::hint::black_box([x; 16]);
+    buffer.iter().sum()
+}
+
+#[inline(never)]
+fn chain(x: u64) -> u64 {
+    leaf(x) + 1
+}
+
+#[inline(never)]
+fn recursive(n: u64) -> u64 {
+    if n == 0 { 0 } else { recursive(n - 1) + 1 }
+}
+
+#[inline(never)]
+fn indirect(f: fn(u64) -> u64) -> u64 {
+    f(3)
+}
+
+fn main() {
+    let f = std::hint::black_box(leaf as fn(u64) -> u64);
+    println!("{} {} {}", chain(1), recursive(2), indirect(f));
+}
@@ -0,0 +1,33 @@
+// Checks the stack usage report written by `--emit=stack-sizes`, and that the emission type is
+// unstable.
+
+//@ only-linux
+
+extern crate run_make_support;
+
+use run_make_support::{run, rustc, tmp_dir};
+
+fn main() {
+    rustc()
+        .input("main.rs")
+        .arg("-Zunstable-options")
+        .emit("stack-sizes,link")
+        .arg("-Copt-level=1")
+        .run();
+    run("main");
+
+    let report = std::fs::read_to_string(tmp_dir().join("main.stack-sizes")).unwrap();
+    for expected in [
+        "Stack usage of `main`:",
+        "deepest call chain: main::chain -> main::leaf",
+        "unbounded: recursion through `main::recursive`",
+        "unbounded: indirect call in `main::indirect`",
+    ] {
+        assert!(report.contains(expected), "missing `{expected}` in:\n{report}");
+    }
+
+    let output = rustc().input("main.rs").emit("stack-sizes").output();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("the `-Z unstable-options` flag must also be passed"), "{stderr}");
+}
@@ -0,0 +1,4 @@
+// Test the arguments rustc passes to mold via the `gnu-mold` linker flavors, and the fallback to
+// the GNU linker when mold is missing.
+
+fn main() {}
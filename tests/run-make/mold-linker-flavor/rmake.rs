@@ -0,0 +1,61 @@
+// Test the arguments rustc passes to mold via the `gnu-mold` linker flavors, and the fallback to
+// the GNU linker when mold is missing.
+
+//@ only-linux
+
+extern crate run_make_support;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use run_make_support::{rustc, tmp_dir};
+
+/// Installs a linker called `name` into `bin`, which records the arguments it's invoked with into
+/// `args.txt` instead of linking.
+fn install_fake_linker(bin: &Path, name: &str) {
+    let linker = bin.join(name);
+    let args = bin.join("args.txt");
+    fs::write(&linker, format!("#!/bin/sh\necho \"$@\" > \"{}\"\n", args.display())).unwrap();
+    fs::set_permissions(&linker, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn main() {
+    let bin = tmp_dir().join("bin");
+    fs::create_dir(&bin).unwrap();
+
+    install_fake_linker(&bin, "mold");
+    rustc()
+        .input("main.rs")
+        .arg("-Zunstable-options")
+        .arg("-Clinker-flavor=gnu-mold")
+        .arg(&format!("-Clinker={}", bin.join("mold").display()))
+        .arg("-Zlinker-threads=2")
+        .run();
+    let args = fs::read_to_string(bin.join("args.txt")).unwrap();
+    assert!(args.contains("--thread-count=2"), "{args}");
+
+    // Through `cc`, mold is selected with `-fuse-ld=mold`.
+    install_fake_linker(&bin, "cc");
+    rustc()
+        .input("main.rs")
+        .arg("-Zunstable-options")
+        .arg("-Clinker-flavor=gnu-mold-cc")
+        .env("PATH", &bin)
+        .run();
+    let args = fs::read_to_string(bin.join("args.txt")).unwrap();
+    assert!(args.contains("-fuse-ld=mold"), "{args}");
+
+    // Without mold in the `PATH`, `cc` is used without `-fuse-ld=mold`.
+    fs::remove_file(bin.join("mold")).unwrap();
+    let output = rustc()
+        .input("main.rs")
+        .arg("-Zunstable-options")
+        .arg("-Clinker-flavor=gnu-mold-cc")
+        .env("PATH", &bin)
+        .run();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("falling back to linker flavor `gnu-cc`"), "{stderr}");
+    let args = fs::read_to_string(bin.join("args.txt")).unwrap();
+    assert!(!args.contains("-fuse-ld=mold"), "{args}");
+}
@@ -0,0 +1,25 @@
+#![feature(optimize_attribute)]
+#![crate_type = "rlib"]
+
+#[no_mangle]
+pub fn unoptimized(n: u32) -> u32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < n {
+        sum ^= i;
+        i += 1;
+    }
+    sum
+}
+
+#[optimize(level = "3")]
+#[no_mangle]
+pub fn optimized(n: u32) -> u32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < n {
+        sum ^= i;
+        i += 1;
+    }
+    sum
+}
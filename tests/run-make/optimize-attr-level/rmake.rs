@@ -0,0 +1,45 @@
+// Checks that `#[optimize(level = "...")]` optimizes a function in an otherwise unoptimized crate
+// by placing it in a codegen unit of its own, which is optimized at that level, while the rest of
+// the crate is left unoptimized.
+
+extern crate run_make_support;
+
+use run_make_support::{rustc, tmp_dir};
+
+/// Returns the name of the LLVM IR file defining the function `name`, and its body.
+fn function(name: &str) -> (String, String) {
+    for entry in std::fs::read_dir(tmp_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "ll") {
+            let ir = std::fs::read_to_string(&path).unwrap();
+            let mut lines = ir.lines().skip_while(|line| {
+                !(line.starts_with("define") && line.contains(&format!("@{name}(")))
+            });
+            if let Some(define) = lines.next() {
+                let body: Vec<&str> = lines.take_while(|line| *line != "}").collect();
+                let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
+                return (file_name, format!("{define}\n{}", body.join("\n")));
+            }
+        }
+    }
+    panic!("`{name}` is not defined");
+}
+
+fn main() {
+    rustc()
+        .input("lib.rs")
+        .arg("-Copt-level=0")
+        .arg("-Coverflow-checks=off")
+        .arg("-Zhuman-readable-cgu-names")
+        .emit("llvm-ir")
+        .run();
+
+    let (unoptimized_file, unoptimized) = function("unoptimized");
+    let (optimized_file, optimized) = function("optimized");
+    assert_ne!(unoptimized_file, optimized_file);
+    assert!(optimized_file.contains("opt-Aggressive"), "{optimized_file}");
+
+    // The loop variables live on the stack without optimizations, and in registers with them.
+    assert!(unoptimized.contains("alloca"), "{unoptimized}");
+    assert!(!optimized.contains("alloca"), "{optimized}");
+}
@@ -0,0 +1,35 @@
+#[no_mangle]
+pub fn cold_function(c: u8) {
+    println!("cold {}", c);
+}
+
+#[cfg(not(stale))]
+#[no_mangle]
+pub fn hot_function(c: u8) {
+    std::env::set_var(format!("var{}", c), format!("hot {}", c));
+}
+
+// The control flow of this version differs from the one the profiles were collected for.
+#[cfg(stale)]
+#[no_mangle]
+pub fn hot_function(c: u8) {
+    if c.is_ascii_digit() {
+        std::env::set_var(format!("digit{}", c), format!("hot {}", c));
+    } else {
+        std::env::set_var(format!("var{}", c), format!("hot {}", c));
+    }
+}
+
+fn main() {
+    let arg = std::env::args().nth(1).unwrap_or_else(|| "some-argument".to_string());
+
+    for i in 0 .. 1000_000 {
+        let some_value = arg.as_bytes()[i % arg.len()];
+        if some_value == b'!' {
+            // This branch is never taken at runtime
+            cold_function(some_value);
+        } else {
+            hot_function(some_value);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// This test is like `pgo-use`, except that the raw profiles are passed to `-Cprofile-use` directly
+// instead of being merged with `llvm-profdata` first. It also checks that functions that changed
+// since the profiles were collected are reported as stale.
+
+//@ needs-profiler-support
+//@ ignore-cross-compile
+
+// FIXME(mati865): MinGW GCC miscompiles compiler-rt profiling library but with Clang it works
+// properly. Since we only have GCC on the CI ignore the test for now.
+//@ ignore-windows-gnu
+
+extern crate run_make_support;
+
+use run_make_support::{run, rustc, tmp_dir, Rustc};
+
+fn compile(profile: &str) -> Rustc {
+    let mut cmd = rustc();
+    cmd.input("main.rs")
+        .arg("-Copt-level=2")
+        .arg("-Ccodegen-units=1")
+        .arg("-Cllvm-args=-disable-preinline")
+        .arg(&format!("-Cprofile-use={}", tmp_dir().join(profile).display()));
+    cmd
+}
+
+/// Returns the attributes of the function called `name` in the LLVM IR `ir`.
+fn function_attrs<'a>(ir: &'a str, name: &str) -> &'a str {
+    let lines: Vec<&str> = ir.lines().collect();
+    let define = lines
+        .iter()
+        .position(|line| line.starts_with("define") && line.contains(&format!("@{name}(")))
+        .unwrap_or_else(|| panic!("`{name}` is not defined"));
+    lines[..define]
+        .iter()
+        .rev()
+        .copied()
+        .find(|line| line.starts_with("; Function Attrs:"))
+        .unwrap_or_else(|| panic!("`{name}` has no attributes"))
+}
+
+fn main() {
+    // Compile the test program with instrumentation, and run it to generate some profiling data.
+    rustc()
+        .input("main.rs")
+        .arg("-Copt-level=2")
+        .arg("-Ccodegen-units=1")
+        .arg("-Cllvm-args=-disable-preinline")
+        .arg(&format!("-Cprofile-generate={}", tmp_dir().join("profiles").display()))
+        .run();
+    run("main");
+
+    // Raw profiles are unstable.
+    let output = compile("profiles").output();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("passing raw profiles to `-C profile-use` is unstable"), "{stderr}");
+
+    // Compile the test program again, passing the directory with the raw profiles.
+    let output = compile("profiles").arg("-Zunstable-options").emit("llvm-ir,dep-info").run();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("stale"), "{stderr}");
+
+    let ir = std::fs::read_to_string(tmp_dir().join("main.ll")).unwrap();
+    assert!(ir.contains(r#"!{!"ProfileFormat", !"InstrProf"}"#));
+    assert!(ir.contains(r#"!"ProfileSummary""#));
+    // The hot function is marked with `inlinehint`, and the cold function with `cold`.
+    assert!(function_attrs(&ir, "hot_function").contains("inlinehint"));
+    assert!(function_attrs(&ir, "cold_function").contains("cold"));
+
+    // The raw profiles are tracked in the dep-info file.
+    let dep_info = std::fs::read_to_string(tmp_dir().join("main.d")).unwrap();
+    assert!(dep_info.contains(".profraw"), "{dep_info}");
+
+    // The merged profile is a temporary file.
+    assert!(!tmp_dir().join("main.merged.profdata").exists());
+
+    // Changing the control flow of a profiled function makes its profile stale.
+    let output = compile("profiles").arg("-Zunstable-options").cfg("stale").run();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("the profile data is likely stale"), "{stderr}");
+    assert!(stderr.contains("hot_function"), "{stderr}");
+
+    // An empty profile directory is an error.
+    std::fs::create_dir(tmp_dir().join("empty")).unwrap();
+    let output = compile("empty").arg("-Zunstable-options").output();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("contains no `.profraw` files"), "{stderr}");
+}
@@ -0,0 +1,10 @@
+extern crate bar;
+
+use bar::{make_bar, Bar};
+
+fn main() {
+    let b: Bar = make_bar(3);
+    b.print();
+    #[cfg(changed)]
+    make_bar(4).print();
+}
@@ -0,0 +1,97 @@
+// Checks that incremental builds with `-Csplit-debuginfo=packed` reuse the DWARF packages of the
+// local crate and of its dependencies while their inputs don't change, and that
+// `-Zdebuginfo-compression` applies to the `.dwp`.
+
+//@ only-linux
+//@ ignore-cross-compile
+
+extern crate run_make_support;
+
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
+
+use run_make_support::object::{self, Object, ObjectSection};
+use run_make_support::{rustc, tmp_dir};
+
+/// Builds `main.rs` incrementally, with `cfg` set if any.
+fn build_main(cfg: Option<&str>) {
+    let mut rustc = rustc();
+    rustc
+        .input("main.rs")
+        .extern_("bar", tmp_dir().join("libbar.rlib"))
+        .arg("-Csplit-debuginfo=packed")
+        .arg("-Cdebuginfo=2")
+        .arg(&format!("-Cincremental={}", tmp_dir().join("incr").display()));
+    if let Some(cfg) = cfg {
+        rustc.cfg(cfg);
+    }
+    rustc.run();
+}
+
+/// Returns the modification time and inode of the incremental package `name`. The session
+/// directory is a new one for each build, and the files reused from the previous session are
+/// hard links to the ones in it.
+fn package_identity(name: &str) -> (i64, i64, u64) {
+    let mut packages = Vec::<PathBuf>::new();
+    for crate_dir in fs::read_dir(tmp_dir().join("incr")).unwrap() {
+        // The session directories sit next to their lock files.
+        for session_dir in fs::read_dir(crate_dir.unwrap().path()).unwrap() {
+            let path = session_dir.unwrap().path().join(name);
+            if path.is_file() {
+                packages.push(path);
+            }
+        }
+    }
+    assert_eq!(packages.len(), 1, "{packages:?}");
+    let metadata = fs::metadata(&packages[0]).unwrap();
+    (metadata.mtime(), metadata.mtime_nsec(), metadata.ino())
+}
+
+fn main() {
+    rustc()
+        .input("bar.rs")
+        .arg("--crate-type=lib")
+        .arg("-Csplit-debuginfo=packed")
+        .arg("-Cdebuginfo=2")
+        .run();
+
+    build_main(None);
+    let dependencies = package_identity("dependencies.dwp");
+    let local = package_identity("crate.dwp");
+
+    // Nothing changed, so both packages are reused as they are.
+    build_main(None);
+    assert_eq!(package_identity("dependencies.dwp"), dependencies);
+    assert_eq!(package_identity("crate.dwp"), local);
+
+    // Only the local crate changed, so only its package is rewritten.
+    build_main(Some("changed"));
+    assert_eq!(package_identity("dependencies.dwp"), dependencies);
+    assert_ne!(package_identity("crate.dwp"), local);
+
+    // The debug sections of the package are compressed with zlib.
+    rustc()
+        .input("main.rs")
+        .extern_("bar", tmp_dir().join("libbar.rlib"))
+        .arg("-Csplit-debuginfo=packed")
+        .arg("-Cdebuginfo=2")
+        .arg("-Zdebuginfo-compression=zlib")
+        .run();
+    let dwp = fs::read(tmp_dir().join("main.dwp")).unwrap();
+    let dwp = object::File::parse(&*dwp).unwrap();
+    let debug_info = dwp.section_by_name(".debug_info.dwo").unwrap();
+    let object::SectionFlags::Elf { sh_flags } = debug_info.flags() else { unreachable!() };
+    assert_ne!(sh_flags & u64::from(object::elf::SHF_COMPRESSED), 0);
+
+    // zstd isn't supported for packages, which are then written uncompressed.
+    let output = rustc()
+        .input("main.rs")
+        .extern_("bar", tmp_dir().join("libbar.rlib"))
+        .arg("-Csplit-debuginfo=packed")
+        .arg("-Cdebuginfo=2")
+        .arg("-Zdebuginfo-compression=zstd")
+        .run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is not supported for DWARF packages"), "{stderr}");
+}
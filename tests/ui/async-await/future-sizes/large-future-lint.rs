@@ -0,0 +1,35 @@
+//@ compile-flags: -Z future-size-limit=1024 --crate-type lib
+//@ edition:2021
+//@ build-pass
+
+use std::future::Future;
+use std::hint::black_box;
+use std::pin::pin;
+use std::task::Context;
+
+async fn wait() {}
+
+async fn big() {
+    let buf = [0u8; 4096];
+    wait().await;
+    black_box(buf);
+}
+
+async fn small() {
+    let buf = [0u8; 16];
+    wait().await;
+    black_box(buf);
+}
+
+#[allow(large_futures)]
+async fn allowed() {
+    let buf = [0u8; 4096];
+    wait().await;
+    black_box(buf);
+}
+
+pub fn poll_all(cx: &mut Context<'_>) {
+    let _ = pin!(big()).poll(cx);
+    let _ = pin!(small()).poll(cx);
+    let _ = pin!(allowed()).poll(cx);
+}
@@ -0,0 +1,16 @@
+#![feature(optimize_attribute)]
+#![allow(dead_code)]
+
+#[optimize(level = "3")]
+fn fast() {}
+
+#[optimize(level = "z")]
+fn tiny() {}
+
+#[optimize(level = "4")] //~ ERROR invalid optimization level
+fn too_fast() {}
+
+#[optimize(level)] //~ ERROR invalid optimization level
+fn no_level() {}
+
+fn main() {}
@@ -0,0 +1,43 @@
+//@ run-pass
+//@ compile-flags: -Zcoroutine-refined-layout
+//@ only-64bit
+
+// Check that letting always-live locals overlap with other saved locals keeps
+// every value intact across suspension points, including values that are moved
+// out of before a later yield, and that locals which are not live at the same
+// time still share their space.
+
+#![feature(coroutines, coroutine_trait)]
+
+use std::mem::size_of_val;
+use std::ops::{Coroutine, CoroutineState};
+use std::pin::Pin;
+
+fn consume(v: Vec<u32>) -> u32 {
+    v.into_iter().sum()
+}
+
+fn main() {
+    let mut co = || {
+        let a = vec![1, 2, 3];
+        yield 0;
+        let sum = consume(a);
+        let b = vec![sum; 4];
+        yield sum;
+        let c = [7u8; 64];
+        yield consume(b);
+        c.iter().map(|&x| x as u32).sum::<u32>()
+    };
+
+    // `a` is moved out of before `b` and `c` are assigned, so it can share its
+    // slot with either of them. Without any overlap, the three locals alone
+    // would take up 24 + 24 + 64 = 112 bytes, plus the discriminant.
+    let size = size_of_val(&co);
+    assert!(size <= 96, "coroutine is {size} bytes");
+
+    let mut co = Pin::new(&mut co);
+    assert_eq!(co.as_mut().resume(()), CoroutineState::Yielded(0));
+    assert_eq!(co.as_mut().resume(()), CoroutineState::Yielded(6));
+    assert_eq!(co.as_mut().resume(()), CoroutineState::Yielded(24));
+    assert_eq!(co.as_mut().resume(()), CoroutineState::Complete(448));
+}
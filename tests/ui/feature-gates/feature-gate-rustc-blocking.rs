@@ -0,0 +1,6 @@
+// Test that `#[rustc_blocking]` is feature gated.
+
+#[rustc_blocking] //~ ERROR the `#[rustc_blocking]` attribute is an experimental feature
+fn wait() {}
+
+fn main() {}
@@ -0,0 +1,7 @@
+//@ compile-flags: --target=x86_64-pc-windows-gnu -Z unstable-options -C linker-flavor=gnu-mold
+//@ compile-flags: --crate-type=rlib
+//@ error-pattern: linker flavor `gnu-mold` can only be used on targets producing ELF files
+//@ needs-llvm-components: x86
+
+#![feature(no_core)]
+#![no_core]
@@ -0,0 +1,6 @@
+#![feature(rustc_blocking)]
+
+#[rustc_blocking]
+pub fn read_sensor() -> u32 {
+    0
+}
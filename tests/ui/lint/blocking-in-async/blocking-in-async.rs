@@ -0,0 +1,55 @@
+// Checks that calls to functions marked with `#[rustc_blocking]` are linted in `async` bodies,
+// both for the functions of the standard library and those of other crates.
+
+//@ edition: 2021
+//@ aux-build: blocking-api.rs
+
+#![deny(blocking_in_async)]
+#![feature(rustc_blocking)]
+
+extern crate blocking_api;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[rustc_blocking]
+fn wait_for_input() {}
+
+trait Fetch {
+    fn fetch(&self);
+}
+
+struct Remote;
+
+impl Fetch for Remote {
+    #[rustc_blocking]
+    fn fetch(&self) {}
+}
+
+async fn in_async_fn(mutex: &Mutex<i32>) {
+    std::thread::sleep(Duration::from_millis(1)); //~ ERROR call to the blocking function
+    let _guard = mutex.lock(); //~ ERROR call to the blocking function
+    let _ = std::fs::read_to_string("config.toml"); //~ ERROR call to the blocking function
+    wait_for_input(); //~ ERROR call to the blocking function
+    blocking_api::read_sensor(); //~ ERROR call to the blocking function
+    Remote.fetch(); //~ ERROR call to the blocking function
+}
+
+fn in_async_block() -> impl std::future::Future<Output = ()> {
+    async {
+        wait_for_input(); //~ ERROR call to the blocking function
+    }
+}
+
+async fn in_closure() {
+    // Closures have their own body, and are commonly run on another thread.
+    let f = || wait_for_input();
+    f();
+    let _ = std::thread::spawn(|| wait_for_input()).join(); //~ ERROR call to the blocking function
+}
+
+fn not_async() {
+    wait_for_input();
+}
+
+fn main() {}
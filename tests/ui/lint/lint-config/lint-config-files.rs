@@ -0,0 +1,13 @@
+// Checks that the overrides of the lint configuration file match modules by the path of the
+// source file of their contents.
+
+//@ compile-flags: -Zunstable-options
+//@ compile-flags: --lint-config={{src-base}}/lint/lint-config/lint-config-files.toml
+
+#[path = "sub/generated.rs"]
+mod generated;
+
+fn main() {
+    let x = 1; //~ ERROR unused variable: `x`
+    generated::f();
+}
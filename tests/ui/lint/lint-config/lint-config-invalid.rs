@@ -0,0 +1,6 @@
+// Checks the error for a malformed lint configuration file.
+
+//@ compile-flags: -Zunstable-options --lint-config={{src-base}}/lint/lint-config/lint-config-invalid.toml
+//@ error-pattern: invalid lint configuration file
+
+fn main() {}
@@ -0,0 +1,18 @@
+// Checks that the `limit` parameter of `large_assignments` in the lint configuration file sets
+// the move size limit, without a `#![move_size_limit]` attribute.
+
+//@ build-fail
+//@ compile-flags: -Zunstable-options
+//@ compile-flags: --lint-config={{src-base}}/lint/lint-config/lint-config-large-assignments.toml
+
+#![allow(unused)]
+
+// Note: This type does not implement Copy.
+struct Data([u8; 9999]);
+
+fn main() {
+    let data = Data([100; 9999]); //~ ERROR large_assignments
+    take_data(data);
+}
+
+fn take_data(data: Data) {}
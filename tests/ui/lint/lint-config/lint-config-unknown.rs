@@ -0,0 +1,6 @@
+// Checks that unknown lints in the lint configuration file are reported.
+
+//@ check-pass
+//@ compile-flags: -Zunstable-options --lint-config={{src-base}}/lint/lint-config/lint-config-unknown.toml
+
+fn main() {}
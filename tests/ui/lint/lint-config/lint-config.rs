@@ -0,0 +1,27 @@
+// Checks the lint levels set by the lint configuration file, the overrides of some modules,
+// and that lint attributes take precedence over both.
+
+//@ compile-flags: -Zunstable-options --lint-config={{src-base}}/lint/lint-config/lint-config.toml
+
+fn main() {
+    let x = 1; //~ ERROR unused variable: `x`
+}
+
+mod generated {
+    pub fn f() {
+        let y = 1;
+    }
+
+    pub mod nested {
+        pub fn g() {
+            let z = 1;
+        }
+    }
+}
+
+mod attr {
+    #[warn(unused_variables)]
+    pub fn h() {
+        let w = 1; //~ WARN unused variable: `w`
+    }
+}
@@ -0,0 +1,3 @@
+pub fn f() {
+    let y = 1;
+}
@@ -0,0 +1,20 @@
+// A value that is only moved out on some paths may still be held across the suspend point.
+
+//@ edition:2018
+#![feature(must_not_suspend)]
+#![deny(must_not_suspend)]
+
+use std::sync::Mutex;
+
+async fn other() {}
+
+pub async fn dropped_conditionally(m: &Mutex<i32>, early: bool) {
+    let mut guard = m.lock().unwrap(); //~ ERROR `MutexGuard` held across
+    *guard += 1;
+    if early {
+        drop(guard);
+    }
+    other().await;
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+// Values moved out before a suspend point are not held across it, even if they were borrowed.
+
+//@ edition:2018
+//@ check-pass
+#![feature(must_not_suspend)]
+#![deny(must_not_suspend)]
+
+use std::sync::{Mutex, MutexGuard};
+
+async fn other() {}
+
+fn consume(_guard: MutexGuard<'_, i32>) {}
+
+pub async fn dropped(m: &Mutex<i32>) {
+    let mut guard = m.lock().unwrap();
+    *guard += 1;
+    drop(guard);
+    other().await;
+}
+
+pub async fn moved(m: &Mutex<i32>) {
+    let guard = m.lock().unwrap();
+    consume(guard);
+    other().await;
+}
+
+pub async fn dropped_in_loop(m: &Mutex<i32>) {
+    for _ in 0..3 {
+        let mut guard = m.lock().unwrap();
+        *guard += 1;
+        drop(guard);
+        other().await;
+    }
+}
+
+fn main() {}
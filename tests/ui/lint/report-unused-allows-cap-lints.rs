@@ -0,0 +1,12 @@
+//@ check-pass
+//@ compile-flags: -Zreport-unused-allows --cap-lints=allow
+
+// The unused allows are reported through the `unused_allow` lint, so `--cap-lints`, which is
+// used for dependencies, silences the reports and their summary.
+
+#[allow(non_camel_case_types)] // not used
+struct Foo;
+
+fn main() {
+    let _ = Foo;
+}
@@ -0,0 +1,38 @@
+//@ check-pass
+//@ compile-flags: -Zreport-unused-allows
+
+// Reports the `#[allow]` attributes that never suppressed a lint, whether it is a late or an early
+// lint, and tracks each of the lints of an attribute on its own.
+
+#![warn(unused)]
+
+#[allow(unused_variables, unused_mut)] // only `unused_variables` is used
+fn partially_used() {
+    let x = 0;
+}
+
+#[allow(non_camel_case_types)] // not used
+struct Foo;
+
+#[allow(warnings)] // used, through the lint group
+fn through_warnings() {
+    let y = 0;
+}
+
+#[allow(while_true)] // used
+fn early_used() {
+    while true {
+        break;
+    }
+}
+
+#[allow(unused_parens)] // not used
+fn early_unused() {}
+
+fn main() {
+    partially_used();
+    let _ = Foo;
+    through_warnings();
+    early_used();
+    early_unused();
+}
@@ -0,0 +1,55 @@
+//@ check-pass
+//@ run-rustfix
+
+#![feature(lint_reasons)]
+
+// Unfulfilled expectations come with suggestions to remove them from the attribute, which are
+// only shown in the JSON output.
+
+#[expect(unused_variables)]
+//~^ WARNING this lint expectation is unfulfilled [unfulfilled_lint_expectations]
+//~| NOTE `#[warn(unfulfilled_lint_expectations)]` on by default
+fn whole_attribute() {}
+
+#[expect(unused_mut, while_true, reason = "no longer needed")]
+//~^ WARNING this lint expectation is unfulfilled [unfulfilled_lint_expectations]
+//~| NOTE no longer needed
+//~| WARNING this lint expectation is unfulfilled [unfulfilled_lint_expectations]
+//~| NOTE no longer needed
+fn whole_attribute_with_reason() {}
+
+#[expect(unused_mut, unused_variables)]
+//~^ WARNING this lint expectation is unfulfilled [unfulfilled_lint_expectations]
+fn leading_lint() {
+    let x = 0;
+}
+
+#[expect(unused_variables, unused_mut, while_true)]
+//~^ WARNING this lint expectation is unfulfilled [unfulfilled_lint_expectations]
+//~| WARNING this lint expectation is unfulfilled [unfulfilled_lint_expectations]
+fn trailing_lints() {
+    let x = 0;
+}
+
+#[expect(unused_variables, unused_mut, reason = "still needed for `x`")]
+//~^ WARNING this lint expectation is unfulfilled [unfulfilled_lint_expectations]
+//~| NOTE still needed for `x`
+fn trailing_lint_with_reason() {
+    let x = 0;
+}
+
+#[expect(unused_mut, unused_variables, while_true)]
+//~^ WARNING this lint expectation is unfulfilled [unfulfilled_lint_expectations]
+//~| WARNING this lint expectation is unfulfilled [unfulfilled_lint_expectations]
+fn lints_on_both_sides() {
+    let x = 0;
+}
+
+fn main() {
+    whole_attribute();
+    whole_attribute_with_reason();
+    leading_lint();
+    trailing_lints();
+    trailing_lint_with_reason();
+    lints_on_both_sides();
+}
@@ -0,0 +1,15 @@
+//@ no-prefer-dynamic
+//@ compile-flags: -Cmetadata=baseline
+
+#![crate_name = "unchanged_diagnostics"]
+#![crate_type = "lib"]
+
+pub mod ops {
+    pub trait Double {
+        fn double(&self) {}
+    }
+
+    impl Double for u32 {}
+}
+
+pub fn double(_: u32) {}
@@ -0,0 +1,24 @@
+// ignore-tidy-linelength
+//@ aux-build:unchanged-diagnostics-baseline.rs
+//@ compile-flags: --crate-type=lib -Zunstable-options --check-semver-against={{build-base}}/lint/semver/unchanged-diagnostics/auxiliary/libunchanged_diagnostics.rlib
+// The path of the baseline depends on the compare mode.
+//@ ignore-compare-mode-polonius
+//@ ignore-compare-mode-next-solver
+//@ ignore-compare-mode-next-solver-coherence
+//@ ignore-compare-mode-split-dwarf
+//@ ignore-compare-mode-split-dwarf-single
+
+// Checks that `--check-semver-against` does not change the diagnostics of the crate: the baseline
+// is not a dependency, so its items and impls are not suggested.
+
+pub mod ops {
+    pub trait Double {
+        fn double(&self) {}
+    }
+
+    impl Double for u32 {}
+}
+
+pub fn double(x: u32) {
+    x.double(); //~ ERROR no method named `double` found for type `u32` in the current scope
+}
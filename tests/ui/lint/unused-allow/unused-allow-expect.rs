@@ -0,0 +1,23 @@
+//@ check-pass
+
+// The `unused_allow` lint can be expected like other lints.
+
+#![feature(lint_reasons)]
+#![warn(unused_allow)]
+
+#[expect(unused_allow)]
+#[allow(unused_variables)]
+fn fulfilled() {}
+
+#[expect(unused_allow)]
+//~^ WARNING this lint expectation is unfulfilled
+//~| NOTE `#[warn(unfulfilled_lint_expectations)]` on by default
+#[allow(unused_variables)]
+fn unfulfilled() {
+    let x = 0;
+}
+
+fn main() {
+    fulfilled();
+    unfulfilled();
+}
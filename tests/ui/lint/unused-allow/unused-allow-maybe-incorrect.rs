@@ -0,0 +1,22 @@
+//@ check-pass
+//@ run-rustfix
+//@ rustfix-only-machine-applicable
+
+// Unlike those for unfulfilled expectations, the suggestions to remove unused allows are not
+// machine-applicable, as some lints are not checked at all when they are allowed.
+
+#![feature(lint_reasons)]
+#![warn(unused_allow)]
+
+#[allow(unused_variables)]
+//~^ WARNING this `#[allow]` never suppressed a lint
+fn unused_allow() {}
+
+#[expect(unused_variables)]
+//~^ WARNING this lint expectation is unfulfilled
+fn unfulfilled_expectation() {}
+
+fn main() {
+    unused_allow();
+    unfulfilled_expectation();
+}
@@ -0,0 +1,18 @@
+//@ check-pass
+
+// `unused_allow` also takes effect when it is only enabled for a module.
+
+#[allow(unused_variables)] // not checked
+fn outside() {}
+
+#[warn(unused_allow)]
+mod checked {
+    #[allow(unused_variables)]
+    //~^ WARNING this `#[allow]` never suppressed a lint
+    pub fn unused() {}
+}
+
+fn main() {
+    outside();
+    checked::unused();
+}
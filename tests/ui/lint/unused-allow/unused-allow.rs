@@ -0,0 +1,50 @@
+//@ check-pass
+//@ run-rustfix
+
+#![warn(unused_allow)]
+#![warn(unused)]
+
+#[allow(unused_variables)]
+//~^ WARNING this `#[allow]` never suppressed a lint
+fn unused() {}
+
+#[allow(unused_variables)]
+fn used() {
+    let x = 0;
+}
+
+#[allow(unused_mut, unused_variables)]
+//~^ WARNING this `#[allow]` never suppressed a lint
+fn leading_lint() {
+    let x = 0;
+}
+
+#[allow(unused_variables, unused_mut, while_true)]
+//~^ WARNING this `#[allow]` never suppressed a lint
+//~| WARNING this `#[allow]` never suppressed a lint
+fn trailing_lints() {
+    let x = 0;
+}
+
+// The dead code lint is still emitted, and suppressed, for items that are allowed to be dead.
+#[allow(dead_code)]
+fn dead() {}
+
+#[allow(while_true)]
+fn early_lint() {
+    while true {
+        break;
+    }
+}
+
+#[allow(unused_allow, unused_parens)]
+fn not_reported() {}
+
+fn main() {
+    unused();
+    used();
+    leading_lint();
+    trailing_lints();
+    early_lint();
+    not_reported();
+}